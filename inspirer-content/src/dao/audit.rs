@@ -0,0 +1,90 @@
+use chrono::Utc;
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder, Set,
+};
+use uuid::Uuid;
+
+use crate::{
+    entity::{audit_logs, users},
+    error::InspirerContentResult,
+    model::{
+        audit::{AuditLogCondition, NewAuditLog},
+        paginate::{Paginated, Pagination},
+    },
+};
+
+#[async_trait::async_trait]
+pub trait AuditLogDao {
+    async fn create_audit_log(&self, id: Uuid, log: NewAuditLog) -> InspirerContentResult<()>;
+    /// 按时间倒序查询审计日志及执行操作的用户
+    async fn get_audit_logs(
+        &self,
+        condition: AuditLogCondition,
+        pagination: Pagination,
+    ) -> InspirerContentResult<Paginated<(audit_logs::Model, Option<users::Model>)>>;
+}
+
+#[async_trait::async_trait]
+impl<T: ConnectionTrait> AuditLogDao for T {
+    async fn create_audit_log(&self, id: Uuid, log: NewAuditLog) -> InspirerContentResult<()> {
+        let model = audit_logs::ActiveModel {
+            id: Set(id),
+            actor_id: Set(log.actor_id),
+            access_token_id: Set(log.access_token_id),
+            action: Set(log.action),
+            target_type: Set(log.target_type),
+            target_id: Set(log.target_id),
+            detail: Set(log.detail),
+            ip: Set(log.ip),
+            user_agent: Set(log.user_agent),
+            created_at: Set(Utc::now()),
+        };
+
+        audit_logs::Entity::insert(model).exec(self).await?;
+
+        Ok(())
+    }
+
+    async fn get_audit_logs(
+        &self,
+        condition: AuditLogCondition,
+        pagination: Pagination,
+    ) -> InspirerContentResult<Paginated<(audit_logs::Model, Option<users::Model>)>> {
+        let mut selector = audit_logs::Entity::find().find_also_related(users::Entity);
+
+        if let Some(actor_id) = condition.actor_id {
+            selector = selector.filter(audit_logs::Column::ActorId.eq(actor_id));
+        }
+
+        if let Some(action) = condition.action {
+            selector = selector.filter(audit_logs::Column::Action.eq(action));
+        }
+
+        if let Some(target_id) = condition.target_id {
+            selector = selector.filter(audit_logs::Column::TargetId.eq(target_id));
+        }
+
+        if let Some(since) = condition.since {
+            selector = selector.filter(audit_logs::Column::CreatedAt.gte(since));
+        }
+
+        if let Some(until) = condition.until {
+            selector = selector.filter(audit_logs::Column::CreatedAt.lt(until));
+        }
+
+        let paginator = selector
+            .order_by_desc(audit_logs::Column::CreatedAt)
+            .order_by_desc(audit_logs::Column::Id)
+            .paginate(self, pagination.page_size);
+
+        let data = paginator.fetch_page(pagination.page - 1).await?;
+
+        Ok(Paginated {
+            data,
+            page: pagination.page,
+            page_size: pagination.page_size,
+            total: paginator.num_items().await?,
+            last_page: paginator.num_pages().await?,
+        })
+    }
+}
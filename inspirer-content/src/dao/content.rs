@@ -1,12 +1,15 @@
+use chrono::{DateTime, Utc};
 use sea_orm::{
-    sea_query::Expr, ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, PaginatorTrait,
-    QueryFilter, QueryOrder, Set,
+    sea_query::{Expr, Query},
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DeriveColumn, EntityTrait, EnumIter,
+    IdenStatic, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, Set,
 };
 use uuid::Uuid;
 
 use crate::{
     entity::content_entities,
     entity::users,
+    entity::{categories, content_categories, content_tags, tags},
     entity::{content_update_logs, contents},
     enumerate::content::ContentType,
     error::{Error, InspirerContentResult},
@@ -16,6 +19,11 @@ use crate::{
     },
 };
 
+#[derive(Copy, Clone, Debug, EnumIter, DeriveColumn)]
+enum IdColumn {
+    Id,
+}
+
 #[async_trait::async_trait]
 pub trait ContentDao {
     async fn create_content(
@@ -38,6 +46,12 @@ pub trait ContentDao {
         &self,
         id: Uuid,
     ) -> InspirerContentResult<Option<(contents::Model, Option<content_entities::Model>)>>;
+    async fn get_contents_by_ids(
+        &self,
+        ids: Vec<Uuid>,
+    ) -> InspirerContentResult<Vec<(contents::Model, Option<users::Model>)>>;
+    /// 获取全部未删除内容的 ID
+    async fn get_content_ids(&self) -> InspirerContentResult<Vec<Uuid>>;
     async fn find_content_by_name(
         &self,
         name: String,
@@ -56,8 +70,30 @@ pub trait ContentDao {
     async fn delete_content_entity(&self, id: Uuid) -> InspirerContentResult<()>;
     async fn force_delete_content(&self, id: Uuid) -> InspirerContentResult<()>;
     async fn revert_deleted_content(&self, id: Uuid) -> InspirerContentResult<()>;
-    async fn publish_content(&self, id: Uuid) -> InspirerContentResult<()>;
+    async fn publish_content(
+        &self,
+        id: Uuid,
+        published_at: DateTime<Utc>,
+    ) -> InspirerContentResult<()>;
+    /// 预定发布未发布的内容，内容不存在或已发布时返回 `false`
+    async fn schedule_content(
+        &self,
+        id: Uuid,
+        publish_at: DateTime<Utc>,
+    ) -> InspirerContentResult<bool>;
+    /// 发布所有预定发布时间不晚于 `now` 的内容，返回发布的数量及查询到的待发布内容 ID
+    ///
+    /// 待发布内容在查询后可能被取消发布或重新预定，这些内容不会被发布，但仍会出现在返回的 ID 中。
+    async fn publish_scheduled_contents(
+        &self,
+        now: DateTime<Utc>,
+    ) -> InspirerContentResult<(u64, Vec<Uuid>)>;
     async fn unpublish_content(&self, id: Uuid) -> InspirerContentResult<()>;
+    async fn set_content_display(&self, id: Uuid, display: bool) -> InspirerContentResult<()>;
+    /// 统计用户拥有的内容数量，包括已删除的内容
+    async fn count_owner_contents(&self, owner_id: Uuid) -> InspirerContentResult<usize>;
+    /// 将用户的全部内容转移给另一用户，返回转移的数量
+    async fn reassign_contents(&self, from: Uuid, to: Uuid) -> InspirerContentResult<u64>;
 }
 
 #[async_trait::async_trait]
@@ -122,7 +158,49 @@ impl<T: ConnectionTrait> ContentDao for T {
             selector = selector.filter(contents::Column::ContentType.ne(ContentType::Page));
         }
 
-        if condition.sort.len() > 0 {
+        if let Some(owner_id) = condition.owner_id {
+            selector = selector.filter(contents::Column::OwnerId.eq(owner_id));
+        }
+
+        if let Some(tag) = condition.tag {
+            selector = selector.filter(
+                contents::Column::Id.in_subquery(
+                    Query::select()
+                        .column(content_tags::Column::ContentId)
+                        .from(content_tags::Entity)
+                        .inner_join(
+                            tags::Entity,
+                            Expr::tbl(tags::Entity, tags::Column::Id)
+                                .equals(content_tags::Entity, content_tags::Column::TagId),
+                        )
+                        .and_where(Expr::tbl(tags::Entity, tags::Column::Name).eq(tag))
+                        .to_owned(),
+                ),
+            );
+        }
+
+        if let Some(category) = condition.category {
+            selector = selector.filter(
+                contents::Column::Id.in_subquery(
+                    Query::select()
+                        .column(content_categories::Column::ContentId)
+                        .from(content_categories::Entity)
+                        .inner_join(
+                            categories::Entity,
+                            Expr::tbl(categories::Entity, categories::Column::Id).equals(
+                                content_categories::Entity,
+                                content_categories::Column::CategoryId,
+                            ),
+                        )
+                        .and_where(
+                            Expr::tbl(categories::Entity, categories::Column::Name).eq(category),
+                        )
+                        .to_owned(),
+                ),
+            );
+        }
+
+        if !condition.sort.is_empty() {
             for sort in condition.sort.iter() {
                 selector = selector.order_by(
                     Into::<contents::Column>::into(sort.inner()),
@@ -155,6 +233,33 @@ impl<T: ConnectionTrait> ContentDao for T {
             .map_err(Into::into)
     }
 
+    async fn get_contents_by_ids(
+        &self,
+        ids: Vec<Uuid>,
+    ) -> InspirerContentResult<Vec<(contents::Model, Option<users::Model>)>> {
+        if ids.is_empty() {
+            return Ok(vec![]);
+        }
+
+        contents::Entity::find()
+            .find_also_related(users::Entity)
+            .filter(contents::Column::Id.is_in(ids))
+            .all(self)
+            .await
+            .map_err(Into::into)
+    }
+
+    async fn get_content_ids(&self) -> InspirerContentResult<Vec<Uuid>> {
+        contents::Entity::find()
+            .select_only()
+            .column_as(contents::Column::Id, IdColumn::Id)
+            .filter(contents::Column::IsDeleted.eq(false))
+            .into_values::<_, IdColumn>()
+            .all(self)
+            .await
+            .map_err(Into::into)
+    }
+
     async fn find_content_by_name(
         &self,
         name: String,
@@ -176,6 +281,8 @@ impl<T: ConnectionTrait> ContentDao for T {
             && update_content.meta.description.is_none()
             && update_content.meta.keywords.is_none()
             && update_content.meta.name.is_none()
+            && update_content.meta.tags.is_none()
+            && update_content.meta.categories.is_none()
             && update_content.entity.is_none()
         {
             return Ok(());
@@ -201,7 +308,7 @@ impl<T: ConnectionTrait> ContentDao for T {
         }
 
         if let Some(name) = &update_content.meta.name {
-            active_model.content_name = Set(Some(name.clone()));
+            active_model.content_name = Set(name.clone());
         }
 
         if let Some(entity) = &update_content.entity {
@@ -279,13 +386,18 @@ impl<T: ConnectionTrait> ContentDao for T {
         Ok(())
     }
 
-    async fn publish_content(&self, id: Uuid) -> InspirerContentResult<()> {
+    async fn publish_content(
+        &self,
+        id: Uuid,
+        published_at: DateTime<Utc>,
+    ) -> InspirerContentResult<()> {
         contents::Entity::update_many()
             .filter(contents::Column::Id.eq(id))
             .col_expr(contents::Column::IsPublish, Expr::value(true))
+            .col_expr(contents::Column::PublishedAt, Expr::value(published_at))
             .col_expr(
-                contents::Column::PublishedAt,
-                Expr::value(chrono::Utc::now()),
+                contents::Column::ScheduledAt,
+                Expr::value(Option::<DateTime<Utc>>::None),
             )
             .exec(self)
             .await?;
@@ -293,15 +405,102 @@ impl<T: ConnectionTrait> ContentDao for T {
         Ok(())
     }
 
+    async fn schedule_content(
+        &self,
+        id: Uuid,
+        publish_at: DateTime<Utc>,
+    ) -> InspirerContentResult<bool> {
+        let res = contents::Entity::update_many()
+            .filter(contents::Column::Id.eq(id))
+            .filter(contents::Column::IsPublish.eq(false))
+            .col_expr(contents::Column::ScheduledAt, Expr::value(publish_at))
+            .exec(self)
+            .await?;
+
+        Ok(res.rows_affected > 0)
+    }
+
+    async fn publish_scheduled_contents(
+        &self,
+        now: DateTime<Utc>,
+    ) -> InspirerContentResult<(u64, Vec<Uuid>)> {
+        let ids = contents::Entity::find()
+            .select_only()
+            .column_as(contents::Column::Id, IdColumn::Id)
+            .filter(contents::Column::IsPublish.eq(false))
+            .filter(contents::Column::IsDeleted.eq(false))
+            .filter(contents::Column::ScheduledAt.lte(now))
+            .into_values::<_, IdColumn>()
+            .all(self)
+            .await?;
+
+        if ids.is_empty() {
+            return Ok((0, ids));
+        }
+
+        // 更新时再次校验发布状态，避免覆盖查询后发生的取消发布或重新预定
+        let res = contents::Entity::update_many()
+            .filter(contents::Column::Id.is_in(ids.clone()))
+            .filter(contents::Column::IsPublish.eq(false))
+            .filter(contents::Column::IsDeleted.eq(false))
+            .filter(contents::Column::ScheduledAt.is_not_null())
+            .filter(contents::Column::ScheduledAt.lte(now))
+            .col_expr(contents::Column::IsPublish, Expr::value(true))
+            .col_expr(
+                contents::Column::PublishedAt,
+                Expr::col(contents::Column::ScheduledAt).into(),
+            )
+            .col_expr(
+                contents::Column::ScheduledAt,
+                Expr::value(Option::<DateTime<Utc>>::None),
+            )
+            .exec(self)
+            .await?;
+
+        Ok((res.rows_affected, ids))
+    }
+
     async fn unpublish_content(&self, id: Uuid) -> InspirerContentResult<()> {
         contents::Entity::update_many()
             .filter(contents::Column::Id.eq(id))
             .col_expr(contents::Column::IsPublish, Expr::value(false))
+            .col_expr(
+                contents::Column::ScheduledAt,
+                Expr::value(Option::<DateTime<Utc>>::None),
+            )
             .exec(self)
             .await?;
 
         Ok(())
     }
+
+    async fn set_content_display(&self, id: Uuid, display: bool) -> InspirerContentResult<()> {
+        contents::Entity::update_many()
+            .filter(contents::Column::Id.eq(id))
+            .col_expr(contents::Column::IsDisplay, Expr::value(display))
+            .exec(self)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn count_owner_contents(&self, owner_id: Uuid) -> InspirerContentResult<usize> {
+        contents::Entity::find()
+            .filter(contents::Column::OwnerId.eq(owner_id))
+            .count(self)
+            .await
+            .map_err(Into::into)
+    }
+
+    async fn reassign_contents(&self, from: Uuid, to: Uuid) -> InspirerContentResult<u64> {
+        let res = contents::Entity::update_many()
+            .filter(contents::Column::OwnerId.eq(from))
+            .col_expr(contents::Column::OwnerId, Expr::value(to))
+            .exec(self)
+            .await?;
+
+        Ok(res.rows_affected)
+    }
 }
 
 #[async_trait::async_trait]
@@ -313,6 +512,10 @@ pub trait ContentUpdateLogDao {
         content_id: Uuid,
         update_content: UpdateContent,
     ) -> InspirerContentResult<()>;
+    async fn get_content_update_logs(
+        &self,
+        content_id: Uuid,
+    ) -> InspirerContentResult<Vec<(content_update_logs::Model, Option<users::Model>)>>;
 }
 
 #[async_trait::async_trait]
@@ -340,4 +543,17 @@ impl<T: ConnectionTrait> ContentUpdateLogDao for T {
 
         Ok(())
     }
+
+    async fn get_content_update_logs(
+        &self,
+        content_id: Uuid,
+    ) -> InspirerContentResult<Vec<(content_update_logs::Model, Option<users::Model>)>> {
+        content_update_logs::Entity::find()
+            .find_also_related(users::Entity)
+            .filter(content_update_logs::Column::ContentId.eq(content_id))
+            .order_by_asc(content_update_logs::Column::CreatedAt)
+            .all(self)
+            .await
+            .map_err(Into::into)
+    }
 }
@@ -1,2 +1,8 @@
+pub mod audit;
 pub mod content;
+pub mod session;
+pub mod signature;
+pub mod taxonomy;
+pub mod throttle;
+pub mod token;
 pub mod user;
\ No newline at end of file
@@ -0,0 +1,226 @@
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    sea_query::Expr, ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, QueryOrder, Set,
+};
+use uuid::Uuid;
+
+use crate::{entity::user_sessions, error::InspirerContentResult};
+
+#[async_trait::async_trait]
+pub trait SessionDao {
+    async fn create_session(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+        access_token_id: Uuid,
+        refresh_token_hash: Vec<u8>,
+        user_agent: String,
+        expires_at: DateTime<Utc>,
+    ) -> InspirerContentResult<()>;
+    async fn find_session_by_id(
+        &self,
+        id: Uuid,
+    ) -> InspirerContentResult<Option<user_sessions::Model>>;
+    async fn find_session_by_access_token(
+        &self,
+        access_token_id: Uuid,
+    ) -> InspirerContentResult<Option<user_sessions::Model>>;
+    async fn find_session_by_refresh_token(
+        &self,
+        refresh_token_hash: Vec<u8>,
+    ) -> InspirerContentResult<Option<user_sessions::Model>>;
+    /// 查找刷新令牌已被轮换掉的会话
+    async fn find_session_by_previous_refresh_token(
+        &self,
+        refresh_token_hash: Vec<u8>,
+    ) -> InspirerContentResult<Option<user_sessions::Model>>;
+    /// 轮换会话令牌，仅当当前刷新令牌仍为 `refresh_token_hash` 时成功，返回是否轮换成功
+    async fn rotate_session(
+        &self,
+        id: Uuid,
+        refresh_token_hash: Vec<u8>,
+        new_access_token_id: Uuid,
+        new_refresh_token_hash: Vec<u8>,
+        expires_at: DateTime<Utc>,
+    ) -> InspirerContentResult<bool>;
+    /// 获取用户未被吊销的会话（含已过期会话）
+    async fn get_user_sessions(
+        &self,
+        user_id: Uuid,
+    ) -> InspirerContentResult<Vec<user_sessions::Model>>;
+    async fn revoke_session(&self, id: Uuid) -> InspirerContentResult<()>;
+    async fn revoke_user_sessions(&self, user_id: Uuid) -> InspirerContentResult<()>;
+    /// 吊销用户除 `except` 外的全部会话
+    async fn revoke_other_user_sessions(
+        &self,
+        user_id: Uuid,
+        except: Uuid,
+    ) -> InspirerContentResult<()>;
+    async fn delete_user_sessions(&self, user_id: Uuid) -> InspirerContentResult<()>;
+}
+
+#[async_trait::async_trait]
+impl<T: ConnectionTrait> SessionDao for T {
+    async fn create_session(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+        access_token_id: Uuid,
+        refresh_token_hash: Vec<u8>,
+        user_agent: String,
+        expires_at: DateTime<Utc>,
+    ) -> InspirerContentResult<()> {
+        let now = Utc::now();
+        let model = user_sessions::ActiveModel {
+            id: Set(id),
+            user_id: Set(user_id),
+            access_token_id: Set(access_token_id),
+            refresh_token_hash: Set(refresh_token_hash),
+            previous_refresh_token_hash: Set(None),
+            user_agent: Set(user_agent),
+            created_at: Set(now),
+            refreshed_at: Set(now),
+            expires_at: Set(expires_at),
+            revoked_at: Set(None),
+        };
+
+        user_sessions::Entity::insert(model).exec(self).await?;
+
+        Ok(())
+    }
+
+    async fn find_session_by_id(
+        &self,
+        id: Uuid,
+    ) -> InspirerContentResult<Option<user_sessions::Model>> {
+        user_sessions::Entity::find_by_id(id)
+            .one(self)
+            .await
+            .map_err(Into::into)
+    }
+
+    async fn find_session_by_access_token(
+        &self,
+        access_token_id: Uuid,
+    ) -> InspirerContentResult<Option<user_sessions::Model>> {
+        user_sessions::Entity::find()
+            .filter(user_sessions::Column::AccessTokenId.eq(access_token_id))
+            .one(self)
+            .await
+            .map_err(Into::into)
+    }
+
+    async fn find_session_by_refresh_token(
+        &self,
+        refresh_token_hash: Vec<u8>,
+    ) -> InspirerContentResult<Option<user_sessions::Model>> {
+        user_sessions::Entity::find()
+            .filter(user_sessions::Column::RefreshTokenHash.eq(refresh_token_hash))
+            .one(self)
+            .await
+            .map_err(Into::into)
+    }
+
+    async fn find_session_by_previous_refresh_token(
+        &self,
+        refresh_token_hash: Vec<u8>,
+    ) -> InspirerContentResult<Option<user_sessions::Model>> {
+        user_sessions::Entity::find()
+            .filter(user_sessions::Column::PreviousRefreshTokenHash.eq(refresh_token_hash))
+            .one(self)
+            .await
+            .map_err(Into::into)
+    }
+
+    async fn rotate_session(
+        &self,
+        id: Uuid,
+        refresh_token_hash: Vec<u8>,
+        new_access_token_id: Uuid,
+        new_refresh_token_hash: Vec<u8>,
+        expires_at: DateTime<Utc>,
+    ) -> InspirerContentResult<bool> {
+        let res = user_sessions::Entity::update_many()
+            .filter(user_sessions::Column::Id.eq(id))
+            .filter(user_sessions::Column::RefreshTokenHash.eq(refresh_token_hash.clone()))
+            .filter(user_sessions::Column::RevokedAt.is_null())
+            .col_expr(
+                user_sessions::Column::AccessTokenId,
+                Expr::value(new_access_token_id),
+            )
+            .col_expr(
+                user_sessions::Column::RefreshTokenHash,
+                Expr::value(new_refresh_token_hash),
+            )
+            .col_expr(
+                user_sessions::Column::PreviousRefreshTokenHash,
+                Expr::value(refresh_token_hash),
+            )
+            .col_expr(user_sessions::Column::RefreshedAt, Expr::value(Utc::now()))
+            .col_expr(user_sessions::Column::ExpiresAt, Expr::value(expires_at))
+            .exec(self)
+            .await?;
+
+        Ok(res.rows_affected == 1)
+    }
+
+    async fn get_user_sessions(
+        &self,
+        user_id: Uuid,
+    ) -> InspirerContentResult<Vec<user_sessions::Model>> {
+        user_sessions::Entity::find()
+            .filter(user_sessions::Column::UserId.eq(user_id))
+            .filter(user_sessions::Column::RevokedAt.is_null())
+            .order_by_desc(user_sessions::Column::RefreshedAt)
+            .all(self)
+            .await
+            .map_err(Into::into)
+    }
+
+    async fn revoke_session(&self, id: Uuid) -> InspirerContentResult<()> {
+        user_sessions::Entity::update_many()
+            .filter(user_sessions::Column::Id.eq(id))
+            .filter(user_sessions::Column::RevokedAt.is_null())
+            .col_expr(user_sessions::Column::RevokedAt, Expr::value(Utc::now()))
+            .exec(self)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn revoke_user_sessions(&self, user_id: Uuid) -> InspirerContentResult<()> {
+        user_sessions::Entity::update_many()
+            .filter(user_sessions::Column::UserId.eq(user_id))
+            .filter(user_sessions::Column::RevokedAt.is_null())
+            .col_expr(user_sessions::Column::RevokedAt, Expr::value(Utc::now()))
+            .exec(self)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn revoke_other_user_sessions(
+        &self,
+        user_id: Uuid,
+        except: Uuid,
+    ) -> InspirerContentResult<()> {
+        user_sessions::Entity::update_many()
+            .filter(user_sessions::Column::UserId.eq(user_id))
+            .filter(user_sessions::Column::Id.ne(except))
+            .filter(user_sessions::Column::RevokedAt.is_null())
+            .col_expr(user_sessions::Column::RevokedAt, Expr::value(Utc::now()))
+            .exec(self)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn delete_user_sessions(&self, user_id: Uuid) -> InspirerContentResult<()> {
+        user_sessions::Entity::delete_many()
+            .filter(user_sessions::Column::UserId.eq(user_id))
+            .exec(self)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,130 @@
+use chrono::{DateTime, Utc};
+use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, Set};
+use uuid::Uuid;
+
+use crate::{
+    entity::{login_challenges, request_nonces},
+    error::InspirerContentResult,
+};
+
+#[async_trait::async_trait]
+pub trait NonceDao {
+    /// 记录已使用的随机数，重复记录时返回 `DatabaseWriteConflict`
+    async fn create_nonce(
+        &self,
+        user_id: Uuid,
+        nonce: String,
+        expires_at: DateTime<Utc>,
+    ) -> InspirerContentResult<()>;
+    async fn delete_expired_nonces(&self, now: DateTime<Utc>) -> InspirerContentResult<()>;
+    async fn delete_user_nonces(&self, user_id: Uuid) -> InspirerContentResult<()>;
+}
+
+#[async_trait::async_trait]
+impl<T: ConnectionTrait> NonceDao for T {
+    async fn create_nonce(
+        &self,
+        user_id: Uuid,
+        nonce: String,
+        expires_at: DateTime<Utc>,
+    ) -> InspirerContentResult<()> {
+        let model = request_nonces::ActiveModel {
+            user_id: Set(user_id),
+            nonce: Set(nonce),
+            expires_at: Set(expires_at),
+        };
+
+        request_nonces::Entity::insert(model).exec(self).await?;
+
+        Ok(())
+    }
+
+    async fn delete_expired_nonces(&self, now: DateTime<Utc>) -> InspirerContentResult<()> {
+        request_nonces::Entity::delete_many()
+            .filter(request_nonces::Column::ExpiresAt.lt(now))
+            .exec(self)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn delete_user_nonces(&self, user_id: Uuid) -> InspirerContentResult<()> {
+        request_nonces::Entity::delete_many()
+            .filter(request_nonces::Column::UserId.eq(user_id))
+            .exec(self)
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+pub trait LoginChallengeDao {
+    async fn create_login_challenge(
+        &self,
+        challenge: String,
+        fingerprint: Vec<u8>,
+        expires_at: DateTime<Utc>,
+    ) -> InspirerContentResult<()>;
+    /// 取出并删除登录挑战，每个挑战只能被使用一次
+    async fn take_login_challenge(
+        &self,
+        challenge: String,
+    ) -> InspirerContentResult<Option<login_challenges::Model>>;
+    async fn delete_expired_login_challenges(
+        &self,
+        now: DateTime<Utc>,
+    ) -> InspirerContentResult<()>;
+}
+
+#[async_trait::async_trait]
+impl<T: ConnectionTrait> LoginChallengeDao for T {
+    async fn create_login_challenge(
+        &self,
+        challenge: String,
+        fingerprint: Vec<u8>,
+        expires_at: DateTime<Utc>,
+    ) -> InspirerContentResult<()> {
+        let model = login_challenges::ActiveModel {
+            challenge: Set(challenge),
+            public_key_fingerprint: Set(fingerprint),
+            expires_at: Set(expires_at),
+        };
+
+        login_challenges::Entity::insert(model).exec(self).await?;
+
+        Ok(())
+    }
+
+    async fn take_login_challenge(
+        &self,
+        challenge: String,
+    ) -> InspirerContentResult<Option<login_challenges::Model>> {
+        let model = match login_challenges::Entity::find_by_id(challenge.clone())
+            .one(self)
+            .await?
+        {
+            Some(model) => model,
+            None => return Ok(None),
+        };
+
+        // 并发使用同一挑战时只有一个请求能删除成功
+        let res = login_challenges::Entity::delete_by_id(challenge)
+            .exec(self)
+            .await?;
+
+        Ok((res.rows_affected == 1).then_some(model))
+    }
+
+    async fn delete_expired_login_challenges(
+        &self,
+        now: DateTime<Utc>,
+    ) -> InspirerContentResult<()> {
+        login_challenges::Entity::delete_many()
+            .filter(login_challenges::Column::ExpiresAt.lt(now))
+            .exec(self)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,304 @@
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, ModelTrait, QueryFilter,
+    QueryOrder, Set,
+};
+use uuid::Uuid;
+
+use crate::{
+    entity::{categories, content_categories, content_tags, contents, tags},
+    error::{Error, InspirerContentResult},
+    model::taxonomy::{NewCategory, UpdateCategory},
+    util::uuid::generate_v1_uuid,
+};
+
+#[async_trait::async_trait]
+pub trait TagDao {
+    async fn get_tags(&self) -> InspirerContentResult<Vec<tags::Model>>;
+    async fn find_tag_by_name(&self, name: String) -> InspirerContentResult<Option<tags::Model>>;
+    async fn create_tag(&self, id: Uuid, name: String) -> InspirerContentResult<()>;
+    async fn delete_tag(&self, id: Uuid) -> InspirerContentResult<()>;
+    async fn get_content_tags(&self, content_id: Uuid) -> InspirerContentResult<Vec<tags::Model>>;
+    /// 替换内容的全部标签，`create_missing` 为 `true` 时自动创建不存在的标签
+    async fn set_content_tags(
+        &self,
+        content_id: Uuid,
+        names: &[String],
+        create_missing: bool,
+    ) -> InspirerContentResult<()>;
+    async fn delete_content_tags(&self, content_id: Uuid) -> InspirerContentResult<()>;
+}
+
+#[async_trait::async_trait]
+impl<T: ConnectionTrait> TagDao for T {
+    async fn get_tags(&self) -> InspirerContentResult<Vec<tags::Model>> {
+        tags::Entity::find()
+            .order_by_asc(tags::Column::Name)
+            .all(self)
+            .await
+            .map_err(Into::into)
+    }
+
+    async fn find_tag_by_name(&self, name: String) -> InspirerContentResult<Option<tags::Model>> {
+        tags::Entity::find()
+            .filter(tags::Column::Name.eq(name))
+            .one(self)
+            .await
+            .map_err(Into::into)
+    }
+
+    async fn create_tag(&self, id: Uuid, name: String) -> InspirerContentResult<()> {
+        let model = tags::ActiveModel {
+            id: Set(id),
+            name: Set(name),
+            ..Default::default()
+        };
+
+        tags::Entity::insert(model).exec(self).await?;
+
+        Ok(())
+    }
+
+    async fn delete_tag(&self, id: Uuid) -> InspirerContentResult<()> {
+        content_tags::Entity::delete_many()
+            .filter(content_tags::Column::TagId.eq(id))
+            .exec(self)
+            .await?;
+        tags::Entity::delete_by_id(id).exec(self).await?;
+
+        Ok(())
+    }
+
+    async fn get_content_tags(&self, content_id: Uuid) -> InspirerContentResult<Vec<tags::Model>> {
+        let content = contents::Entity::find_by_id(content_id)
+            .one(self)
+            .await?
+            .ok_or(Error::ContentNotFound)?;
+
+        content
+            .find_related(tags::Entity)
+            .order_by_asc(tags::Column::Name)
+            .all(self)
+            .await
+            .map_err(Into::into)
+    }
+
+    async fn set_content_tags(
+        &self,
+        content_id: Uuid,
+        names: &[String],
+        create_missing: bool,
+    ) -> InspirerContentResult<()> {
+        self.delete_content_tags(content_id).await?;
+
+        if names.is_empty() {
+            return Ok(());
+        }
+
+        let mut ids = Vec::with_capacity(names.len());
+        for name in names {
+            let id = match self.find_tag_by_name(name.clone()).await? {
+                Some(tag) => tag.id,
+                None if !create_missing => return Err(Error::CreateTermForbidden(name.clone())),
+                None => {
+                    let id = generate_v1_uuid();
+                    self.create_tag(id, name.clone()).await?;
+                    id
+                }
+            };
+            ids.push(id);
+        }
+
+        content_tags::Entity::insert_many(ids.into_iter().map(|tag_id| {
+            content_tags::ActiveModel {
+                content_id: Set(content_id),
+                tag_id: Set(tag_id),
+            }
+        }))
+        .exec(self)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn delete_content_tags(&self, content_id: Uuid) -> InspirerContentResult<()> {
+        content_tags::Entity::delete_many()
+            .filter(content_tags::Column::ContentId.eq(content_id))
+            .exec(self)
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+pub trait CategoryDao {
+    async fn get_categories(&self) -> InspirerContentResult<Vec<categories::Model>>;
+    async fn find_category_by_name(
+        &self,
+        name: String,
+    ) -> InspirerContentResult<Option<categories::Model>>;
+    async fn create_category(
+        &self,
+        id: Uuid,
+        new_category: &NewCategory,
+    ) -> InspirerContentResult<()>;
+    async fn update_category(
+        &self,
+        id: Uuid,
+        update_category: &UpdateCategory,
+    ) -> InspirerContentResult<()>;
+    async fn delete_category(&self, id: Uuid) -> InspirerContentResult<()>;
+    async fn get_content_categories(
+        &self,
+        content_id: Uuid,
+    ) -> InspirerContentResult<Vec<categories::Model>>;
+    /// 替换内容的全部分类，`create_missing` 为 `true` 时自动创建不存在的分类
+    async fn set_content_categories(
+        &self,
+        content_id: Uuid,
+        names: &[String],
+        create_missing: bool,
+    ) -> InspirerContentResult<()>;
+    async fn delete_content_categories(&self, content_id: Uuid) -> InspirerContentResult<()>;
+}
+
+#[async_trait::async_trait]
+impl<T: ConnectionTrait> CategoryDao for T {
+    async fn get_categories(&self) -> InspirerContentResult<Vec<categories::Model>> {
+        categories::Entity::find()
+            .order_by_asc(categories::Column::Name)
+            .all(self)
+            .await
+            .map_err(Into::into)
+    }
+
+    async fn find_category_by_name(
+        &self,
+        name: String,
+    ) -> InspirerContentResult<Option<categories::Model>> {
+        categories::Entity::find()
+            .filter(categories::Column::Name.eq(name))
+            .one(self)
+            .await
+            .map_err(Into::into)
+    }
+
+    async fn create_category(
+        &self,
+        id: Uuid,
+        new_category: &NewCategory,
+    ) -> InspirerContentResult<()> {
+        let model = categories::ActiveModel {
+            id: Set(id),
+            name: Set(new_category.name.clone()),
+            description: Set(new_category.description.clone()),
+            ..Default::default()
+        };
+
+        categories::Entity::insert(model).exec(self).await?;
+
+        Ok(())
+    }
+
+    async fn update_category(
+        &self,
+        id: Uuid,
+        update_category: &UpdateCategory,
+    ) -> InspirerContentResult<()> {
+        let model = categories::Entity::find_by_id(id)
+            .one(self)
+            .await?
+            .ok_or(Error::CategoryNotFound)?;
+        let mut active_model: categories::ActiveModel = model.into();
+
+        if let Some(name) = &update_category.name {
+            active_model.name = Set(name.clone());
+        }
+
+        if let Some(description) = &update_category.description {
+            active_model.description = Set(description.clone());
+        }
+
+        active_model.update(self).await?;
+
+        Ok(())
+    }
+
+    async fn delete_category(&self, id: Uuid) -> InspirerContentResult<()> {
+        content_categories::Entity::delete_many()
+            .filter(content_categories::Column::CategoryId.eq(id))
+            .exec(self)
+            .await?;
+        categories::Entity::delete_by_id(id).exec(self).await?;
+
+        Ok(())
+    }
+
+    async fn get_content_categories(
+        &self,
+        content_id: Uuid,
+    ) -> InspirerContentResult<Vec<categories::Model>> {
+        let content = contents::Entity::find_by_id(content_id)
+            .one(self)
+            .await?
+            .ok_or(Error::ContentNotFound)?;
+
+        content
+            .find_related(categories::Entity)
+            .order_by_asc(categories::Column::Name)
+            .all(self)
+            .await
+            .map_err(Into::into)
+    }
+
+    async fn set_content_categories(
+        &self,
+        content_id: Uuid,
+        names: &[String],
+        create_missing: bool,
+    ) -> InspirerContentResult<()> {
+        self.delete_content_categories(content_id).await?;
+
+        if names.is_empty() {
+            return Ok(());
+        }
+
+        let mut ids = Vec::with_capacity(names.len());
+        for name in names {
+            let id = match self.find_category_by_name(name.clone()).await? {
+                Some(category) => category.id,
+                None if !create_missing => return Err(Error::CreateTermForbidden(name.clone())),
+                None => {
+                    let id = generate_v1_uuid();
+                    let new_category = NewCategory {
+                        name: name.clone(),
+                        ..Default::default()
+                    };
+                    self.create_category(id, &new_category).await?;
+                    id
+                }
+            };
+            ids.push(id);
+        }
+
+        content_categories::Entity::insert_many(ids.into_iter().map(|category_id| {
+            content_categories::ActiveModel {
+                content_id: Set(content_id),
+                category_id: Set(category_id),
+            }
+        }))
+        .exec(self)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn delete_content_categories(&self, content_id: Uuid) -> InspirerContentResult<()> {
+        content_categories::Entity::delete_many()
+            .filter(content_categories::Column::ContentId.eq(content_id))
+            .exec(self)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,107 @@
+use chrono::{DateTime, Duration, Utc};
+use sea_orm::{
+    sea_query::{Expr, OnConflict},
+    ColumnTrait, Condition, ConnectionTrait, DbErr, EntityTrait, QueryFilter, QueryTrait, Set,
+};
+
+use crate::{entity::login_attempts, error::InspirerContentResult, throttle::AttemptRecord};
+
+#[async_trait::async_trait]
+pub trait LoginAttemptDao {
+    async fn find_login_attempt(&self, key: String)
+        -> InspirerContentResult<Option<AttemptRecord>>;
+    /// 原子地记录一次失败，返回新的记录
+    ///
+    /// 上次失败超过 `lockout` 且未锁定的记录重新计数，失败次数达到 `max_failures` 时锁定 `lockout`。
+    async fn increase_login_failures(
+        &self,
+        key: String,
+        now: DateTime<Utc>,
+        lockout: Duration,
+        max_failures: u32,
+    ) -> InspirerContentResult<AttemptRecord>;
+    /// 删除失败记录，返回记录是否存在
+    async fn delete_login_attempt(&self, key: String) -> InspirerContentResult<bool>;
+}
+
+#[async_trait::async_trait]
+impl<T: ConnectionTrait> LoginAttemptDao for T {
+    async fn find_login_attempt(
+        &self,
+        key: String,
+    ) -> InspirerContentResult<Option<AttemptRecord>> {
+        let model = login_attempts::Entity::find_by_id(key).one(self).await?;
+
+        Ok(model.map(|model| AttemptRecord {
+            failures: model.failures,
+            last_failure_at: model.last_failure_at,
+            locked_until: model.locked_until,
+        }))
+    }
+
+    async fn increase_login_failures(
+        &self,
+        key: String,
+        now: DateTime<Utc>,
+        lockout: Duration,
+        max_failures: u32,
+    ) -> InspirerContentResult<AttemptRecord> {
+        use login_attempts::Column;
+
+        let stale = Condition::all()
+            .add(Column::LastFailureAt.lte(now - lockout))
+            .add(
+                Condition::any()
+                    .add(Column::LockedUntil.is_null())
+                    .add(Column::LockedUntil.lte(now)),
+            );
+
+        let model = login_attempts::ActiveModel {
+            attempt_key: Set(key.clone()),
+            failures: Set(1),
+            last_failure_at: Set(now),
+            locked_until: Set((max_failures <= 1).then(|| now + lockout)),
+        };
+
+        // MySQL 按顺序求值赋值语句，`last_failure_at` 必须最后更新
+        let on_conflict = OnConflict::column(Column::AttemptKey)
+            .update_exprs([
+                (
+                    Column::Failures,
+                    Expr::case(stale.clone(), Expr::val(1))
+                        .finally(Expr::expr(Expr::col(Column::Failures).add(1)))
+                        .into(),
+                ),
+                (
+                    Column::LockedUntil,
+                    Expr::case(stale, Expr::val(Option::<DateTime<Utc>>::None))
+                        .finally(Expr::col(Column::LockedUntil))
+                        .into(),
+                ),
+                (Column::LastFailureAt, Expr::value(now)),
+            ])
+            .to_owned();
+
+        let stmt = login_attempts::Entity::insert(model)
+            .on_conflict(on_conflict)
+            .build(self.get_database_backend());
+        self.execute(stmt).await?;
+
+        login_attempts::Entity::update_many()
+            .col_expr(Column::LockedUntil, Expr::value(now + lockout))
+            .filter(Column::AttemptKey.eq(key.clone()))
+            .filter(Column::Failures.gte(max_failures))
+            .exec(self)
+            .await?;
+
+        self.find_login_attempt(key.clone())
+            .await?
+            .ok_or_else(|| DbErr::RecordNotFound(key).into())
+    }
+
+    async fn delete_login_attempt(&self, key: String) -> InspirerContentResult<bool> {
+        let res = login_attempts::Entity::delete_by_id(key).exec(self).await?;
+
+        Ok(res.rows_affected > 0)
+    }
+}
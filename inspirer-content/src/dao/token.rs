@@ -0,0 +1,140 @@
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    sea_query::Expr, ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, QueryOrder, Set,
+};
+use uuid::Uuid;
+
+use crate::{entity::personal_access_tokens, error::InspirerContentResult};
+
+#[async_trait::async_trait]
+pub trait AccessTokenDao {
+    async fn create_access_token(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+        name: String,
+        token_hash: Vec<u8>,
+        scopes: serde_json::Value,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> InspirerContentResult<()>;
+    async fn find_access_token_by_id(
+        &self,
+        id: Uuid,
+    ) -> InspirerContentResult<Option<personal_access_tokens::Model>>;
+    async fn find_access_token_by_hash(
+        &self,
+        token_hash: Vec<u8>,
+    ) -> InspirerContentResult<Option<personal_access_tokens::Model>>;
+    /// 获取用户的全部访问令牌（含已过期令牌）
+    async fn get_user_access_tokens(
+        &self,
+        user_id: Uuid,
+    ) -> InspirerContentResult<Vec<personal_access_tokens::Model>>;
+    async fn update_access_token_last_used(
+        &self,
+        id: Uuid,
+        last_used_at: DateTime<Utc>,
+    ) -> InspirerContentResult<()>;
+    /// 删除属于 `user_id` 的访问令牌，返回是否删除成功
+    async fn delete_access_token(&self, user_id: Uuid, id: Uuid) -> InspirerContentResult<bool>;
+    async fn delete_user_access_tokens(&self, user_id: Uuid) -> InspirerContentResult<()>;
+}
+
+#[async_trait::async_trait]
+impl<T: ConnectionTrait> AccessTokenDao for T {
+    async fn create_access_token(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+        name: String,
+        token_hash: Vec<u8>,
+        scopes: serde_json::Value,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> InspirerContentResult<()> {
+        let model = personal_access_tokens::ActiveModel {
+            id: Set(id),
+            user_id: Set(user_id),
+            name: Set(name),
+            token_hash: Set(token_hash),
+            scopes: Set(scopes),
+            expires_at: Set(expires_at),
+            last_used_at: Set(None),
+            created_at: Set(Utc::now()),
+        };
+
+        personal_access_tokens::Entity::insert(model)
+            .exec(self)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn find_access_token_by_id(
+        &self,
+        id: Uuid,
+    ) -> InspirerContentResult<Option<personal_access_tokens::Model>> {
+        personal_access_tokens::Entity::find_by_id(id)
+            .one(self)
+            .await
+            .map_err(Into::into)
+    }
+
+    async fn find_access_token_by_hash(
+        &self,
+        token_hash: Vec<u8>,
+    ) -> InspirerContentResult<Option<personal_access_tokens::Model>> {
+        personal_access_tokens::Entity::find()
+            .filter(personal_access_tokens::Column::TokenHash.eq(token_hash))
+            .one(self)
+            .await
+            .map_err(Into::into)
+    }
+
+    async fn get_user_access_tokens(
+        &self,
+        user_id: Uuid,
+    ) -> InspirerContentResult<Vec<personal_access_tokens::Model>> {
+        personal_access_tokens::Entity::find()
+            .filter(personal_access_tokens::Column::UserId.eq(user_id))
+            .order_by_desc(personal_access_tokens::Column::CreatedAt)
+            .all(self)
+            .await
+            .map_err(Into::into)
+    }
+
+    async fn update_access_token_last_used(
+        &self,
+        id: Uuid,
+        last_used_at: DateTime<Utc>,
+    ) -> InspirerContentResult<()> {
+        personal_access_tokens::Entity::update_many()
+            .filter(personal_access_tokens::Column::Id.eq(id))
+            .col_expr(
+                personal_access_tokens::Column::LastUsedAt,
+                Expr::value(last_used_at),
+            )
+            .exec(self)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn delete_access_token(&self, user_id: Uuid, id: Uuid) -> InspirerContentResult<bool> {
+        let res = personal_access_tokens::Entity::delete_many()
+            .filter(personal_access_tokens::Column::Id.eq(id))
+            .filter(personal_access_tokens::Column::UserId.eq(user_id))
+            .exec(self)
+            .await?;
+
+        Ok(res.rows_affected == 1)
+    }
+
+    async fn delete_user_access_tokens(&self, user_id: Uuid) -> InspirerContentResult<()> {
+        personal_access_tokens::Entity::delete_many()
+            .filter(personal_access_tokens::Column::UserId.eq(user_id))
+            .exec(self)
+            .await?;
+
+        Ok(())
+    }
+}
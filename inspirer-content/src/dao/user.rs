@@ -1,7 +1,15 @@
-use sea_orm::{ConnectionTrait, EntityTrait, QueryFilter, Set, ColumnTrait};
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    sea_query::Expr, ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait, DbBackend,
+    EntityTrait, JsonValue, QueryFilter, QueryOrder, Set,
+};
 use uuid::Uuid;
 
-use crate::{entity::users, error::InspirerContentResult, model::user::NewUser};
+use crate::{
+    entity::users,
+    error::{Error, InspirerContentResult},
+    model::user::{NewUser, UpdateUser},
+};
 
 #[async_trait::async_trait]
 pub trait UserDao {
@@ -13,12 +21,63 @@ pub trait UserDao {
         public_key_fingerprint: Vec<u8>,
     ) -> InspirerContentResult<Uuid>;
 
+    async fn get_users(&self) -> InspirerContentResult<Vec<users::Model>>;
     async fn get_user_by_id(&self, id: Uuid) -> InspirerContentResult<Option<users::Model>>;
     async fn get_user_by_public_key(
         &self,
         fingerprint: Vec<u8>,
     ) -> InspirerContentResult<Option<users::Model>>;
-    async fn get_user_by_username(&self, username: String) -> InspirerContentResult<Option<users::Model>>;
+    /// 获取轮换前使用该公钥的用户，不检查宽限期
+    async fn get_users_by_previous_public_key(
+        &self,
+        fingerprint: Vec<u8>,
+    ) -> InspirerContentResult<Vec<users::Model>>;
+    async fn get_user_by_username(
+        &self,
+        username: String,
+    ) -> InspirerContentResult<Option<users::Model>>;
+    /// 替换用户公钥，原公钥在 `previous_key_expires_at` 前仍然有效
+    async fn update_user_public_key(
+        &self,
+        id: Uuid,
+        public_key: Vec<u8>,
+        public_key_fingerprint: Vec<u8>,
+        previous_key_expires_at: Option<DateTime<Utc>>,
+    ) -> InspirerContentResult<users::Model>;
+    async fn update_user(
+        &self,
+        id: Uuid,
+        update_user: &UpdateUser,
+    ) -> InspirerContentResult<users::Model>;
+    async fn update_user_password(&self, id: Uuid, password: String) -> InspirerContentResult<()>;
+    /// 设置用户的禁用时间，为空时启用用户
+    async fn update_user_disabled_at(
+        &self,
+        id: Uuid,
+        disabled_at: Option<DateTime<Utc>>,
+    ) -> InspirerContentResult<()>;
+    /// 保存两步验证密钥（已加密）及恢复码摘要，开启状态被清除；均为空时关闭两步验证
+    async fn update_user_totp(
+        &self,
+        id: Uuid,
+        secret: Option<Vec<u8>>,
+        recovery_codes: Option<Vec<String>>,
+    ) -> InspirerContentResult<()>;
+    async fn enable_user_totp(
+        &self,
+        id: Uuid,
+        enabled_at: DateTime<Utc>,
+    ) -> InspirerContentResult<()>;
+    /// 记录最近使用的验证码时间步，时间步不晚于已记录的值时不更新并返回 `false`
+    async fn update_user_totp_last_step(&self, id: Uuid, step: i64) -> InspirerContentResult<bool>;
+    /// 恢复码仍为 `previous` 时更新，否则不更新并返回 `false`
+    async fn update_user_recovery_codes(
+        &self,
+        id: Uuid,
+        previous: JsonValue,
+        recovery_codes: Vec<String>,
+    ) -> InspirerContentResult<bool>;
+    async fn delete_user(&self, id: Uuid) -> InspirerContentResult<()>;
 }
 
 #[async_trait::async_trait]
@@ -36,6 +95,7 @@ impl<T: ConnectionTrait> UserDao for T {
             password: Set(new_user.password.clone()),
             nickname: Set(new_user.nickname.clone()),
             avatar: Set(new_user.avatar.clone()),
+            role: Set(new_user.role),
             public_key: Set(public_key),
             public_key_fingerprint: Set(public_key_fingerprint),
             ..Default::default()
@@ -46,6 +106,14 @@ impl<T: ConnectionTrait> UserDao for T {
         Ok(id)
     }
 
+    async fn get_users(&self) -> InspirerContentResult<Vec<users::Model>> {
+        users::Entity::find()
+            .order_by_asc(users::Column::CreatedAt)
+            .all(self)
+            .await
+            .map_err(Into::into)
+    }
+
     async fn get_user_by_id(&self, id: Uuid) -> InspirerContentResult<Option<users::Model>> {
         users::Entity::find_by_id(id)
             .one(self)
@@ -64,11 +132,185 @@ impl<T: ConnectionTrait> UserDao for T {
             .map_err(Into::into)
     }
 
-    async fn get_user_by_username(&self, username: String) -> InspirerContentResult<Option<users::Model>> {
+    async fn get_users_by_previous_public_key(
+        &self,
+        fingerprint: Vec<u8>,
+    ) -> InspirerContentResult<Vec<users::Model>> {
+        users::Entity::find()
+            .filter(users::Column::PreviousPublicKeyFingerprint.eq(fingerprint))
+            .all(self)
+            .await
+            .map_err(Into::into)
+    }
+
+    async fn get_user_by_username(
+        &self,
+        username: String,
+    ) -> InspirerContentResult<Option<users::Model>> {
         users::Entity::find()
             .filter(users::Column::Username.eq(username))
             .one(self)
             .await
             .map_err(Into::into)
     }
+
+    async fn update_user_public_key(
+        &self,
+        id: Uuid,
+        public_key: Vec<u8>,
+        public_key_fingerprint: Vec<u8>,
+        previous_key_expires_at: Option<DateTime<Utc>>,
+    ) -> InspirerContentResult<users::Model> {
+        let model = find_user(self, id).await?;
+
+        // 没有宽限期时不保留原公钥
+        let keep_previous = previous_key_expires_at.is_some();
+        let mut active_model = users::ActiveModel {
+            previous_public_key: Set(keep_previous.then(|| model.public_key.clone())),
+            previous_public_key_fingerprint: Set(
+                keep_previous.then(|| model.public_key_fingerprint.clone())
+            ),
+            previous_key_expires_at: Set(previous_key_expires_at),
+            ..model.into()
+        };
+        active_model.public_key = Set(public_key);
+        active_model.public_key_fingerprint = Set(public_key_fingerprint);
+
+        active_model.update(self).await.map_err(Into::into)
+    }
+
+    async fn update_user(
+        &self,
+        id: Uuid,
+        update_user: &UpdateUser,
+    ) -> InspirerContentResult<users::Model> {
+        let model = find_user(self, id).await?;
+        let mut active_model: users::ActiveModel = model.into();
+
+        if let Some(nickname) = &update_user.nickname {
+            active_model.nickname = Set(nickname.clone());
+        }
+
+        if let Some(avatar) = &update_user.avatar {
+            active_model.avatar = Set(avatar.clone());
+        }
+
+        if let Some(profile) = &update_user.profile {
+            active_model.user_profile =
+                Set(serde_json::to_value(profile).map_err(Error::FormatError)?);
+        }
+
+        active_model.update(self).await.map_err(Into::into)
+    }
+
+    async fn update_user_password(&self, id: Uuid, password: String) -> InspirerContentResult<()> {
+        let mut active_model: users::ActiveModel = find_user(self, id).await?.into();
+        active_model.password = Set(password);
+        active_model.update(self).await?;
+
+        Ok(())
+    }
+
+    async fn update_user_disabled_at(
+        &self,
+        id: Uuid,
+        disabled_at: Option<DateTime<Utc>>,
+    ) -> InspirerContentResult<()> {
+        let mut active_model: users::ActiveModel = find_user(self, id).await?.into();
+        active_model.disabled_at = Set(disabled_at);
+        active_model.update(self).await?;
+
+        Ok(())
+    }
+
+    async fn update_user_totp(
+        &self,
+        id: Uuid,
+        secret: Option<Vec<u8>>,
+        recovery_codes: Option<Vec<String>>,
+    ) -> InspirerContentResult<()> {
+        let mut active_model: users::ActiveModel = find_user(self, id).await?.into();
+        active_model.totp_secret = Set(secret);
+        active_model.totp_enabled_at = Set(None);
+        active_model.totp_last_step = Set(None);
+        active_model.totp_recovery_codes = Set(recovery_codes
+            .map(|codes| serde_json::to_value(codes).map_err(Error::FormatError))
+            .transpose()?);
+        active_model.update(self).await?;
+
+        Ok(())
+    }
+
+    async fn enable_user_totp(
+        &self,
+        id: Uuid,
+        enabled_at: DateTime<Utc>,
+    ) -> InspirerContentResult<()> {
+        let mut active_model: users::ActiveModel = find_user(self, id).await?.into();
+        active_model.totp_enabled_at = Set(Some(enabled_at));
+        active_model.update(self).await?;
+
+        Ok(())
+    }
+
+    async fn update_user_totp_last_step(&self, id: Uuid, step: i64) -> InspirerContentResult<bool> {
+        // 以条件更新防止同一验证码被并发使用
+        let res = users::Entity::update_many()
+            .col_expr(users::Column::TotpLastStep, Expr::value(step))
+            .filter(users::Column::Id.eq(id))
+            .filter(
+                Condition::any()
+                    .add(users::Column::TotpLastStep.is_null())
+                    .add(users::Column::TotpLastStep.lt(step)),
+            )
+            .exec(self)
+            .await?;
+
+        Ok(res.rows_affected == 1)
+    }
+
+    async fn update_user_recovery_codes(
+        &self,
+        id: Uuid,
+        previous: JsonValue,
+        recovery_codes: Vec<String>,
+    ) -> InspirerContentResult<bool> {
+        // 以条件更新防止同一恢复码被并发使用，MySQL 的 JSON 列需与 JSON 值比较
+        let unchanged = match self.get_database_backend() {
+            DbBackend::MySql => Expr::cust_with_values(
+                "`totp_recovery_codes` = CAST(? AS JSON)",
+                [previous.to_string()],
+            ),
+            _ => users::Column::TotpRecoveryCodes.eq(previous),
+        };
+
+        let res = users::Entity::update_many()
+            .col_expr(
+                users::Column::TotpRecoveryCodes,
+                Expr::value(serde_json::to_value(recovery_codes).map_err(Error::FormatError)?),
+            )
+            .filter(users::Column::Id.eq(id))
+            .filter(unchanged)
+            .exec(self)
+            .await?;
+
+        Ok(res.rows_affected == 1)
+    }
+
+    async fn delete_user(&self, id: Uuid) -> InspirerContentResult<()> {
+        let res = users::Entity::delete_by_id(id).exec(self).await?;
+
+        if res.rows_affected == 0 {
+            return Err(Error::UserNotFound);
+        }
+
+        Ok(())
+    }
+}
+
+async fn find_user<T: ConnectionTrait>(db: &T, id: Uuid) -> InspirerContentResult<users::Model> {
+    users::Entity::find_by_id(id)
+        .one(db)
+        .await?
+        .ok_or(Error::UserNotFound)
 }
@@ -25,6 +25,7 @@ pub struct Model {
     pub is_display: bool,
     pub is_deleted: bool,
     pub published_at: Option<DateTimeUtc>,
+    pub scheduled_at: Option<DateTimeUtc>,
     pub deleted_at: Option<DateTimeUtc>,
 }
 
@@ -48,4 +49,24 @@ impl Related<super::users::Entity> for Entity {
     }
 }
 
+impl Related<super::tags::Entity> for Entity {
+    fn to() -> RelationDef {
+        super::content_tags::Relation::Tag.def()
+    }
+
+    fn via() -> Option<RelationDef> {
+        Some(super::content_tags::Relation::Content.def().rev())
+    }
+}
+
+impl Related<super::categories::Entity> for Entity {
+    fn to() -> RelationDef {
+        super::content_categories::Relation::Category.def()
+    }
+
+    fn via() -> Option<RelationDef> {
+        Some(super::content_categories::Relation::Content.def().rev())
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
@@ -1,8 +1,19 @@
 //! SeaORM Entity. Generated by sea-orm-codegen 0.8.0
 
+#[allow(unused_imports)]
 pub mod prelude;
 
+pub mod audit_logs;
+pub mod categories;
+pub mod content_categories;
+pub mod content_tags;
 pub mod content_update_logs;
 pub mod contents;
 pub mod content_entities;
+pub mod login_attempts;
+pub mod login_challenges;
+pub mod personal_access_tokens;
+pub mod request_nonces;
+pub mod tags;
+pub mod user_sessions;
 pub mod users;
\ No newline at end of file
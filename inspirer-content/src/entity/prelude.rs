@@ -1,6 +1,16 @@
 //! SeaORM Entity. Generated by sea-orm-codegen 0.8.0
 
+pub use super::audit_logs::Entity as AuditLogs;
+pub use super::categories::Entity as Categories;
+pub use super::content_categories::Entity as ContentCategories;
+pub use super::content_tags::Entity as ContentTags;
 pub use super::content_update_logs::Entity as ContentUpdateLogs;
 pub use super::content_entities::Entity as ContentEntities;
 pub use super::contents::Entity as Contents;
+pub use super::login_attempts::Entity as LoginAttempts;
+pub use super::login_challenges::Entity as LoginChallenges;
+pub use super::personal_access_tokens::Entity as PersonalAccessTokens;
+pub use super::request_nonces::Entity as RequestNonces;
+pub use super::tags::Entity as Tags;
+pub use super::user_sessions::Entity as UserSessions;
 pub use super::users::Entity as Users;
@@ -3,6 +3,8 @@
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 
+use crate::enumerate::user::UserRole;
+
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
 #[sea_orm(table_name = "users")]
 pub struct Model {
@@ -13,11 +15,22 @@ pub struct Model {
     pub password: String,
     pub nickname: String,
     pub avatar: String,
+    pub role: UserRole,
+    pub disabled_at: Option<DateTimeUtc>,
     pub user_profile: Json,
     #[sea_orm(column_type = "Custom(\"VARBINARY(500)\".to_owned())")]
     pub public_key: Vec<u8>,
     #[sea_orm(unique)]
     pub public_key_fingerprint: Vec<u8>,
+    #[sea_orm(column_type = "Custom(\"VARBINARY(500)\".to_owned())", nullable)]
+    pub previous_public_key: Option<Vec<u8>>,
+    pub previous_public_key_fingerprint: Option<Vec<u8>>,
+    pub previous_key_expires_at: Option<DateTimeUtc>,
+    #[sea_orm(column_type = "Custom(\"VARBINARY(255)\".to_owned())", nullable)]
+    pub totp_secret: Option<Vec<u8>>,
+    pub totp_enabled_at: Option<DateTimeUtc>,
+    pub totp_last_step: Option<i64>,
+    pub totp_recovery_codes: Option<Json>,
     pub created_at: DateTimeUtc,
     pub updated_at: DateTimeUtc,
 }
@@ -34,3 +34,216 @@ pub mod content {
         }
     }
 }
+
+pub mod user {
+    use sea_orm::{DeriveActiveEnum, EnumIter};
+    use serde::{Deserialize, Serialize};
+    use strum::{Display, EnumString, EnumVariantNames};
+
+    /// 用户角色，权限由高到低
+    #[derive(
+        Debug,
+        Clone,
+        Copy,
+        PartialEq,
+        Eq,
+        Default,
+        EnumIter,
+        DeriveActiveEnum,
+        Serialize,
+        Deserialize,
+        Display,
+        EnumString,
+        EnumVariantNames,
+    )]
+    #[sea_orm(rs_type = "u32", db_type = "Unsigned")]
+    #[serde(rename_all = "kebab-case")]
+    #[strum(serialize_all = "kebab-case")]
+    #[repr(u32)]
+    pub enum UserRole {
+        /// 管理员，拥有全部权限
+        #[sea_orm(num_value = 1)]
+        Admin = 1,
+        /// 编辑，可管理所有人的内容及分类标签
+        #[sea_orm(num_value = 2)]
+        Editor = 2,
+        /// 作者，可发布与管理自己的内容
+        #[default]
+        #[sea_orm(num_value = 3)]
+        Author = 3,
+        /// 投稿者，可撰写自己的内容但不能发布
+        #[sea_orm(num_value = 4)]
+        Contributor = 4,
+    }
+}
+
+pub mod token {
+    use serde::{Deserialize, Serialize};
+    use strum::{Display, EnumString, EnumVariantNames};
+
+    /// 个人访问令牌的权限范围
+    #[derive(
+        Debug,
+        Clone,
+        Copy,
+        PartialEq,
+        Eq,
+        Hash,
+        PartialOrd,
+        Ord,
+        Serialize,
+        Deserialize,
+        Display,
+        EnumString,
+        EnumVariantNames,
+    )]
+    pub enum TokenScope {
+        /// 查看内容
+        #[serde(rename = "content:read")]
+        #[strum(serialize = "content:read")]
+        ContentRead,
+        /// 创建及修改内容
+        #[serde(rename = "content:write")]
+        #[strum(serialize = "content:write")]
+        ContentWrite,
+        /// 发布及取消发布内容
+        #[serde(rename = "content:publish")]
+        #[strum(serialize = "content:publish")]
+        ContentPublish,
+        /// 删除及恢复内容
+        #[serde(rename = "content:delete")]
+        #[strum(serialize = "content:delete")]
+        ContentDelete,
+    }
+}
+
+pub mod audit {
+    use sea_orm::{DeriveActiveEnum, EnumIter};
+    use serde::{Deserialize, Serialize};
+    use strum::{Display, EnumString, EnumVariantNames};
+
+    /// 审计日志记录的操作
+    #[derive(
+        Debug,
+        Clone,
+        Copy,
+        PartialEq,
+        Eq,
+        EnumIter,
+        DeriveActiveEnum,
+        Serialize,
+        Deserialize,
+        Display,
+        EnumString,
+        EnumVariantNames,
+    )]
+    #[sea_orm(rs_type = "String", db_type = "String(Some(50))")]
+    #[serde(rename_all = "kebab-case")]
+    #[strum(serialize_all = "kebab-case")]
+    pub enum AuditAction {
+        /// 登录成功
+        #[sea_orm(string_value = "login")]
+        Login,
+        /// 登录失败
+        #[sea_orm(string_value = "login-failed")]
+        LoginFailed,
+        /// 使用刷新令牌换取新的访问令牌
+        #[sea_orm(string_value = "token-refreshed")]
+        TokenRefreshed,
+        /// 注销当前会话
+        #[sea_orm(string_value = "logout")]
+        Logout,
+        /// 吊销登录会话
+        #[sea_orm(string_value = "session-revoked")]
+        SessionRevoked,
+        /// 创建个人访问令牌
+        #[sea_orm(string_value = "access-token-created")]
+        AccessTokenCreated,
+        /// 吊销个人访问令牌
+        #[sea_orm(string_value = "access-token-revoked")]
+        AccessTokenRevoked,
+        /// 发布内容
+        #[sea_orm(string_value = "content-published")]
+        ContentPublished,
+        /// 取消发布内容
+        #[sea_orm(string_value = "content-unpublished")]
+        ContentUnpublished,
+        /// 隐藏内容
+        #[sea_orm(string_value = "content-hidden")]
+        ContentHidden,
+        /// 取消隐藏内容
+        #[sea_orm(string_value = "content-shown")]
+        ContentShown,
+        /// 删除内容（可恢复）
+        #[sea_orm(string_value = "content-deleted")]
+        ContentDeleted,
+        /// 彻底删除内容
+        #[sea_orm(string_value = "content-force-deleted")]
+        ContentForceDeleted,
+        /// 恢复已删除的内容
+        #[sea_orm(string_value = "content-reverted")]
+        ContentReverted,
+        /// 恢复内容的修订版本
+        #[sea_orm(string_value = "content-revision-restored")]
+        ContentRevisionRestored,
+        /// 创建用户
+        #[sea_orm(string_value = "user-created")]
+        UserCreated,
+        /// 更新用户资料
+        #[sea_orm(string_value = "user-updated")]
+        UserUpdated,
+        /// 修改或重置密码
+        #[sea_orm(string_value = "user-password-changed")]
+        UserPasswordChanged,
+        /// 轮换密钥或登记公钥
+        #[sea_orm(string_value = "user-key-changed")]
+        UserKeyChanged,
+        /// 开启两步验证
+        #[sea_orm(string_value = "user-two-factor-enabled")]
+        UserTwoFactorEnabled,
+        /// 关闭或重置两步验证
+        #[sea_orm(string_value = "user-two-factor-disabled")]
+        UserTwoFactorDisabled,
+        /// 禁用用户
+        #[sea_orm(string_value = "user-disabled")]
+        UserDisabled,
+        /// 启用用户
+        #[sea_orm(string_value = "user-enabled")]
+        UserEnabled,
+        /// 删除用户
+        #[sea_orm(string_value = "user-deleted")]
+        UserDeleted,
+        /// 解除登录锁定
+        #[sea_orm(string_value = "user-unlocked")]
+        UserUnlocked,
+    }
+
+    /// 审计日志的操作对象类型
+    #[derive(
+        Debug,
+        Clone,
+        Copy,
+        PartialEq,
+        Eq,
+        EnumIter,
+        DeriveActiveEnum,
+        Serialize,
+        Deserialize,
+        Display,
+        EnumString,
+        EnumVariantNames,
+    )]
+    #[sea_orm(rs_type = "String", db_type = "String(Some(20))")]
+    #[serde(rename_all = "kebab-case")]
+    #[strum(serialize_all = "kebab-case")]
+    pub enum AuditTargetType {
+        #[sea_orm(string_value = "user")]
+        User,
+        #[sea_orm(string_value = "content")]
+        Content,
+        #[sea_orm(string_value = "session")]
+        Session,
+        #[sea_orm(string_value = "access-token")]
+        AccessToken,
+    }
+}
@@ -12,6 +12,18 @@ pub enum Error {
     FormatError(serde_json::Error),
     #[error("内容未找到")]
     ContentNotFound,
+    #[error("内容已发布，不能预定发布")]
+    ContentAlreadyPublished,
+    #[error("修订记录未找到")]
+    ContentRevisionNotFound,
+    #[error("标签未找到")]
+    TagNotFound,
+    #[error("分类未找到")]
+    CategoryNotFound,
+    #[error("标签或分类名称不能为空")]
+    EmptyTermName,
+    #[error("没有权限创建标签或分类：{0}")]
+    CreateTermForbidden(String),
     #[error("创建内容序列失败")]
     GenerateIdError(#[from] uuid::Error),
     #[error("ID格式非法")]
@@ -22,14 +34,66 @@ pub enum Error {
     RingKeyPairFormatError,
     #[error(transparent)]
     PasswordHashError(#[from] argon2::password_hash::Error),
+    #[error("用户不存在")]
+    UserNotFound,
+    #[error("账号已被禁用")]
+    UserDisabled,
+    #[error("用户仍拥有内容，请指定内容的新所有者")]
+    UserHasContents,
+    #[error("无法将内容转移给该用户")]
+    InvalidReassignTarget,
+    #[error("{0}")]
+    InvalidPassword(String),
+    #[error("{0}")]
+    InvalidProfile(String),
+    #[error("当前密码错误")]
+    IncorrectPassword,
+    #[error("两步验证码错误")]
+    InvalidTwoFactorCode,
+    #[error("已开启两步验证")]
+    TwoFactorAlreadyEnabled,
+    #[error("未开启两步验证")]
+    TwoFactorNotEnabled,
+    #[error("解密数据失败，请检查加密密钥配置")]
+    DecryptSecretError,
+    #[error("未配置加密密钥")]
+    SecretKeyNotConfigured,
+    #[error("登录尝试次数过多，请在 {retry_after} 秒后重试")]
+    TooManyLoginAttempts { retry_after: u64 },
     #[error("用户不存在或密码错误")]
     UserNotFoundOrPasswordError,
+    #[error("登录已失效，请重新登录")]
+    InvalidSession,
+    #[error("会话不存在")]
+    SessionNotFound,
+    #[error("访问令牌无效或已过期")]
+    InvalidAccessToken,
+    #[error("访问令牌不存在")]
+    AccessTokenNotFound,
+    #[error("{0}")]
+    InvalidAccessTokenOptions(String),
+    #[error("请求签名无效")]
+    InvalidSignature,
+    #[error("请求签名已过期，请检查客户端时间")]
+    SignatureExpired,
+    #[error("请求已被处理，请勿重复提交")]
+    ReplayedRequest,
+    #[error("公钥格式错误，仅支持 PEM 或 OpenSSH 格式的 Ed25519 公钥")]
+    InvalidPublicKey,
+    #[error("该账号已禁用密码登录，请使用密钥登录")]
+    PasswordLoginDisabled,
+    #[error("数据库迁移失败：{0}")]
+    MigrateError(#[from] sqlx::migrate::MigrateError),
+    #[error("搜索索引错误：{0}")]
+    SearchError(#[from] tantivy::TantivyError),
+    #[error("不支持的数据库类型")]
+    UnsupportedDatabaseBackend,
 }
 
 impl From<DbErr> for Error {
     fn from(err: DbErr) -> Self {
         match err {
-            DbErr::Exec(err) if err.contains("1062") && err.contains("23000") => {
+            DbErr::Exec(ref msg) | DbErr::Query(ref msg) if is_unique_violation(msg) => {
                 Error::DatabaseWriteConflict
             }
             _ => Error::DatabaseError(err),
@@ -37,6 +101,19 @@ impl From<DbErr> for Error {
     }
 }
 
+/// 判断数据库错误信息是否为唯一约束冲突
+///
+/// SeaORM 仅以文本形式透出底层驱动错误，这里按各后端的错误码/错误信息进行匹配：
+///
+/// - MySQL: `1062 (23000): Duplicate entry ...`
+/// - SQLite: `UNIQUE constraint failed: ...`（包括主键冲突）
+/// - PostgreSQL: `duplicate key value violates unique constraint ...`（SQLSTATE 23505）
+fn is_unique_violation(msg: &str) -> bool {
+    (msg.contains("1062") && msg.contains("23000"))
+        || msg.contains("UNIQUE constraint failed")
+        || msg.contains("duplicate key value violates unique constraint")
+}
+
 impl<E> From<TransactionError<E>> for Error
 where
     E: Into<Error> + std::error::Error,
@@ -1,8 +1,12 @@
 mod entity;
 pub mod manager;
+pub mod migrate;
 pub mod error;
 mod dao;
 pub mod model;
+pub mod render;
+pub mod search;
+pub mod throttle;
 pub mod service;
 pub mod enumerate;
 pub mod util;
@@ -1,25 +1,109 @@
+use std::path::PathBuf;
+
 use derive_builder::Builder;
-use sea_orm::{Database, DatabaseConnection};
+use sea_orm::{ConnectionTrait, DatabaseConnection};
 
-use crate::error::InspirerContentResult;
+use crate::{
+    error::{Error, InspirerContentResult},
+    migrate::DatabasePool,
+    model::user::PasswordPolicy,
+    render::RenderCache,
+    search::SearchIndex,
+    throttle::{LoginThrottlePolicy, MemoryAttemptStore},
+    util::cipher::SecretCipher,
+};
 
 #[derive(Clone)]
 pub struct Manager {
     pub(crate) database: DatabaseConnection,
+    pub(crate) pool: DatabasePool,
+    pub(crate) render_cache: RenderCache,
+    pub(crate) search_index: SearchIndex,
+    pub(crate) disable_admin_password_login: bool,
+    pub(crate) password_policy: PasswordPolicy,
+    pub(crate) login_throttle_policy: LoginThrottlePolicy,
+    /// 登录失败记录保存在内存中，为空时保存在数据库中
+    pub(crate) login_attempts: Option<MemoryAttemptStore>,
+    /// 未配置加密密钥时为空，使用两步验证时返回 `SecretKeyNotConfigured`
+    pub(crate) secret_cipher: Option<SecretCipher>,
 }
 
 #[derive(Debug, Builder, Default)]
 pub struct ManagerConfig {
-    /// 数据库地址
+    /// 数据库地址，支持 `mysql://` 与 `sqlite://`
     database_url: String,
+    /// 数据目录，用于存放搜索索引等数据；为空时索引仅保存在内存中
+    #[builder(default, setter(into, strip_option))]
+    data_dir: Option<PathBuf>,
+    /// 禁止管理员使用密码登录，管理员只能使用密钥登录
+    #[builder(default)]
+    disable_admin_password_login: bool,
+    /// 创建用户及重置密码时使用的密码策略
+    #[builder(default)]
+    password_policy: PasswordPolicy,
+    #[builder(default)]
+    login_throttle_policy: LoginThrottlePolicy,
+    /// 将登录失败记录保存在数据库中，以便多个实例共享并在重启后保留
+    #[builder(default)]
+    persist_login_attempts: bool,
+    /// 用于加密两步验证密钥等敏感数据，修改后已开启的两步验证将无法使用；为空时无法使用两步验证
+    #[builder(default, setter(into, strip_option))]
+    secret_key: Option<Vec<u8>>,
 }
 
 impl Manager {
     pub async fn create_from_config(config: ManagerConfig) -> InspirerContentResult<Self> {
-        let database = Database::connect(&config.database_url).await?;
-        tracing::info!("Created database component");
+        let (pool, database) = DatabasePool::connect(&config.database_url).await?;
+        tracing::info!(
+            "Created database component, backend = {:?}",
+            database.get_database_backend()
+        );
+
+        let search_index =
+            SearchIndex::open(config.data_dir.map(|dir| dir.join("search")).as_deref())?;
+        tracing::info!("Created search index component");
 
         tracing::info!("Created inspirer content manager module");
-        Ok(Manager { database })
-    }    
+        Ok(Manager {
+            database,
+            pool,
+            render_cache: RenderCache::default(),
+            search_index,
+            disable_admin_password_login: config.disable_admin_password_login,
+            password_policy: config.password_policy,
+            login_throttle_policy: config.login_throttle_policy,
+            login_attempts: (!config.persist_login_attempts).then(MemoryAttemptStore::default),
+            secret_cipher: config.secret_key.as_deref().map(SecretCipher::new),
+        })
+    }
+
+    pub(crate) fn secret_cipher(&self) -> InspirerContentResult<&SecretCipher> {
+        self.secret_cipher
+            .as_ref()
+            .ok_or(Error::SecretKeyNotConfigured)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::{Manager, ManagerConfigBuilder};
+    use crate::util::uuid::generate_v4_uuid;
+
+    /// 创建一个基于临时 SQLite 数据库文件的 Manager，并执行全部迁移
+    pub async fn sqlite_manager() -> Manager {
+        let path = std::env::temp_dir().join(format!("inspirer-test-{}.db", generate_v4_uuid()));
+        let manager = Manager::create_from_config(
+            ManagerConfigBuilder::default()
+                .database_url(format!("sqlite://{}?mode=rwc", path.display()))
+                .secret_key("test-secret")
+                .build()
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+        manager.migrate().await.unwrap();
+
+        manager
+    }
 }
@@ -0,0 +1,255 @@
+use std::collections::HashMap;
+
+use sea_orm::{DatabaseConnection, DbErr, SqlxMySqlConnector, SqlxSqliteConnector};
+use serde::Serialize;
+use sqlx::{
+    migrate::{Migrate, Migrator},
+    pool::PoolOptions,
+    Database, Pool,
+};
+
+use crate::{
+    error::{Error, InspirerContentResult},
+    manager::Manager,
+};
+
+/// 内嵌的 MySQL 迁移文件（与 sqlx-cli 目录结构一致）
+static MYSQL_MIGRATOR: Migrator = sqlx::migrate!("../migrations/mysql");
+/// 内嵌的 SQLite 迁移文件（与 sqlx-cli 目录结构一致）
+static SQLITE_MIGRATOR: Migrator = sqlx::migrate!("../migrations/sqlite");
+
+/// 单个迁移的状态
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct MigrationStatus {
+    pub version: i64,
+    pub description: String,
+    /// 是否已执行
+    pub applied: bool,
+    /// 已执行的迁移内容与当前内嵌的迁移内容不一致
+    pub checksum_mismatch: bool,
+}
+
+/// 数据库连接池
+///
+/// SeaORM 不对外暴露底层 sqlx 连接池，因此由这里建立连接池并交给 SeaORM 使用，迁移时复用同一连接池。
+#[derive(Clone)]
+pub(crate) enum DatabasePool {
+    MySql(Pool<sqlx::MySql>),
+    Sqlite(Pool<sqlx::Sqlite>),
+}
+
+impl DatabasePool {
+    /// 按数据库地址建立连接池，返回连接池及基于该连接池的 SeaORM 连接
+    pub(crate) async fn connect(url: &str) -> InspirerContentResult<(Self, DatabaseConnection)> {
+        if url.starts_with("mysql:") {
+            let pool = PoolOptions::<sqlx::MySql>::new()
+                .connect(url)
+                .await
+                .map_err(connect_error)?;
+
+            Ok((
+                DatabasePool::MySql(pool.clone()),
+                SqlxMySqlConnector::from_sqlx_mysql_pool(pool),
+            ))
+        } else if url.starts_with("sqlite:") {
+            // 与 SeaORM 的默认配置一致，SQLite 仅使用一个连接
+            let pool = PoolOptions::<sqlx::Sqlite>::new()
+                .max_connections(1)
+                .connect(url)
+                .await
+                .map_err(connect_error)?;
+
+            Ok((
+                DatabasePool::Sqlite(pool.clone()),
+                SqlxSqliteConnector::from_sqlx_sqlite_pool(pool),
+            ))
+        } else {
+            Err(Error::UnsupportedDatabaseBackend)
+        }
+    }
+}
+
+fn connect_error(err: sqlx::Error) -> Error {
+    Error::DatabaseError(DbErr::Conn(err.to_string()))
+}
+
+impl Manager {
+    /// 执行所有未执行的迁移
+    pub async fn migrate(&self) -> InspirerContentResult<()> {
+        match &self.pool {
+            DatabasePool::MySql(pool) => MYSQL_MIGRATOR.run(pool).await?,
+            DatabasePool::Sqlite(pool) => SQLITE_MIGRATOR.run(pool).await?,
+        }
+
+        Ok(())
+    }
+
+    /// 回滚最近执行的 `steps` 个迁移
+    pub async fn rollback(&self, steps: usize) -> InspirerContentResult<()> {
+        match &self.pool {
+            DatabasePool::MySql(pool) => rollback(&MYSQL_MIGRATOR, pool, steps).await,
+            DatabasePool::Sqlite(pool) => rollback(&SQLITE_MIGRATOR, pool, steps).await,
+        }
+    }
+
+    /// 回滚全部迁移后重新执行
+    pub async fn migrate_fresh(&self) -> InspirerContentResult<()> {
+        match &self.pool {
+            DatabasePool::MySql(pool) => {
+                MYSQL_MIGRATOR.undo(pool, 0).await?;
+                MYSQL_MIGRATOR.run(pool).await?;
+            }
+            DatabasePool::Sqlite(pool) => {
+                SQLITE_MIGRATOR.undo(pool, 0).await?;
+                SQLITE_MIGRATOR.run(pool).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 获取所有内嵌迁移的执行状态
+    pub async fn migration_status(&self) -> InspirerContentResult<Vec<MigrationStatus>> {
+        match &self.pool {
+            DatabasePool::MySql(pool) => status(&MYSQL_MIGRATOR, pool).await,
+            DatabasePool::Sqlite(pool) => status(&SQLITE_MIGRATOR, pool).await,
+        }
+    }
+
+    /// 获取尚未执行的迁移
+    pub async fn pending_migrations(&self) -> InspirerContentResult<Vec<MigrationStatus>> {
+        Ok(self
+            .migration_status()
+            .await?
+            .into_iter()
+            .filter(|status| !status.applied)
+            .collect())
+    }
+}
+
+async fn applied_migrations<DB>(pool: &Pool<DB>) -> InspirerContentResult<HashMap<i64, Vec<u8>>>
+where
+    DB: Database,
+    DB::Connection: Migrate,
+{
+    let mut conn = pool
+        .acquire()
+        .await
+        .map_err(|err| Error::MigrateError(err.into()))?;
+    conn.ensure_migrations_table().await?;
+
+    Ok(conn
+        .list_applied_migrations()
+        .await?
+        .into_iter()
+        .map(|migration| (migration.version, migration.checksum.into_owned()))
+        .collect())
+}
+
+async fn status<DB>(
+    migrator: &Migrator,
+    pool: &Pool<DB>,
+) -> InspirerContentResult<Vec<MigrationStatus>>
+where
+    DB: Database,
+    DB::Connection: Migrate,
+{
+    let applied = applied_migrations(pool).await?;
+
+    Ok(migrator
+        .iter()
+        .filter(|migration| !migration.migration_type.is_down_migration())
+        .map(|migration| {
+            let checksum = applied.get(&migration.version);
+
+            MigrationStatus {
+                version: migration.version,
+                description: migration.description.to_string(),
+                applied: checksum.is_some(),
+                checksum_mismatch: checksum
+                    .map(|checksum| checksum.as_slice() != migration.checksum.as_ref())
+                    .unwrap_or(false),
+            }
+        })
+        .collect())
+}
+
+async fn rollback<DB>(
+    migrator: &Migrator,
+    pool: &Pool<DB>,
+    steps: usize,
+) -> InspirerContentResult<()>
+where
+    DB: Database,
+    DB::Connection: Migrate,
+{
+    let mut versions = applied_migrations(pool)
+        .await?
+        .into_keys()
+        .collect::<Vec<_>>();
+    versions.sort_unstable_by(|a, b| b.cmp(a));
+
+    // 回滚所有版本号大于目标版本的迁移
+    let target = versions.get(steps).copied().unwrap_or(0);
+    migrator.undo(pool, target).await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        manager::{tests::sqlite_manager, Manager, ManagerConfigBuilder},
+        service::user::UserService,
+    };
+
+    #[tokio::test]
+    async fn test_migrate() {
+        let manager = sqlite_manager().await;
+
+        let status = manager.migration_status().await.unwrap();
+        assert!(!status.is_empty());
+        assert!(status
+            .iter()
+            .all(|status| status.applied && !status.checksum_mismatch));
+        assert!(manager.pending_migrations().await.unwrap().is_empty());
+
+        manager.rollback(2).await.unwrap();
+        let pending = manager.pending_migrations().await.unwrap();
+        assert_eq!(pending.len(), 2);
+        assert_eq!(pending[1].version, status.last().unwrap().version);
+
+        manager.migrate().await.unwrap();
+        assert!(manager.pending_migrations().await.unwrap().is_empty());
+
+        manager.migrate_fresh().await.unwrap();
+        assert!(manager.pending_migrations().await.unwrap().is_empty());
+
+        manager.rollback(status.len()).await.unwrap();
+        assert_eq!(
+            manager.pending_migrations().await.unwrap().len(),
+            status.len()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_migrate_in_memory() {
+        let manager = Manager::create_from_config(
+            ManagerConfigBuilder::default()
+                .database_url("sqlite::memory:".into())
+                .build()
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+        manager.migrate().await.unwrap();
+        assert!(manager.pending_migrations().await.unwrap().is_empty());
+        // 迁移与业务查询使用同一个内存数据库
+        assert!(manager
+            .get_user_by_id(Default::default())
+            .await
+            .unwrap()
+            .is_none());
+    }
+}
@@ -0,0 +1,66 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+pub use crate::entity::audit_logs::Model as AuditLogModel;
+use crate::enumerate::audit::{AuditAction, AuditTargetType};
+
+/// 待记录的审计日志
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewAuditLog {
+    pub action: AuditAction,
+    /// 执行操作的用户，命令行操作及登录失败时为空
+    pub actor_id: Option<Uuid>,
+    /// 使用个人访问令牌执行操作时的令牌
+    pub access_token_id: Option<Uuid>,
+    pub target_type: Option<AuditTargetType>,
+    pub target_id: Option<Uuid>,
+    pub detail: Option<serde_json::Value>,
+    pub ip: Option<String>,
+    pub user_agent: String,
+}
+
+impl NewAuditLog {
+    pub fn new(action: AuditAction) -> Self {
+        NewAuditLog {
+            action,
+            actor_id: None,
+            access_token_id: None,
+            target_type: None,
+            target_id: None,
+            detail: None,
+            ip: None,
+            user_agent: String::new(),
+        }
+    }
+
+    pub fn actor(mut self, actor_id: Uuid) -> Self {
+        self.actor_id = Some(actor_id);
+        self
+    }
+
+    pub fn access_token(mut self, access_token_id: Uuid) -> Self {
+        self.access_token_id = Some(access_token_id);
+        self
+    }
+
+    pub fn target(mut self, target_type: AuditTargetType, target_id: Uuid) -> Self {
+        self.target_type = Some(target_type);
+        self.target_id = Some(target_id);
+        self
+    }
+
+    pub fn detail(mut self, detail: serde_json::Value) -> Self {
+        self.detail = Some(detail);
+        self
+    }
+}
+
+/// 审计日志查询条件，为空的条件不参与过滤
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AuditLogCondition {
+    pub actor_id: Option<Uuid>,
+    pub action: Option<AuditAction>,
+    pub target_id: Option<Uuid>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+}
@@ -1,8 +1,9 @@
 pub use crate::entity::content_entities::Model as ContentEntityModel;
 pub use crate::entity::contents::Model as ContentModel;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
+use uuid::Uuid;
 
-use super::Order;
+use super::{user::ContentAction, Order};
 #[derive(Default, Deserialize, Serialize)]
 #[serde(default)]
 pub struct NewContent {
@@ -11,16 +12,20 @@ pub struct NewContent {
     pub entity: ContentEntity,
 }
 
-#[derive(Debug, Deserialize, Default, Serialize)]
+#[derive(Debug, Deserialize, Default, Serialize, Clone)]
 #[serde(default)]
 pub struct ContentMeta {
     pub title: String,
     pub keywords: String,
     pub description: String,
     pub name: Option<String>,
+    /// 标签名称，不存在的标签会被自动创建
+    pub tags: Vec<String>,
+    /// 分类名称，不存在的分类会被自动创建
+    pub categories: Vec<String>,
 }
 
-#[derive(Debug, Deserialize, Default, Serialize)]
+#[derive(Debug, Deserialize, Default, Serialize, Clone)]
 #[serde(default)]
 pub struct UpdateContentMeta {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -29,11 +34,22 @@ pub struct UpdateContentMeta {
     pub keywords: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
+    /// 早期迁移生成的更新记录中该字段名为 `content_name`；为 `Some(None)`（即 `null`）时清除名称
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_some",
+        alias = "content_name"
+    )]
+    pub name: Option<Option<String>>,
+    /// 替换内容的全部标签
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub name: Option<String>,
+    pub tags: Option<Vec<String>>,
+    /// 替换内容的全部分类
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub categories: Option<Vec<String>>,
 }
 
-#[derive(Debug, Deserialize, Default, Serialize)]
+#[derive(Debug, Deserialize, Default, Serialize, Clone)]
 #[serde(default)]
 pub struct UpdateContent {
     #[serde(flatten)]
@@ -49,6 +65,15 @@ pub enum ContentEntity {
     Page(String),
 }
 
+impl ContentEntity {
+    /// 内容正文
+    pub fn body(&self) -> &str {
+        match self {
+            ContentEntity::Post(body) | ContentEntity::Page(body) => body,
+        }
+    }
+}
+
 impl Default for ContentEntity {
     fn default() -> Self {
         ContentEntity::Post(String::new())
@@ -62,23 +87,72 @@ pub struct GetListCondition {
     pub with_unpublish: bool,
     pub without_page: bool,
     pub list_deleted: bool,
+    /// 仅列出该用户所有的内容
+    pub owner_id: Option<Uuid>,
+    /// 仅列出包含该标签的内容
+    pub tag: Option<String>,
+    /// 仅列出属于该分类的内容
+    pub category: Option<String>,
     pub sort: Vec<Order<SortField>>,
 }
 
+impl GetListCondition {
+    /// 公开展示的内容：已发布、未隐藏且不包含页面，按发布时间倒序
+    pub fn published() -> Self {
+        GetListCondition {
+            without_page: true,
+            sort: vec![
+                Order::Desc(SortField::PublishedAt),
+                Order::Desc(SortField::CreatedAt),
+            ],
+            ..Default::default()
+        }
+    }
+}
+
+/// 批量操作内容的动作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BulkContentAction {
+    Publish,
+    Unpublish,
+    Hide,
+    Show,
+    /// 删除内容（可恢复）
+    Delete,
+    /// 恢复已删除的内容
+    Restore,
+}
+
+impl BulkContentAction {
+    /// 执行该动作所需的内容操作权限
+    pub fn content_action(&self) -> ContentAction {
+        match self {
+            BulkContentAction::Publish
+            | BulkContentAction::Unpublish
+            | BulkContentAction::Hide
+            | BulkContentAction::Show => ContentAction::Publish,
+            BulkContentAction::Delete | BulkContentAction::Restore => ContentAction::Delete,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum SortField {
     PublishedAt,
     CreatedAt,
     DeletedAt,
+    ModifiedAt,
 }
 
-impl Into<crate::entity::contents::Column> for SortField {
-    fn into(self) -> crate::entity::contents::Column {
-        match self {
+impl From<SortField> for crate::entity::contents::Column {
+    fn from(field: SortField) -> Self {
+        match field {
             SortField::CreatedAt => crate::entity::contents::Column::CreatedAt,
             SortField::PublishedAt => crate::entity::contents::Column::PublishedAt,
             SortField::DeletedAt => crate::entity::contents::Column::DeletedAt,
+            SortField::ModifiedAt => crate::entity::contents::Column::ModifiedAt,
         }
     }
 }
@@ -88,6 +162,8 @@ pub struct Content {
     #[serde(flatten)]
     pub meta: ContentModel,
     pub entity: ContentEntity,
+    pub tags: Vec<String>,
+    pub categories: Vec<String>,
 }
 
 impl From<NewContent> for UpdateContent {
@@ -98,13 +174,24 @@ impl From<NewContent> for UpdateContent {
                 title: Some(meta.title),
                 keywords: Some(meta.keywords),
                 description: Some(meta.description),
-                name: meta.name,
+                name: meta.name.map(Some),
+                tags: Some(meta.tags),
+                categories: Some(meta.categories),
             },
             entity: Some(entity),
         }
     }
 }
 
+/// 区分字段缺失与 `null`：字段存在时（包括 `null`）总是得到 `Some`
+fn deserialize_some<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    T::deserialize(deserializer).map(Some)
+}
+
 #[derive(Debug, Serialize)]
 pub struct ContentConfig {
     pub content_support_type: &'static [&'static str],
@@ -1,7 +1,15 @@
 use serde::Deserialize;
 
+pub mod audit;
 pub mod content;
 pub mod paginate;
+pub mod profile;
+pub mod revision;
+pub mod search;
+pub mod session;
+pub mod signature;
+pub mod taxonomy;
+pub mod token;
 pub mod user;
 
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
@@ -11,9 +19,9 @@ pub enum Order<T> {
     Desc(T),
 }
 
-impl<T: Clone> Into<sea_orm::query::Order> for Order<T> {
-    fn into(self) -> sea_orm::query::Order {
-        self.into_order()
+impl<T: Clone> From<Order<T>> for sea_orm::query::Order {
+    fn from(order: Order<T>) -> Self {
+        order.into_order()
     }
 }
 
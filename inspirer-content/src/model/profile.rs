@@ -0,0 +1,145 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, InspirerContentResult};
+
+const BIO_MAX_CHARS: usize = 500;
+const LINKS_MAX: usize = 10;
+const LINK_TITLE_MAX_CHARS: usize = 50;
+const URL_MAX_CHARS: usize = 500;
+const SOCIAL_MAX: usize = 10;
+const SOCIAL_PLATFORM_MAX_CHARS: usize = 32;
+const SOCIAL_HANDLE_MAX_CHARS: usize = 100;
+
+/// 用户资料，保存在 `users.user_profile` 中
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct UserProfileData {
+    /// 个人简介
+    pub bio: String,
+    pub links: Vec<ProfileLink>,
+    /// 社交账号，键为平台名称（如 `github`），值为账号
+    pub social: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProfileLink {
+    pub title: String,
+    pub url: String,
+}
+
+impl UserProfileData {
+    pub fn validate(&self) -> InspirerContentResult<()> {
+        if self.bio.chars().count() > BIO_MAX_CHARS {
+            return Err(invalid(format!("个人简介不能超过 {BIO_MAX_CHARS} 个字符")));
+        }
+
+        if self.links.len() > LINKS_MAX {
+            return Err(invalid(format!("链接不能超过 {LINKS_MAX} 个")));
+        }
+
+        for link in &self.links {
+            if link.title.trim().is_empty() || link.title.chars().count() > LINK_TITLE_MAX_CHARS {
+                return Err(invalid(format!(
+                    "链接标题不能为空且不能超过 {LINK_TITLE_MAX_CHARS} 个字符"
+                )));
+            }
+
+            if !is_http_url(&link.url) {
+                return Err(invalid(format!("链接地址无效：{}", link.url)));
+            }
+        }
+
+        if self.social.len() > SOCIAL_MAX {
+            return Err(invalid(format!("社交账号不能超过 {SOCIAL_MAX} 个")));
+        }
+
+        for (platform, handle) in &self.social {
+            let valid_platform = !platform.is_empty()
+                && platform.len() <= SOCIAL_PLATFORM_MAX_CHARS
+                && platform
+                    .bytes()
+                    .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-');
+            if !valid_platform {
+                return Err(invalid(format!(
+                    "社交平台名称仅支持小写字母、数字及 -：{platform}"
+                )));
+            }
+
+            if handle.trim().is_empty() || handle.chars().count() > SOCIAL_HANDLE_MAX_CHARS {
+                return Err(invalid(format!(
+                    "社交账号不能为空且不能超过 {SOCIAL_HANDLE_MAX_CHARS} 个字符"
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// 是否为 http 或 https 地址
+pub fn is_http_url(url: &str) -> bool {
+    let rest = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"));
+
+    match rest {
+        Some(rest) => {
+            !rest.is_empty()
+                && url.chars().count() <= URL_MAX_CHARS
+                && !url.chars().any(|c| c.is_whitespace() || c.is_control())
+        }
+        None => false,
+    }
+}
+
+fn invalid(msg: String) -> Error {
+    Error::InvalidProfile(msg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_profile() {
+        let profile: UserProfileData = serde_json::from_value(serde_json::json!({
+            "bio": "Rustacean",
+            "links": [{ "title": "博客", "url": "https://example.com" }],
+            "social": { "github": "chongyi" },
+        }))
+        .unwrap();
+        assert!(profile.validate().is_ok());
+        assert!(UserProfileData::default().validate().is_ok());
+
+        assert!(
+            serde_json::from_value::<UserProfileData>(serde_json::json!({
+                "unknown": true,
+            }))
+            .is_err()
+        );
+
+        let invalid_url = UserProfileData {
+            links: vec![ProfileLink {
+                title: "link".into(),
+                url: "javascript:alert(1)".into(),
+            }],
+            ..Default::default()
+        };
+        assert!(invalid_url.validate().is_err());
+
+        let mut invalid_platform = UserProfileData::default();
+        invalid_platform
+            .social
+            .insert("GitHub".into(), "chongyi".into());
+        assert!(invalid_platform.validate().is_err());
+
+        let long_bio = UserProfileData {
+            bio: "字".repeat(BIO_MAX_CHARS + 1),
+            ..Default::default()
+        };
+        assert!(long_bio.validate().is_err());
+    }
+}
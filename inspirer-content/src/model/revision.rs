@@ -0,0 +1,354 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use similar::{ChangeTag, TextDiff};
+use uuid::Uuid;
+
+use crate::enumerate::content::ContentType;
+
+use super::{
+    content::{ContentEntity, ContentMeta, UpdateContent, UpdateContentMeta},
+    user::UserModel,
+};
+
+/// 内容修订记录，每次创建或更新内容都会产生一条
+#[derive(Debug, Clone)]
+pub struct ContentRevision {
+    pub id: Uuid,
+    pub content_id: Uuid,
+    pub author: Option<UserModel>,
+    /// 本次更新提交的（部分）内容
+    pub update_data: UpdateContent,
+    pub created_at: DateTime<Utc>,
+}
+
+/// 通过回放修订记录得到的某一版本的完整内容
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ContentSnapshot {
+    #[serde(flatten)]
+    pub meta: ContentMeta,
+    pub entity: ContentEntity,
+    /// 修订记录中是否记录过标签，早期的修订记录不包含标签
+    #[serde(skip)]
+    pub tags_recorded: bool,
+    /// 修订记录中是否记录过分类，早期的修订记录不包含分类
+    #[serde(skip)]
+    pub categories_recorded: bool,
+}
+
+impl ContentSnapshot {
+    /// 在当前状态上应用一次更新
+    pub fn apply(&mut self, update_content: &UpdateContent) {
+        let meta = &update_content.meta;
+
+        if let Some(title) = &meta.title {
+            self.meta.title = title.clone();
+        }
+
+        if let Some(keywords) = &meta.keywords {
+            self.meta.keywords = keywords.clone();
+        }
+
+        if let Some(description) = &meta.description {
+            self.meta.description = description.clone();
+        }
+
+        if let Some(name) = &meta.name {
+            self.meta.name = name.clone();
+        }
+
+        if let Some(tags) = &meta.tags {
+            self.meta.tags = tags.clone();
+            self.tags_recorded = true;
+        }
+
+        if let Some(categories) = &meta.categories {
+            self.meta.categories = categories.clone();
+            self.categories_recorded = true;
+        }
+
+        if let Some(entity) = &update_content.entity {
+            self.entity = entity.clone();
+        }
+    }
+
+    /// 对比两个版本，得到从 `self` 到 `other` 的变更
+    pub fn diff(&self, other: &ContentSnapshot) -> ContentDiff {
+        let tags = (self.meta.tags.join(", "), other.meta.tags.join(", "));
+        let categories = (
+            self.meta.categories.join(", "),
+            other.meta.categories.join(", "),
+        );
+        let fields = [
+            ("title", Some(&self.meta.title), Some(&other.meta.title)),
+            (
+                "keywords",
+                Some(&self.meta.keywords),
+                Some(&other.meta.keywords),
+            ),
+            (
+                "description",
+                Some(&self.meta.description),
+                Some(&other.meta.description),
+            ),
+            ("name", self.meta.name.as_ref(), other.meta.name.as_ref()),
+            ("tags", Some(&tags.0), Some(&tags.1)),
+            ("categories", Some(&categories.0), Some(&categories.1)),
+        ]
+        .into_iter()
+        .filter(|(_, old, new)| old != new)
+        .map(|(field, old, new)| FieldChange {
+            field,
+            old: old.cloned(),
+            new: new.cloned(),
+        })
+        .collect();
+
+        ContentDiff {
+            fields,
+            entity: EntityDiff::new(&self.entity, &other.entity),
+        }
+    }
+
+    /// 按顺序回放一组更新
+    pub fn replay<'a, I: IntoIterator<Item = &'a UpdateContent>>(updates: I) -> Self {
+        updates.into_iter().fold(
+            ContentSnapshot::default(),
+            |mut snapshot, update_content| {
+                snapshot.apply(update_content);
+                snapshot
+            },
+        )
+    }
+}
+
+/// 将快照转换为一次完整的更新，用于恢复到该版本
+///
+/// 修订记录中未记录过的标签与分类保持内容当前的值不变。
+impl From<ContentSnapshot> for UpdateContent {
+    fn from(snapshot: ContentSnapshot) -> Self {
+        let ContentSnapshot {
+            meta,
+            entity,
+            tags_recorded,
+            categories_recorded,
+        } = snapshot;
+
+        UpdateContent {
+            meta: UpdateContentMeta {
+                title: Some(meta.title),
+                keywords: Some(meta.keywords),
+                description: Some(meta.description),
+                name: Some(meta.name),
+                tags: tags_recorded.then_some(meta.tags),
+                categories: categories_recorded.then_some(meta.categories),
+            },
+            entity: Some(entity),
+        }
+    }
+}
+
+/// 两个修订版本之间的差异
+#[derive(Debug, Clone, Serialize)]
+pub struct ContentDiff {
+    /// 发生变化的元信息字段
+    pub fields: Vec<FieldChange>,
+    pub entity: EntityDiff,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct FieldChange {
+    pub field: &'static str,
+    pub old: Option<String>,
+    pub new: Option<String>,
+}
+
+/// 正文差异，按行对比并以带上下文的片段（hunk）形式给出
+#[derive(Debug, Clone, Serialize)]
+pub struct EntityDiff {
+    pub old_type: ContentType,
+    pub new_type: ContentType,
+    pub insertions: usize,
+    pub deletions: usize,
+    pub hunks: Vec<DiffHunk>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DiffHunk {
+    pub lines: Vec<DiffLine>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct DiffLine {
+    pub tag: DiffTag,
+    /// 在旧版本中的行号（从 1 开始）
+    pub old_line: Option<usize>,
+    /// 在新版本中的行号（从 1 开始）
+    pub new_line: Option<usize>,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffTag {
+    Equal,
+    Insert,
+    Delete,
+}
+
+impl From<ChangeTag> for DiffTag {
+    fn from(tag: ChangeTag) -> Self {
+        match tag {
+            ChangeTag::Equal => DiffTag::Equal,
+            ChangeTag::Insert => DiffTag::Insert,
+            ChangeTag::Delete => DiffTag::Delete,
+        }
+    }
+}
+
+/// 每个差异片段前后保留的上下文行数
+const DIFF_CONTEXT_LINES: usize = 3;
+
+impl EntityDiff {
+    pub fn new(old: &ContentEntity, new: &ContentEntity) -> Self {
+        let diff = TextDiff::from_lines(old.body(), new.body());
+
+        let mut insertions = 0;
+        let mut deletions = 0;
+
+        let hunks = diff
+            .grouped_ops(DIFF_CONTEXT_LINES)
+            .iter()
+            .map(|group| DiffHunk {
+                lines: group
+                    .iter()
+                    .flat_map(|op| diff.iter_changes(op))
+                    .map(|change| {
+                        match change.tag() {
+                            ChangeTag::Insert => insertions += 1,
+                            ChangeTag::Delete => deletions += 1,
+                            ChangeTag::Equal => (),
+                        }
+
+                        DiffLine {
+                            tag: change.tag().into(),
+                            old_line: change.old_index().map(|index| index + 1),
+                            new_line: change.new_index().map(|index| index + 1),
+                            value: change
+                                .to_string_lossy()
+                                .trim_end_matches(&['\r', '\n'][..])
+                                .to_string(),
+                        }
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        EntityDiff {
+            old_type: old.into(),
+            new_type: new.into(),
+            insertions,
+            deletions,
+            hunks,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(title: &str, name: Option<&str>, body: &str) -> ContentSnapshot {
+        ContentSnapshot {
+            meta: ContentMeta {
+                title: title.into(),
+                name: name.map(Into::into),
+                ..Default::default()
+            },
+            entity: ContentEntity::Post(body.into()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_diff() {
+        let old = snapshot("old", None, "a\nb\nc\nd\ne\nf\ng\nh\ni\n");
+        let new = snapshot("new", Some("name"), "a\nb\nc\nd\nE\nf\ng\nh\ni\nj\n");
+
+        let diff = old.diff(&new);
+
+        assert_eq!(
+            diff.fields,
+            vec![
+                FieldChange {
+                    field: "title",
+                    old: Some("old".into()),
+                    new: Some("new".into()),
+                },
+                FieldChange {
+                    field: "name",
+                    old: None,
+                    new: Some("name".into()),
+                },
+            ]
+        );
+
+        assert_eq!(diff.entity.insertions, 2);
+        assert_eq!(diff.entity.deletions, 1);
+        assert_eq!(diff.entity.hunks.len(), 1);
+
+        let changed = diff.entity.hunks[0]
+            .lines
+            .iter()
+            .filter(|line| line.tag != DiffTag::Equal)
+            .cloned()
+            .collect::<Vec<_>>();
+        assert_eq!(
+            changed,
+            vec![
+                DiffLine {
+                    tag: DiffTag::Delete,
+                    old_line: Some(5),
+                    new_line: None,
+                    value: "e".into(),
+                },
+                DiffLine {
+                    tag: DiffTag::Insert,
+                    old_line: None,
+                    new_line: Some(5),
+                    value: "E".into(),
+                },
+                DiffLine {
+                    tag: DiffTag::Insert,
+                    old_line: None,
+                    new_line: Some(10),
+                    value: "j".into(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_unchanged() {
+        let old = snapshot("same", None, "body");
+        let diff = old.diff(&old.clone());
+
+        assert!(diff.fields.is_empty());
+        assert!(diff.entity.hunks.is_empty());
+    }
+
+    #[test]
+    fn test_apply_name() {
+        let mut snapshot = snapshot("v1", Some("v1"), "");
+        let parse = |value| serde_json::from_value::<UpdateContent>(value).unwrap();
+
+        // 早期记录使用 `content_name` 字段
+        snapshot.apply(&parse(serde_json::json!({ "content_name": "legacy" })));
+        assert_eq!(snapshot.meta.name.as_deref(), Some("legacy"));
+
+        snapshot.apply(&parse(serde_json::json!({ "title": "v2" })));
+        assert_eq!(snapshot.meta.name.as_deref(), Some("legacy"));
+
+        snapshot.apply(&parse(serde_json::json!({ "name": null })));
+        assert!(snapshot.meta.name.is_none());
+    }
+}
@@ -0,0 +1,15 @@
+use serde::Serialize;
+
+use crate::model::{content::ContentModel, user::UserModel};
+
+/// 搜索结果
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchResult {
+    pub content: ContentModel,
+    pub owner: Option<UserModel>,
+    pub score: f32,
+    /// 标题中命中的高亮片段
+    pub title_highlight: Option<String>,
+    /// 正文中命中的高亮片段
+    pub body_highlight: Option<String>,
+}
@@ -0,0 +1,17 @@
+use chrono::{DateTime, Utc};
+
+pub use crate::entity::user_sessions::Model as SessionModel;
+
+/// 新签发的会话，刷新令牌仅在签发时可见
+#[derive(Debug, Clone)]
+pub struct IssuedSession {
+    pub session: SessionModel,
+    pub refresh_token: String,
+}
+
+impl SessionModel {
+    /// 会话未被吊销且未过期
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.revoked_at.is_none() && self.expires_at > now
+    }
+}
@@ -0,0 +1,38 @@
+use crate::util::signature::signing_message;
+
+pub use crate::entity::login_challenges::Model as LoginChallengeModel;
+
+/// 使用 Ed25519 私钥签名的请求
+#[derive(Debug, Clone)]
+pub struct SignedRequest {
+    /// 公钥指纹（公钥的 SHA-256 摘要）
+    pub fingerprint: Vec<u8>,
+    pub method: String,
+    /// 请求路径，包含查询参数
+    pub path: String,
+    /// 签名时间，Unix 秒
+    pub timestamp: i64,
+    /// 随机数，同一用户在有效期内不可重复使用
+    pub nonce: String,
+    /// 请求体的 SHA-256 摘要
+    pub body_hash: Vec<u8>,
+    pub signature: Vec<u8>,
+}
+
+impl SignedRequest {
+    /// 待签名内容
+    pub fn message(&self) -> String {
+        signing_message(
+            &self.method,
+            &self.path,
+            self.timestamp,
+            &self.nonce,
+            &self.body_hash,
+        )
+    }
+
+    /// 随机数须为 8 至 64 位的可见 ASCII 字符
+    pub fn is_nonce_valid(&self) -> bool {
+        (8..=64).contains(&self.nonce.len()) && self.nonce.bytes().all(|b| b.is_ascii_graphic())
+    }
+}
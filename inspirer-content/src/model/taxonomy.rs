@@ -0,0 +1,44 @@
+pub use crate::entity::categories::Model as CategoryModel;
+pub use crate::entity::tags::Model as TagModel;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Default, Clone)]
+#[serde(default)]
+pub struct NewCategory {
+    pub name: String,
+    pub description: String,
+}
+
+#[derive(Debug, Deserialize, Default, Serialize, Clone)]
+#[serde(default)]
+pub struct UpdateCategory {
+    pub name: Option<String>,
+    pub description: Option<String>,
+}
+
+/// 整理标签或分类名称：去除首尾空白、空名称及重复名称，保持原有顺序
+pub fn normalize_terms<S: AsRef<str>>(names: &[S]) -> Vec<String> {
+    let mut terms: Vec<String> = Vec::with_capacity(names.len());
+
+    for name in names {
+        let name = name.as_ref().trim();
+        if !name.is_empty() && !terms.iter().any(|term| term == name) {
+            terms.push(name.to_string());
+        }
+    }
+
+    terms
+}
+
+#[cfg(test)]
+mod tests {
+    use super::normalize_terms;
+
+    #[test]
+    fn test_normalize_terms() {
+        assert_eq!(
+            normalize_terms(&[" rust ", "", "web", "rust", "  "]),
+            vec!["rust".to_string(), "web".to_string()]
+        );
+    }
+}
@@ -0,0 +1,46 @@
+use chrono::{DateTime, Utc};
+
+pub use crate::entity::personal_access_tokens::Model as AccessTokenModel;
+use crate::{enumerate::token::TokenScope, model::user::ContentAction};
+
+/// 个人访问令牌的前缀，用于与 JWT 访问令牌区分
+pub const ACCESS_TOKEN_PREFIX: &str = "ipat_";
+
+#[derive(Debug, Clone, Default)]
+pub struct NewAccessToken {
+    pub name: String,
+    pub scopes: Vec<TokenScope>,
+    /// 为空时永不过期
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// 新创建的个人访问令牌，令牌明文仅在创建时可见
+#[derive(Debug, Clone)]
+pub struct IssuedAccessToken {
+    pub access_token: AccessTokenModel,
+    pub token: String,
+}
+
+impl AccessTokenModel {
+    /// 令牌的权限范围，无法解析时视为没有任何权限
+    pub fn scopes(&self) -> Vec<TokenScope> {
+        serde_json::from_value(self.scopes.clone()).unwrap_or_default()
+    }
+
+    /// 令牌未过期
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_none_or(|expires_at| expires_at > now)
+    }
+}
+
+impl TokenScope {
+    /// 对内容执行操作所需的权限范围
+    pub fn required_for(action: ContentAction) -> Self {
+        match action {
+            ContentAction::View => TokenScope::ContentRead,
+            ContentAction::Update => TokenScope::ContentWrite,
+            ContentAction::Publish => TokenScope::ContentPublish,
+            ContentAction::Delete | ContentAction::ForceDelete => TokenScope::ContentDelete,
+        }
+    }
+}
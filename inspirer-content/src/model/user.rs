@@ -1,4 +1,13 @@
+use chrono::{DateTime, Utc};
+
 pub use crate::entity::users::Model as UserModel;
+use crate::{
+    enumerate::user::UserRole,
+    error::{Error, InspirerContentResult},
+    model::profile::{is_http_url, UserProfileData},
+};
+
+const NICKNAME_MAX_CHARS: usize = 40;
 
 #[derive(Debug, Clone, Default)]
 pub struct NewUser {
@@ -6,4 +15,220 @@ pub struct NewUser {
     pub password: String,
     pub nickname: String,
     pub avatar: String,
+    pub role: UserRole,
+}
+
+/// 更新用户资料，为空的字段保持不变
+#[derive(Debug, Clone, Default)]
+pub struct UpdateUser {
+    pub nickname: Option<String>,
+    pub avatar: Option<String>,
+    pub profile: Option<UserProfileData>,
+}
+
+impl UpdateUser {
+    pub fn validate(&self) -> InspirerContentResult<()> {
+        if let Some(nickname) = &self.nickname {
+            if nickname.chars().count() > NICKNAME_MAX_CHARS {
+                return Err(Error::InvalidProfile(format!(
+                    "昵称不能超过 {NICKNAME_MAX_CHARS} 个字符"
+                )));
+            }
+        }
+
+        // 头像允许为空
+        if let Some(avatar) = &self.avatar {
+            if !avatar.is_empty() && !is_http_url(avatar) {
+                return Err(Error::InvalidProfile(format!("头像地址无效：{avatar}")));
+            }
+        }
+
+        if let Some(profile) = &self.profile {
+            profile.validate()?;
+        }
+
+        Ok(())
+    }
+}
+
+/// 登记两步验证时生成的密钥与恢复码，恢复码仅在登记时可见
+#[derive(Debug, Clone)]
+pub struct TwoFactorEnrollment {
+    /// Base32 编码的密钥，供无法扫描二维码时手动输入
+    pub secret: String,
+    pub otpauth_uri: String,
+    pub recovery_codes: Vec<String>,
+}
+
+/// 密码策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PasswordPolicy {
+    /// 最小长度（字符数）
+    pub min_length: usize,
+    /// 至少包含的字符类别数，类别为小写字母、大写字母、数字及其他符号
+    pub min_character_classes: usize,
+}
+
+impl Default for PasswordPolicy {
+    fn default() -> Self {
+        PasswordPolicy {
+            min_length: 8,
+            min_character_classes: 1,
+        }
+    }
+}
+
+impl PasswordPolicy {
+    /// 检查密码是否符合策略，空密码总是不符合
+    pub fn check(&self, password: &str) -> InspirerContentResult<()> {
+        if password.is_empty() {
+            return Err(Error::InvalidPassword("密码不能为空".into()));
+        }
+
+        if password.chars().count() < self.min_length {
+            return Err(Error::InvalidPassword(format!(
+                "密码长度不能少于 {} 位",
+                self.min_length
+            )));
+        }
+
+        let classes = [
+            password.chars().any(|c| c.is_lowercase()),
+            password.chars().any(|c| c.is_uppercase()),
+            password.chars().any(|c| c.is_ascii_digit()),
+            password.chars().any(|c| !c.is_alphanumeric()),
+        ];
+        if classes.iter().filter(|matched| **matched).count() < self.min_character_classes {
+            return Err(Error::InvalidPassword(format!(
+                "密码需包含小写字母、大写字母、数字、符号中的至少 {} 类",
+                self.min_character_classes
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+impl UserModel {
+    pub fn is_disabled(&self) -> bool {
+        self.disabled_at.is_some()
+    }
+
+    /// 是否已开启两步验证，尚未确认的登记不算开启
+    pub fn two_factor_enabled(&self) -> bool {
+        self.totp_enabled_at.is_some()
+    }
+
+    /// 未使用的恢复码摘要
+    pub(crate) fn recovery_code_hashes(&self) -> Vec<String> {
+        self.totp_recovery_codes
+            .clone()
+            .and_then(|codes| serde_json::from_value(codes).ok())
+            .unwrap_or_default()
+    }
+
+    /// 用户资料，为空时返回空资料
+    pub fn profile(&self) -> InspirerContentResult<UserProfileData> {
+        if self.user_profile.is_null() {
+            return Ok(UserProfileData::default());
+        }
+
+        serde_json::from_value(self.user_profile.clone()).map_err(Error::FormatError)
+    }
+
+    /// 获取与指纹匹配且仍然有效的公钥，轮换前的公钥在宽限期内仍然有效
+    pub fn public_key_for(&self, fingerprint: &[u8], now: DateTime<Utc>) -> Option<&[u8]> {
+        if self.public_key_fingerprint == fingerprint {
+            return Some(&self.public_key);
+        }
+
+        match (
+            &self.previous_public_key,
+            &self.previous_public_key_fingerprint,
+            self.previous_key_expires_at,
+        ) {
+            (Some(public_key), Some(previous), Some(expires_at))
+                if previous == fingerprint && expires_at > now =>
+            {
+                Some(public_key)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// 对内容执行的操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentAction {
+    View,
+    Update,
+    Publish,
+    Delete,
+    ForceDelete,
+}
+
+impl UserRole {
+    /// 判断角色能否对内容执行指定操作，`is_owner` 表示内容是否属于该用户
+    pub fn can(&self, action: ContentAction, is_owner: bool) -> bool {
+        match (self, action) {
+            (UserRole::Admin, _) => true,
+            // 彻底删除仅限管理员
+            (_, ContentAction::ForceDelete) => false,
+            (UserRole::Editor, _) => true,
+            (UserRole::Author, _) => is_owner,
+            (UserRole::Contributor, ContentAction::View | ContentAction::Update) => is_owner,
+            (UserRole::Contributor, _) => false,
+        }
+    }
+
+    /// 能否查看与管理他人的内容
+    pub fn can_manage_others_content(&self) -> bool {
+        matches!(self, UserRole::Admin | UserRole::Editor)
+    }
+
+    /// 能否管理分类与标签
+    pub fn can_manage_taxonomy(&self) -> bool {
+        matches!(self, UserRole::Admin | UserRole::Editor)
+    }
+
+    /// 能否查看审计日志
+    pub fn can_view_audit_logs(&self) -> bool {
+        matches!(self, UserRole::Admin)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_password_policy() {
+        let policy = PasswordPolicy::default();
+        assert!(policy.check("password").is_ok());
+        assert!(policy.check("").is_err());
+        assert!(policy.check("short").is_err());
+
+        let policy = PasswordPolicy {
+            min_length: 10,
+            min_character_classes: 3,
+        };
+        assert!(policy.check("Password-1").is_ok());
+        assert!(policy.check("密码Password1").is_ok());
+        assert!(policy.check("password-1").is_ok());
+        assert!(policy.check("password12").is_err());
+        assert!(policy.check("Pass-1").is_err());
+    }
+
+    #[test]
+    fn test_role_permission() {
+        assert!(UserRole::Admin.can(ContentAction::ForceDelete, false));
+        assert!(!UserRole::Editor.can(ContentAction::ForceDelete, true));
+        assert!(UserRole::Editor.can(ContentAction::Publish, false));
+        assert!(UserRole::Author.can(ContentAction::Delete, true));
+        assert!(!UserRole::Author.can(ContentAction::Update, false));
+        assert!(UserRole::Contributor.can(ContentAction::Update, true));
+        assert!(!UserRole::Contributor.can(ContentAction::Publish, true));
+        assert!(UserRole::Admin.can_view_audit_logs());
+        assert!(!UserRole::Editor.can_view_audit_logs());
+    }
 }
\ No newline at end of file
@@ -0,0 +1,214 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, RwLock},
+};
+
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use pulldown_cmark::{html, Event, Options, Parser, Tag};
+use uuid::Uuid;
+
+static SANITIZER: Lazy<ammonia::Builder<'static>> = Lazy::new(|| {
+    let mut builder = ammonia::Builder::default();
+
+    builder
+        // 任务列表
+        .add_tags(["input"])
+        .add_tag_attributes("input", ["type", "checked", "disabled"])
+        // 标题锚点与脚注
+        .add_tag_attributes("h1", ["id"])
+        .add_tag_attributes("h2", ["id"])
+        .add_tag_attributes("h3", ["id"])
+        .add_tag_attributes("h4", ["id"])
+        .add_tag_attributes("h5", ["id"])
+        .add_tag_attributes("h6", ["id"])
+        .add_tag_attributes("div", ["id"])
+        .add_allowed_classes("div", ["footnote-definition"])
+        .add_allowed_classes("sup", ["footnote-reference", "footnote-definition-label"])
+        // 代码块语言与表格对齐
+        .add_tag_attributes("code", ["class"])
+        .add_tag_attributes("th", ["style"])
+        .add_tag_attributes("td", ["style"])
+        .attribute_filter(|element, attribute, value| match (element, attribute) {
+            ("code", "class") => value.starts_with("language-").then_some(value.into()),
+            ("th" | "td", "style") => matches!(
+                value,
+                "text-align: left" | "text-align: center" | "text-align: right"
+            )
+            .then_some(value.into()),
+            ("input", "type") => (value == "checkbox").then_some(value.into()),
+            _ => Some(value.into()),
+        });
+
+    builder
+});
+
+/// 将 Markdown 渲染为经过清理的 HTML
+///
+/// 支持 GFM 表格、脚注、任务列表与删除线，并为标题生成锚点 ID。
+pub fn render_markdown(source: &str) -> String {
+    let options = Options::ENABLE_TABLES
+        | Options::ENABLE_FOOTNOTES
+        | Options::ENABLE_TASKLISTS
+        | Options::ENABLE_STRIKETHROUGH;
+
+    let events = Parser::new_ext(source, options).collect::<Vec<_>>();
+    let anchors = heading_anchors(&events);
+    let mut anchors = anchors.iter().map(String::as_str);
+
+    let events = events.into_iter().map(|event| match event {
+        Event::Start(Tag::Heading(level, _, classes)) => {
+            Event::Start(Tag::Heading(level, anchors.next(), classes))
+        }
+        event => event,
+    });
+
+    let mut output = String::with_capacity(source.len() * 3 / 2);
+    html::push_html(&mut output, events);
+
+    SANITIZER.clean(&output).to_string()
+}
+
+/// 按出现顺序为每个标题生成唯一的锚点 ID
+fn heading_anchors(events: &[Event]) -> Vec<String> {
+    let mut anchors = Vec::new();
+    let mut used = HashSet::new();
+    let mut heading: Option<String> = None;
+
+    for event in events {
+        match event {
+            Event::Start(Tag::Heading(..)) => heading = Some(String::new()),
+            Event::End(Tag::Heading(..)) => {
+                let base = slugify(&heading.take().unwrap_or_default());
+                let mut anchor = base.clone();
+                let mut index = 0;
+
+                while !used.insert(anchor.clone()) {
+                    index += 1;
+                    anchor = format!("{base}-{index}");
+                }
+
+                anchors.push(anchor);
+            }
+            Event::Text(text) | Event::Code(text) => {
+                if let Some(heading) = heading.as_mut() {
+                    heading.push_str(text);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    anchors
+}
+
+fn slugify(text: &str) -> String {
+    let mut slug = String::with_capacity(text.len());
+
+    for c in text.trim().chars() {
+        if c.is_alphanumeric() {
+            slug.extend(c.to_lowercase());
+        } else if (c.is_whitespace() || c == '-' || c == '_') && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+
+    let slug = slug.trim_matches('-');
+    if slug.is_empty() {
+        "section".into()
+    } else {
+        slug.into()
+    }
+}
+
+/// 缓存的渲染结果及渲染时内容的最后修改时间
+type RenderCacheEntry = (DateTime<Utc>, Arc<str>);
+
+/// 渲染结果缓存，以内容最后修改时间校验缓存是否有效
+#[derive(Debug, Clone, Default)]
+pub struct RenderCache {
+    inner: Arc<RwLock<HashMap<Uuid, RenderCacheEntry>>>,
+}
+
+impl RenderCache {
+    /// 获取缓存的渲染结果，不存在时渲染并写入缓存
+    pub fn get_or_render(&self, id: Uuid, modified_at: DateTime<Utc>, source: &str) -> Arc<str> {
+        if let Some((cached_at, html)) = self.inner.read().unwrap().get(&id) {
+            if *cached_at == modified_at {
+                return html.clone();
+            }
+        }
+
+        let html: Arc<str> = render_markdown(source).into();
+        self.inner
+            .write()
+            .unwrap()
+            .insert(id, (modified_at, html.clone()));
+
+        html
+    }
+
+    pub fn invalidate(&self, id: Uuid) {
+        self.inner.write().unwrap().remove(&id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_markdown() {
+        let html = render_markdown(
+            "# Hello World\n\n## Hello World\n\n| a | b |\n|:-:|---|\n| 1 | 2 |\n\n- [x] done\n- [ ] todo\n\nText[^1]\n\n[^1]: note\n",
+        );
+
+        assert!(html.contains(r#"<h1 id="hello-world">Hello World</h1>"#));
+        assert!(html.contains(r#"<h2 id="hello-world-1">Hello World</h2>"#));
+        assert!(html.contains(r#"<th style="text-align: center">a</th>"#));
+        assert!(html.contains(r#"<input disabled="" type="checkbox" checked="">"#));
+        assert!(html.contains(r##"<sup class="footnote-reference"><a href="#1""##));
+        assert!(html.contains(r#"<div class="footnote-definition" id="1">"#));
+    }
+
+    #[test]
+    fn test_render_markdown_sanitize() {
+        let html = render_markdown(
+            "<script>alert(1)</script>\n\n[x](javascript:alert(1))\n\n```rust\nfn main() {}\n```\n\n<p style=\"color: red\" onclick=\"x\">p</p>",
+        );
+
+        assert!(!html.contains("<script"));
+        assert!(!html.contains("javascript:"));
+        assert!(!html.contains("onclick"));
+        assert!(!html.contains("color"));
+        assert!(html.contains(r#"<code class="language-rust">"#));
+    }
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("  Hello, World!  "), "hello-world");
+        assert_eq!(slugify("你好 世界"), "你好-世界");
+        assert_eq!(slugify("!!!"), "section");
+    }
+
+    #[test]
+    fn test_render_cache() {
+        let cache = RenderCache::default();
+        let id = Uuid::new_v4();
+        let now = Utc::now();
+
+        assert_eq!(&*cache.get_or_render(id, now, "*a*"), "<p><em>a</em></p>\n");
+        // 修改时间未变化时使用缓存
+        assert_eq!(&*cache.get_or_render(id, now, "*b*"), "<p><em>a</em></p>\n");
+        assert_eq!(
+            &*cache.get_or_render(id, now + chrono::Duration::seconds(1), "*b*"),
+            "<p><em>b</em></p>\n"
+        );
+
+        cache.invalidate(id);
+        assert_eq!(
+            &*cache.get_or_render(id, now + chrono::Duration::seconds(1), "*c*"),
+            "<p><em>c</em></p>\n"
+        );
+    }
+}
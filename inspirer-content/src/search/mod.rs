@@ -0,0 +1,387 @@
+use std::{
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+use tantivy::{
+    collector::{Count, TopDocs},
+    directory::MmapDirectory,
+    doc,
+    query::{BooleanQuery, Occur, QueryParser, TermQuery},
+    schema::{
+        Field, IndexRecordOption, Schema, TextFieldIndexing, TextOptions, Value, INDEXED, STORED,
+        STRING,
+    },
+    snippet::SnippetGenerator,
+    Index, IndexReader, IndexWriter, ReloadPolicy, TantivyDocument, Term,
+};
+use uuid::Uuid;
+
+use crate::{error::InspirerContentResult, model::content::Content};
+
+use self::tokenizer::{MixedTokenizer, TOKENIZER_NAME};
+
+pub mod tokenizer;
+
+/// 索引写入缓冲区大小
+const WRITER_MEMORY_BUDGET: usize = 20_000_000;
+/// 摘要片段的最大长度（字符数）
+const SNIPPET_MAX_CHARS: usize = 160;
+
+#[derive(Clone, Copy)]
+struct SearchFields {
+    id: Field,
+    title: Field,
+    description: Field,
+    keywords: Field,
+    body: Field,
+    visible: Field,
+}
+
+/// 待写入索引的内容
+#[derive(Debug, Clone)]
+pub struct SearchDocument {
+    pub id: Uuid,
+    pub title: String,
+    pub description: String,
+    pub keywords: String,
+    pub body: String,
+    /// 是否对公开搜索可见：已发布、未隐藏且未删除
+    pub visible: bool,
+}
+
+impl From<&Content> for SearchDocument {
+    fn from(content: &Content) -> Self {
+        let meta = &content.meta;
+
+        SearchDocument {
+            id: meta.id,
+            title: meta.title.clone(),
+            description: meta.description.clone(),
+            keywords: meta.keywords.clone(),
+            body: content.entity.body().to_string(),
+            visible: meta.is_publish && meta.is_display && !meta.is_deleted,
+        }
+    }
+}
+
+/// 搜索命中的内容及高亮片段（HTML，关键词以 `<b>` 标记）
+#[derive(Debug, Clone)]
+pub struct SearchHit {
+    pub id: Uuid,
+    pub score: f32,
+    pub title: Option<String>,
+    pub body: Option<String>,
+}
+
+/// 基于 tantivy 的内容全文索引
+#[derive(Clone)]
+pub struct SearchIndex {
+    index: Index,
+    reader: IndexReader,
+    fields: SearchFields,
+    /// 长期持有的索引写入者，首次写入时创建，写入失败后丢弃
+    ///
+    /// 写入者持有索引目录锁，延迟创建以免不写入索引的管理命令与服务冲突。
+    writer: Arc<Mutex<Option<IndexWriter>>>,
+}
+
+impl SearchIndex {
+    /// 打开位于 `path` 的索引，不存在时创建；`path` 为空时使用内存索引
+    pub fn open(path: Option<&Path>) -> InspirerContentResult<Self> {
+        let (schema, fields) = build_schema();
+
+        let index = match path {
+            Some(path) => {
+                std::fs::create_dir_all(path).map_err(tantivy::TantivyError::from)?;
+                let directory = MmapDirectory::open(path).map_err(tantivy::TantivyError::from)?;
+                Index::open_or_create(directory, schema)?
+            }
+            None => Index::create_in_ram(schema),
+        };
+
+        index.tokenizers().register(TOKENIZER_NAME, MixedTokenizer);
+
+        let reader = index
+            .reader_builder()
+            .reload_policy(ReloadPolicy::OnCommitWithDelay)
+            .try_into()?;
+
+        Ok(SearchIndex {
+            index,
+            reader,
+            fields,
+            writer: Arc::new(Mutex::new(None)),
+        })
+    }
+
+    /// 写入或替换内容的索引
+    pub fn upsert(&self, document: SearchDocument) -> InspirerContentResult<()> {
+        self.write(|writer, fields| {
+            writer.delete_term(id_term(fields, document.id));
+            writer.add_document(to_document(fields, document))?;
+            Ok(())
+        })
+    }
+
+    pub fn remove(&self, id: Uuid) -> InspirerContentResult<()> {
+        self.write(|writer, fields| {
+            writer.delete_term(id_term(fields, id));
+            Ok(())
+        })
+    }
+
+    /// 清空索引并写入全部内容，返回写入的数量
+    pub fn rebuild<I>(&self, documents: I) -> InspirerContentResult<usize>
+    where
+        I: IntoIterator<Item = SearchDocument>,
+    {
+        let mut count = 0;
+
+        self.write(|writer, fields| {
+            writer.delete_all_documents()?;
+            for document in documents {
+                writer.add_document(to_document(fields, document))?;
+                count += 1;
+            }
+            Ok(())
+        })?;
+
+        Ok(count)
+    }
+
+    /// 在可见内容中搜索，返回命中结果及命中总数
+    pub fn search(
+        &self,
+        query: &str,
+        offset: usize,
+        limit: usize,
+    ) -> InspirerContentResult<(Vec<SearchHit>, usize)> {
+        let fields = self.fields;
+        let searcher = self.reader.searcher();
+
+        let mut parser = QueryParser::for_index(
+            &self.index,
+            vec![
+                fields.title,
+                fields.description,
+                fields.keywords,
+                fields.body,
+            ],
+        );
+        parser.set_conjunction_by_default();
+        parser.set_field_boost(fields.title, 3.0);
+        parser.set_field_boost(fields.keywords, 2.0);
+
+        // 用户输入的查询语法错误时忽略错误部分
+        let (text_query, _) = parser.parse_query_lenient(query);
+        let query = BooleanQuery::new(vec![
+            (Occur::Must, text_query.box_clone()),
+            (
+                Occur::Must,
+                Box::new(TermQuery::new(
+                    Term::from_field_u64(fields.visible, 1),
+                    IndexRecordOption::Basic,
+                )),
+            ),
+        ]);
+
+        let (top_docs, total) = searcher.search(
+            &query,
+            &(TopDocs::with_limit(limit.max(1)).and_offset(offset), Count),
+        )?;
+
+        let mut title_snippet = SnippetGenerator::create(&searcher, &*text_query, fields.title)?;
+        title_snippet.set_max_num_chars(SNIPPET_MAX_CHARS);
+        let mut body_snippet = SnippetGenerator::create(&searcher, &*text_query, fields.body)?;
+        body_snippet.set_max_num_chars(SNIPPET_MAX_CHARS);
+
+        let mut hits = Vec::with_capacity(top_docs.len());
+        for (score, address) in top_docs {
+            let document: TantivyDocument = searcher.doc(address)?;
+            let id = document
+                .get_first(fields.id)
+                .and_then(|value| value.as_str())
+                .and_then(|id| Uuid::parse_str(id).ok());
+
+            if let Some(id) = id {
+                hits.push(SearchHit {
+                    id,
+                    score,
+                    title: highlight(&title_snippet, &document),
+                    body: highlight(&body_snippet, &document),
+                });
+            }
+        }
+
+        Ok((hits, total))
+    }
+
+    /// 执行写入操作并提交
+    ///
+    /// 提交会阻塞当前线程，异步上下文中应在阻塞线程池中调用。
+    fn write<F>(&self, op: F) -> InspirerContentResult<()>
+    where
+        F: FnOnce(&mut IndexWriter, SearchFields) -> tantivy::Result<()>,
+    {
+        let mut guard = match self.writer.lock() {
+            Ok(guard) => guard,
+            // 其他写入过程中发生 panic，丢弃其未提交的修改
+            Err(poisoned) => {
+                let mut guard = poisoned.into_inner();
+                *guard = None;
+                self.writer.clear_poison();
+                guard
+            }
+        };
+
+        let writer = match &mut *guard {
+            Some(writer) => writer,
+            slot @ None => slot.insert(
+                self.index
+                    .writer_with_num_threads(1, WRITER_MEMORY_BUDGET)?,
+            ),
+        };
+
+        let res = op(writer, self.fields).and_then(|()| writer.commit().map(|_| ()));
+        if let Err(err) = res {
+            // 丢弃写入者，未提交的修改随之作废
+            *guard = None;
+            return Err(err.into());
+        }
+
+        self.reader.reload()?;
+
+        Ok(())
+    }
+}
+
+fn build_schema() -> (Schema, SearchFields) {
+    let text = TextOptions::default().set_indexing_options(
+        TextFieldIndexing::default()
+            .set_tokenizer(TOKENIZER_NAME)
+            .set_index_option(IndexRecordOption::WithFreqsAndPositions),
+    );
+    let stored_text = text.clone().set_stored();
+
+    let mut builder = Schema::builder();
+    let fields = SearchFields {
+        id: builder.add_text_field("id", STRING | STORED),
+        title: builder.add_text_field("title", stored_text.clone()),
+        description: builder.add_text_field("description", stored_text.clone()),
+        keywords: builder.add_text_field("keywords", text),
+        body: builder.add_text_field("body", stored_text),
+        visible: builder.add_u64_field("visible", INDEXED),
+    };
+
+    (builder.build(), fields)
+}
+
+fn id_term(fields: SearchFields, id: Uuid) -> Term {
+    Term::from_field_text(fields.id, &id.to_string())
+}
+
+fn to_document(fields: SearchFields, document: SearchDocument) -> TantivyDocument {
+    doc!(
+        fields.id => document.id.to_string(),
+        fields.title => document.title,
+        fields.description => document.description,
+        fields.keywords => document.keywords,
+        fields.body => document.body,
+        fields.visible => u64::from(document.visible),
+    )
+}
+
+/// 生成高亮片段，未命中时返回空
+fn highlight(generator: &SnippetGenerator, document: &TantivyDocument) -> Option<String> {
+    let snippet = generator.snippet_from_doc(document);
+
+    if snippet.highlighted().is_empty() {
+        return None;
+    }
+
+    // 中日韩文字按单字分词，相邻的高亮合并为一段
+    Some(snippet.to_html().replace("</b><b>", ""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn document(title: &str, body: &str, visible: bool) -> SearchDocument {
+        SearchDocument {
+            id: Uuid::new_v4(),
+            title: title.into(),
+            description: String::new(),
+            keywords: String::new(),
+            body: body.into(),
+            visible,
+        }
+    }
+
+    #[test]
+    fn test_search_index() {
+        let index = SearchIndex::open(None).unwrap();
+        let rust = document("Rust 异步编程", "使用 tokio 编写异步程序", true);
+        let hidden = document("隐藏的异步内容", "draft", false);
+        index.upsert(rust.clone()).unwrap();
+        index.upsert(hidden.clone()).unwrap();
+
+        let (hits, total) = index.search("异步", 0, 10).unwrap();
+        assert_eq!(total, 1);
+        assert_eq!(hits[0].id, rust.id);
+        assert_eq!(hits[0].title.as_deref(), Some("Rust <b>异步</b>编程"));
+        assert_eq!(
+            hits[0].body.as_deref(),
+            Some("使用 tokio 编写<b>异步</b>程序")
+        );
+
+        let (hits, _) = index.search("TOKIO", 0, 10).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert!(hits[0].title.is_none());
+
+        index
+            .upsert(SearchDocument {
+                body: "使用 async-std".into(),
+                ..rust.clone()
+            })
+            .unwrap();
+        assert_eq!(index.search("tokio", 0, 10).unwrap().1, 0);
+
+        index.remove(rust.id).unwrap();
+        assert_eq!(index.search("异步", 0, 10).unwrap().1, 0);
+
+        assert_eq!(index.rebuild(vec![rust, hidden]).unwrap(), 2);
+        assert_eq!(index.search("异步", 0, 10).unwrap().1, 1);
+    }
+
+    #[test]
+    fn test_write_failure() {
+        let index = SearchIndex::open(None).unwrap();
+        let rust = document("Rust", "", true);
+        index.upsert(rust.clone()).unwrap();
+
+        // 写入失败时丢弃未提交的修改
+        let res = index.write(|writer, fields| {
+            writer.delete_term(id_term(fields, rust.id));
+            Err(tantivy::TantivyError::SystemError("failed".into()))
+        });
+        assert!(res.is_err());
+        assert_eq!(index.search("rust", 0, 10).unwrap().1, 1);
+
+        // 写入过程中 panic 后仍可继续写入
+        let cloned = index.clone();
+        let res = std::thread::spawn(move || {
+            cloned.write(|writer, fields| {
+                writer.delete_term(id_term(fields, rust.id));
+                panic!("write panicked");
+            })
+        })
+        .join();
+        assert!(res.is_err());
+        assert_eq!(index.search("rust", 0, 10).unwrap().1, 1);
+
+        index.remove(rust.id).unwrap();
+        assert_eq!(index.search("rust", 0, 10).unwrap().1, 0);
+    }
+}
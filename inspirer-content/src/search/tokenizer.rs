@@ -0,0 +1,109 @@
+use tantivy::tokenizer::{Token, TokenStream, Tokenizer};
+
+/// 分词器名称
+pub const TOKENIZER_NAME: &str = "inspirer";
+
+/// 支持中日韩文字的简单分词器
+///
+/// 中日韩文字按单字切分（查询时由短语查询保证词序），其他文字按字母数字连续片段切分，
+/// 所有词项均转为小写。
+#[derive(Debug, Clone, Default)]
+pub struct MixedTokenizer;
+
+pub struct MixedTokenStream {
+    tokens: Vec<Token>,
+    index: usize,
+}
+
+impl Tokenizer for MixedTokenizer {
+    type TokenStream<'a> = MixedTokenStream;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> Self::TokenStream<'a> {
+        MixedTokenStream {
+            tokens: tokenize(text),
+            index: 0,
+        }
+    }
+}
+
+impl TokenStream for MixedTokenStream {
+    fn advance(&mut self) -> bool {
+        self.index += 1;
+        self.index <= self.tokens.len()
+    }
+
+    fn token(&self) -> &Token {
+        &self.tokens[self.index - 1]
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        &mut self.tokens[self.index - 1]
+    }
+}
+
+fn tokenize(text: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut word_start: Option<usize> = None;
+
+    let push = |tokens: &mut Vec<Token>, from: usize, to: usize| {
+        tokens.push(Token {
+            offset_from: from,
+            offset_to: to,
+            position: tokens.len(),
+            text: text[from..to].to_lowercase(),
+            position_length: 1,
+        });
+    };
+
+    for (offset, c) in text.char_indices() {
+        let is_cjk = is_cjk(c);
+
+        if is_cjk || !c.is_alphanumeric() {
+            if let Some(start) = word_start.take() {
+                push(&mut tokens, start, offset);
+            }
+        }
+
+        if is_cjk {
+            push(&mut tokens, offset, offset + c.len_utf8());
+        } else if c.is_alphanumeric() && word_start.is_none() {
+            word_start = Some(offset);
+        }
+    }
+
+    if let Some(start) = word_start {
+        push(&mut tokens, start, text.len());
+    }
+
+    tokens
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(c as u32,
+        0x3040..=0x30FF     // 平假名、片假名
+        | 0x3400..=0x4DBF   // CJK 扩展 A
+        | 0x4E00..=0x9FFF   // CJK 统一表意文字
+        | 0xAC00..=0xD7AF   // 谚文音节
+        | 0xF900..=0xFAFF   // CJK 兼容表意文字
+        | 0x20000..=0x2FA1F // CJK 扩展 B 及之后
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts(text: &str) -> Vec<String> {
+        tokenize(text).into_iter().map(|token| token.text).collect()
+    }
+
+    #[test]
+    fn test_tokenize() {
+        assert_eq!(texts("Hello, Rust2022!"), vec!["hello", "rust2022"]);
+        assert_eq!(texts("使用Rust编写"), vec!["使", "用", "rust", "编", "写"]);
+
+        let tokens = tokenize("中 a");
+        assert_eq!((tokens[0].offset_from, tokens[0].offset_to), (0, 3));
+        assert_eq!((tokens[1].offset_from, tokens[1].position), (4, 1));
+    }
+}
@@ -0,0 +1,276 @@
+use std::{future::Future, pin::Pin};
+
+use sea_orm::{ConnectionTrait, DatabaseTransaction, TransactionTrait};
+
+use crate::{
+    dao::audit::AuditLogDao,
+    error::{Error, InspirerContentResult},
+    manager::Manager,
+    model::{
+        audit::{AuditLogCondition, AuditLogModel, NewAuditLog},
+        paginate::{Paginated, Pagination},
+        user::UserModel,
+    },
+    util::uuid::generate_v1_uuid,
+};
+
+/// User-Agent 最大长度，与会话及审计日志的数据库字段长度一致
+pub(crate) const MAX_USER_AGENT_LENGTH: usize = 500;
+
+#[async_trait::async_trait]
+pub trait AuditService {
+    async fn record_audit(&self, log: NewAuditLog) -> InspirerContentResult<()>;
+    /// 按时间倒序查询审计日志，同时返回执行操作的用户
+    async fn get_audit_logs(
+        &self,
+        condition: AuditLogCondition,
+        pagination: Pagination,
+    ) -> InspirerContentResult<Paginated<(AuditLogModel, Option<UserModel>)>>;
+}
+
+#[async_trait::async_trait]
+impl AuditService for Manager {
+    async fn record_audit(&self, log: NewAuditLog) -> InspirerContentResult<()> {
+        write_audit_log(&self.database, log).await
+    }
+
+    async fn get_audit_logs(
+        &self,
+        condition: AuditLogCondition,
+        pagination: Pagination,
+    ) -> InspirerContentResult<Paginated<(AuditLogModel, Option<UserModel>)>> {
+        self.database.get_audit_logs(condition, pagination).await
+    }
+}
+
+impl Manager {
+    /// 在同一事务中执行操作并写入审计日志，操作与日志同时提交或回滚
+    pub(crate) async fn audited<T, F>(
+        &self,
+        audit: Option<NewAuditLog>,
+        op: F,
+    ) -> InspirerContentResult<T>
+    where
+        T: Send,
+        F: for<'c> FnOnce(
+                &'c DatabaseTransaction,
+            )
+                -> Pin<Box<dyn Future<Output = InspirerContentResult<T>> + Send + 'c>>
+            + Send
+            + 'static,
+    {
+        self.database
+            .transaction::<_, T, Error>(|trx| {
+                Box::pin(async move {
+                    let res = op(trx).await?;
+                    if let Some(log) = audit {
+                        write_audit_log(trx, log).await?;
+                    }
+                    Ok(res)
+                })
+            })
+            .await
+            .map_err(Into::into)
+    }
+}
+
+pub(crate) async fn write_audit_log<C: ConnectionTrait>(
+    conn: &C,
+    mut log: NewAuditLog,
+) -> InspirerContentResult<()> {
+    log.user_agent = log.user_agent.chars().take(MAX_USER_AGENT_LENGTH).collect();
+
+    conn.create_audit_log(generate_v1_uuid(), log).await
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Utc};
+    use sea_orm::Statement;
+
+    use super::*;
+    use crate::{
+        enumerate::audit::{AuditAction, AuditTargetType},
+        manager::tests::sqlite_manager,
+        model::user::NewUser,
+        service::{session::SessionService, user::UserService},
+        util::uuid::generate_v4_uuid,
+    };
+
+    #[tokio::test]
+    async fn test_audit_log() {
+        let manager = sqlite_manager().await;
+        let (user_id, _) = manager
+            .create_user_simple(
+                NewUser {
+                    username: "chongyi".into(),
+                    password: "password".into(),
+                    ..Default::default()
+                },
+                None,
+            )
+            .await
+            .unwrap();
+        let content_id = generate_v4_uuid();
+        let access_token_id = generate_v4_uuid();
+
+        manager
+            .record_audit(NewAuditLog {
+                ip: Some("127.0.0.1".into()),
+                user_agent: "a".repeat(600),
+                ..NewAuditLog::new(AuditAction::LoginFailed)
+                    .detail(serde_json::json!({ "username": "chongyi" }))
+            })
+            .await
+            .unwrap();
+        manager
+            .record_audit(NewAuditLog::new(AuditAction::Login).actor(user_id))
+            .await
+            .unwrap();
+        manager
+            .record_audit(
+                NewAuditLog::new(AuditAction::ContentForceDeleted)
+                    .actor(user_id)
+                    .access_token(access_token_id)
+                    .target(AuditTargetType::Content, content_id),
+            )
+            .await
+            .unwrap();
+
+        let logs = manager
+            .get_audit_logs(Default::default(), Pagination::default())
+            .await
+            .unwrap();
+        assert_eq!(logs.total, 3);
+        // 按时间倒序
+        assert_eq!(logs.data[0].0.action, AuditAction::ContentForceDeleted);
+        assert_eq!(logs.data[0].1.as_ref().unwrap().username, "chongyi");
+        assert_eq!(logs.data[0].0.access_token_id, Some(access_token_id));
+        assert!(logs.data[1].0.access_token_id.is_none());
+        assert!(logs.data[2].1.is_none());
+        assert_eq!(logs.data[2].0.user_agent.len(), MAX_USER_AGENT_LENGTH);
+        assert_eq!(logs.data[2].0.ip.as_deref(), Some("127.0.0.1"));
+
+        let logs = manager
+            .get_audit_logs(
+                AuditLogCondition {
+                    actor_id: Some(user_id),
+                    ..Default::default()
+                },
+                Pagination::default(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(logs.total, 2);
+
+        let logs = manager
+            .get_audit_logs(
+                AuditLogCondition {
+                    target_id: Some(content_id),
+                    action: Some(AuditAction::ContentForceDeleted),
+                    ..Default::default()
+                },
+                Pagination::default(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(logs.total, 1);
+        assert_eq!(logs.data[0].0.target_type, Some(AuditTargetType::Content));
+
+        let logs = manager
+            .get_audit_logs(
+                AuditLogCondition {
+                    since: Some(Utc::now() + Duration::minutes(1)),
+                    ..Default::default()
+                },
+                Pagination::default(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(logs.total, 0);
+    }
+
+    #[tokio::test]
+    async fn test_audited_rollback() {
+        let manager = sqlite_manager().await;
+        let (user_id, _) = manager
+            .create_user_simple(
+                NewUser {
+                    username: "chongyi".into(),
+                    password: "password".into(),
+                    ..Default::default()
+                },
+                None,
+            )
+            .await
+            .unwrap();
+        // 操作与审计日志同时提交
+        manager
+            .set_user_disabled(
+                user_id,
+                true,
+                Some(NewAuditLog::new(AuditAction::UserDisabled)),
+            )
+            .await
+            .unwrap();
+        assert!(manager
+            .get_user_by_id(user_id)
+            .await
+            .unwrap()
+            .unwrap()
+            .is_disabled());
+        let logs = manager
+            .get_audit_logs(Default::default(), Pagination::default())
+            .await
+            .unwrap();
+        assert_eq!(logs.total, 1);
+        assert_eq!(logs.data[0].0.action, AuditAction::UserDisabled);
+
+        manager
+            .set_user_disabled(user_id, false, None)
+            .await
+            .unwrap();
+        let issued = manager
+            .create_session(user_id, "test".into(), Utc::now() + Duration::days(1), None)
+            .await
+            .unwrap();
+
+        // 审计日志写入失败时操作一并回滚
+        manager
+            .database
+            .execute(Statement::from_string(
+                manager.database.get_database_backend(),
+                "DROP TABLE audit_logs".into(),
+            ))
+            .await
+            .unwrap();
+        assert!(manager
+            .set_user_disabled(
+                user_id,
+                true,
+                Some(NewAuditLog::new(AuditAction::UserDisabled))
+            )
+            .await
+            .is_err());
+        assert!(!manager
+            .get_user_by_id(user_id)
+            .await
+            .unwrap()
+            .unwrap()
+            .is_disabled());
+
+        // 刷新令牌未被轮换，再次使用不会被视为令牌泄露
+        assert!(manager
+            .refresh_session(
+                issued.refresh_token.clone(),
+                Utc::now() + Duration::days(1),
+                Some(NewAuditLog::new(AuditAction::TokenRefreshed)),
+            )
+            .await
+            .is_err());
+        manager
+            .refresh_session(issued.refresh_token, Utc::now() + Duration::days(1), None)
+            .await
+            .unwrap();
+    }
+}
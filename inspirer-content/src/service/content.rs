@@ -1,17 +1,29 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Utc};
 use sea_orm::TransactionTrait;
 use strum::VariantNames;
 use uuid::Uuid;
 
 use crate::{
-    dao::content::{ContentDao, ContentUpdateLogDao},
-    entity::{content_entities, contents, users},
+    dao::{
+        content::{ContentDao, ContentUpdateLogDao},
+        taxonomy::{CategoryDao, TagDao},
+    },
+    entity::{content_entities, content_update_logs, contents, users},
     enumerate::content::ContentType,
     error::{Error, InspirerContentResult},
     manager::Manager,
     model::{
-        content::{Content, ContentConfig, GetListCondition, NewContent, UpdateContent},
+        audit::NewAuditLog,
+        content::{
+            BulkContentAction, Content, ContentConfig, GetListCondition, NewContent, UpdateContent,
+        },
         paginate::{Paginated, Pagination},
+        revision::{ContentDiff, ContentRevision, ContentSnapshot},
+        taxonomy::normalize_terms,
     },
+    service::audit::write_audit_log,
     util::uuid::generate_v1_uuid,
 };
 
@@ -33,21 +45,104 @@ pub trait ContentService {
     }
     async fn find_content_by_id(&self, id: Uuid) -> InspirerContentResult<Content>;
     async fn find_content_by_name(&self, name: String) -> InspirerContentResult<Content>;
+    /// 创建内容，`create_terms` 为 `false` 时不会自动创建不存在的标签与分类
     async fn create_content(
         &self,
         owner_id: Uuid,
         new_content: NewContent,
+        create_terms: bool,
     ) -> InspirerContentResult<Content>;
+    /// 更新内容，`create_terms` 为 `false` 时不会自动创建不存在的标签与分类
     async fn update_content(
         &self,
         user_id: Uuid,
         content_id: Uuid,
         update_content: UpdateContent,
+        create_terms: bool,
+    ) -> InspirerContentResult<()>;
+    /// 将内容正文渲染为 HTML，结果会被缓存直至内容被更新
+    fn render_content(&self, content: &Content) -> String;
+    async fn publish_content(
+        &self,
+        id: Uuid,
+        audit: Option<NewAuditLog>,
+    ) -> InspirerContentResult<()>;
+    /// 在指定时间发布内容：时间晚于当前时间时仅预定发布，否则立即发布并以该时间作为发布时间
+    ///
+    /// 已发布的内容不能预定发布，返回 `ContentAlreadyPublished`。
+    async fn publish_content_at(
+        &self,
+        id: Uuid,
+        publish_at: DateTime<Utc>,
+        audit: Option<NewAuditLog>,
+    ) -> InspirerContentResult<()>;
+    async fn publish_scheduled_contents(&self) -> InspirerContentResult<u64>;
+    async fn unpublish_content(
+        &self,
+        id: Uuid,
+        audit: Option<NewAuditLog>,
+    ) -> InspirerContentResult<()>;
+    async fn delete_content(
+        &self,
+        id: Uuid,
+        force: bool,
+        audit: Option<NewAuditLog>,
+    ) -> InspirerContentResult<()>;
+    async fn revert_deleted_content(
+        &self,
+        id: Uuid,
+        audit: Option<NewAuditLog>,
+    ) -> InspirerContentResult<()>;
+    /// 设置内容是否展示，隐藏的内容不会出现在公开列表及搜索结果中
+    async fn set_display(
+        &self,
+        id: Uuid,
+        display: bool,
+        audit: Option<NewAuditLog>,
     ) -> InspirerContentResult<()>;
-    async fn publish_content(&self, id: Uuid) -> InspirerContentResult<()>;
-    async fn unpublish_content(&self, id: Uuid) -> InspirerContentResult<()>;
-    async fn delete_content(&self, id: Uuid, force: bool) -> InspirerContentResult<()>;
-    async fn revert_deleted_content(&self, id: Uuid) -> InspirerContentResult<()>;
+    /// 在同一事务中对多条内容执行批量操作，按传入顺序返回每条内容的处理结果（重复的 ID 仅处理一次）
+    ///
+    /// 内容不存在时仅记录在该内容的结果中，数据库错误则回滚整个事务。恢复操作仅作用于已删除的内容，
+    /// 其他操作仅作用于未删除的内容，不满足条件的内容视为不存在。处理成功的内容在同一事务中写入
+    /// `audits` 中对应的审计日志。
+    async fn bulk_update_contents(
+        &self,
+        action: BulkContentAction,
+        ids: Vec<Uuid>,
+        audits: HashMap<Uuid, NewAuditLog>,
+    ) -> InspirerContentResult<Vec<(Uuid, InspirerContentResult<()>)>>;
+    /// 获取内容的全部修订记录（按时间正序），修订记录无法解析时返回错误
+    async fn get_content_revisions(
+        &self,
+        content_id: Uuid,
+    ) -> InspirerContentResult<Vec<ContentRevision>>;
+    async fn find_content_revision(
+        &self,
+        content_id: Uuid,
+        revision_id: Uuid,
+    ) -> InspirerContentResult<ContentRevision>;
+    /// 回放修订记录，重建内容在指定修订版本时的完整状态
+    async fn get_content_snapshot(
+        &self,
+        content_id: Uuid,
+        revision_id: Uuid,
+    ) -> InspirerContentResult<ContentSnapshot>;
+    /// 将内容恢复到指定修订版本，恢复操作本身会作为一次新的更新被记录
+    async fn restore_revision(
+        &self,
+        user_id: Uuid,
+        content_id: Uuid,
+        revision_id: Uuid,
+        create_terms: bool,
+        audit: Option<NewAuditLog>,
+    ) -> InspirerContentResult<()>;
+    /// 对比两个修订版本，`from` 为空时与 `to` 的上一个版本对比
+    async fn diff_revisions(
+        &self,
+        content_id: Uuid,
+        from: Option<Uuid>,
+        to: Uuid,
+    ) -> InspirerContentResult<ContentDiff>;
 }
 
 #[async_trait::async_trait]
@@ -60,16 +155,22 @@ impl ContentService for Manager {
         self.database.get_list(condition, pagination).await
     }
     async fn find_content_by_id(&self, id: Uuid) -> InspirerContentResult<Content> {
-        convert_content(self.database.find_content_by_id(id).await?)
+        let content = convert_content(self.database.find_content_by_id(id).await?)?;
+        self.load_content_terms(content).await
     }
     async fn find_content_by_name(&self, name: String) -> InspirerContentResult<Content> {
-        convert_content(self.database.find_content_by_name(name).await?)
+        let content = convert_content(self.database.find_content_by_name(name).await?)?;
+        self.load_content_terms(content).await
     }
     async fn create_content(
         &self,
         owner_id: Uuid,
-        new_content: NewContent,
+        mut new_content: NewContent,
+        create_terms: bool,
     ) -> InspirerContentResult<Content> {
+        new_content.meta.tags = normalize_terms(&new_content.meta.tags);
+        new_content.meta.categories = normalize_terms(&new_content.meta.categories);
+
         let id = generate_v1_uuid();
         let update_log_id = generate_v1_uuid();
 
@@ -78,6 +179,10 @@ impl ContentService for Manager {
                 Box::pin(async move {
                     trx.create_content(id, owner_id, &new_content).await?;
                     trx.create_content_entity(id, &new_content).await?;
+                    trx.set_content_tags(id, &new_content.meta.tags, create_terms)
+                        .await?;
+                    trx.set_content_categories(id, &new_content.meta.categories, create_terms)
+                        .await?;
                     trx.create_content_update_log(update_log_id, owner_id, id, new_content.into())
                         .await?;
                     Ok(())
@@ -85,6 +190,7 @@ impl ContentService for Manager {
             })
             .await?;
 
+        self.sync_search_index(id).await;
         self.find_content_by_id(id).await
     }
     async fn update_content(
@@ -92,68 +198,378 @@ impl ContentService for Manager {
         user_id: Uuid,
         content_id: Uuid,
         update_content: UpdateContent,
+        create_terms: bool,
     ) -> InspirerContentResult<()> {
-        let update_log_id = generate_v1_uuid();
+        self.update_content_audited(user_id, content_id, update_content, create_terms, None)
+            .await
+    }
 
-        self.database
-            .transaction::<_, (), Error>(|trx| {
+    fn render_content(&self, content: &Content) -> String {
+        self.render_cache
+            .get_or_render(
+                content.meta.id,
+                content.meta.modified_at,
+                content.entity.body(),
+            )
+            .to_string()
+    }
+
+    async fn get_content_service_config(&self) -> InspirerContentResult<ContentConfig> {
+        Ok(ContentConfig {
+            content_support_type: ContentType::VARIANTS,
+        })
+    }
+
+    async fn publish_content(
+        &self,
+        id: Uuid,
+        audit: Option<NewAuditLog>,
+    ) -> InspirerContentResult<()> {
+        let now = Utc::now();
+        self.audited(audit, move |trx| {
+            Box::pin(async move { trx.publish_content(id, now).await })
+        })
+        .await?;
+        self.sync_search_index(id).await;
+
+        Ok(())
+    }
+
+    async fn publish_content_at(
+        &self,
+        id: Uuid,
+        publish_at: DateTime<Utc>,
+        audit: Option<NewAuditLog>,
+    ) -> InspirerContentResult<()> {
+        let schedule = publish_at > Utc::now();
+        let res = self
+            .audited(audit, move |trx| {
                 Box::pin(async move {
-                    trx.update_content(content_id, &update_content).await?;
-                    trx.update_content_entity(content_id, &update_content)
-                        .await?;
-                    trx.create_content_update_log(
-                        update_log_id,
-                        user_id,
-                        content_id,
-                        update_content,
-                    )
-                    .await?;
+                    if !schedule {
+                        return trx.publish_content(id, publish_at).await;
+                    }
+
+                    if !trx.schedule_content(id, publish_at).await? {
+                        return Err(Error::ContentAlreadyPublished);
+                    }
 
                     Ok(())
                 })
             })
-            .await?;
+            .await;
+
+        if let Err(Error::ContentAlreadyPublished) = res {
+            self.find_content_by_id(id).await?;
+        }
+        res?;
+        self.sync_search_index(id).await;
 
         Ok(())
     }
 
-    async fn get_content_service_config(&self) -> InspirerContentResult<ContentConfig> {
-        Ok(ContentConfig {
-            content_support_type: ContentType::VARIANTS,
+    async fn publish_scheduled_contents(&self) -> InspirerContentResult<u64> {
+        let (published, ids) = self.database.publish_scheduled_contents(Utc::now()).await?;
+        for id in ids.iter() {
+            self.sync_search_index(*id).await;
+        }
+
+        Ok(published)
+    }
+
+    async fn unpublish_content(
+        &self,
+        id: Uuid,
+        audit: Option<NewAuditLog>,
+    ) -> InspirerContentResult<()> {
+        self.audited(audit, move |trx| {
+            Box::pin(async move { trx.unpublish_content(id).await })
         })
+        .await?;
+        self.sync_search_index(id).await;
+
+        Ok(())
     }
 
-    async fn publish_content(&self, id: Uuid) -> InspirerContentResult<()> {
-        self.database.publish_content(id).await
+    async fn delete_content(
+        &self,
+        id: Uuid,
+        force: bool,
+        audit: Option<NewAuditLog>,
+    ) -> InspirerContentResult<()> {
+        self.audited(audit, move |trx| {
+            Box::pin(async move {
+                if !force {
+                    return trx.delete_content(id).await;
+                }
+
+                trx.force_delete_content(id).await?;
+                trx.delete_content_entity(id).await?;
+                trx.delete_content_tags(id).await?;
+                trx.delete_content_categories(id).await
+            })
+        })
+        .await?;
+
+        self.render_cache.invalidate(id);
+        self.sync_search_index(id).await;
+
+        Ok(())
+    }
+
+    async fn revert_deleted_content(
+        &self,
+        id: Uuid,
+        audit: Option<NewAuditLog>,
+    ) -> InspirerContentResult<()> {
+        self.audited(audit, move |trx| {
+            Box::pin(async move { trx.revert_deleted_content(id).await })
+        })
+        .await?;
+        self.sync_search_index(id).await;
+
+        Ok(())
     }
 
-    async fn unpublish_content(&self, id: Uuid) -> InspirerContentResult<()> {
-        self.database.unpublish_content(id).await
+    async fn set_display(
+        &self,
+        id: Uuid,
+        display: bool,
+        audit: Option<NewAuditLog>,
+    ) -> InspirerContentResult<()> {
+        self.audited(audit, move |trx| {
+            Box::pin(async move { trx.set_content_display(id, display).await })
+        })
+        .await?;
+        self.sync_search_index(id).await;
+
+        Ok(())
     }
 
-    async fn delete_content(&self, id: Uuid, force: bool) -> InspirerContentResult<()> {
-        if force {
-            self.database
-                .transaction::<_, (), Error>(|trx| {
-                    Box::pin(async move {
-                        trx.force_delete_content(id).await?;
-                        trx.delete_content_entity(id).await?;
-                        Ok(())
-                    })
+    async fn bulk_update_contents(
+        &self,
+        action: BulkContentAction,
+        mut ids: Vec<Uuid>,
+        mut audits: HashMap<Uuid, NewAuditLog>,
+    ) -> InspirerContentResult<Vec<(Uuid, InspirerContentResult<()>)>> {
+        let mut seen = HashSet::new();
+        ids.retain(|id| seen.insert(*id));
+
+        let now = Utc::now();
+        let results = self
+            .database
+            .transaction::<_, _, Error>(|trx| {
+                Box::pin(async move {
+                    // 仅恢复操作作用于已删除的内容，其他操作作用于未删除的内容
+                    let exists = trx
+                        .get_contents_by_ids(ids.clone())
+                        .await?
+                        .into_iter()
+                        .filter(|(content, _)| {
+                            content.is_deleted == (action == BulkContentAction::Restore)
+                        })
+                        .map(|(content, _)| content.id)
+                        .collect::<HashSet<_>>();
+
+                    let mut results = Vec::with_capacity(ids.len());
+                    for id in ids {
+                        if !exists.contains(&id) {
+                            results.push((id, Err(Error::ContentNotFound)));
+                            continue;
+                        }
+
+                        match action {
+                            BulkContentAction::Publish => trx.publish_content(id, now).await?,
+                            BulkContentAction::Unpublish => trx.unpublish_content(id).await?,
+                            BulkContentAction::Hide => trx.set_content_display(id, false).await?,
+                            BulkContentAction::Show => trx.set_content_display(id, true).await?,
+                            BulkContentAction::Delete => trx.delete_content(id).await?,
+                            BulkContentAction::Restore => trx.revert_deleted_content(id).await?,
+                        }
+                        if let Some(log) = audits.remove(&id) {
+                            write_audit_log(trx, log).await?;
+                        }
+                        results.push((id, Ok(())));
+                    }
+
+                    Ok(results)
                 })
-                .await?;
-        } else {
-            self.database.delete_content(id).await?;
+            })
+            .await?;
+
+        for (id, _) in results.iter().filter(|(_, result)| result.is_ok()) {
+            if action == BulkContentAction::Delete {
+                self.render_cache.invalidate(*id);
+            }
+            self.sync_search_index(*id).await;
         }
+        tracing::info!("Bulk {action:?} {} contents", results.len());
+
+        Ok(results)
+    }
+
+    async fn get_content_revisions(
+        &self,
+        content_id: Uuid,
+    ) -> InspirerContentResult<Vec<ContentRevision>> {
+        self.database
+            .find_content_by_id(content_id)
+            .await?
+            .ok_or(Error::ContentNotFound)?;
+
+        let mut revisions = self
+            .database
+            .get_content_update_logs(content_id)
+            .await?
+            .into_iter()
+            .map(convert_revision)
+            .collect::<InspirerContentResult<Vec<_>>>()?;
+
+        // 数据库时间戳精度为秒，同一秒内的修订按 UUID v1 中的时间戳排序
+        revisions.sort_by_key(|revision| {
+            (
+                revision.created_at,
+                revision
+                    .id
+                    .get_timestamp()
+                    .map(|ts| ts.to_rfc4122())
+                    .unwrap_or_default(),
+            )
+        });
+
+        Ok(revisions)
+    }
+
+    async fn find_content_revision(
+        &self,
+        content_id: Uuid,
+        revision_id: Uuid,
+    ) -> InspirerContentResult<ContentRevision> {
+        self.get_content_revisions(content_id)
+            .await?
+            .into_iter()
+            .find(|revision| revision.id == revision_id)
+            .ok_or(Error::ContentRevisionNotFound)
+    }
+
+    async fn get_content_snapshot(
+        &self,
+        content_id: Uuid,
+        revision_id: Uuid,
+    ) -> InspirerContentResult<ContentSnapshot> {
+        let revisions = self.get_content_revisions(content_id).await?;
+        let position = revision_position(&revisions, revision_id)?;
+
+        Ok(snapshot_at(&revisions, position))
+    }
+
+    async fn restore_revision(
+        &self,
+        user_id: Uuid,
+        content_id: Uuid,
+        revision_id: Uuid,
+        create_terms: bool,
+        audit: Option<NewAuditLog>,
+    ) -> InspirerContentResult<()> {
+        let snapshot = self.get_content_snapshot(content_id, revision_id).await?;
+
+        self.update_content_audited(user_id, content_id, snapshot.into(), create_terms, audit)
+            .await
+    }
+
+    async fn diff_revisions(
+        &self,
+        content_id: Uuid,
+        from: Option<Uuid>,
+        to: Uuid,
+    ) -> InspirerContentResult<ContentDiff> {
+        let revisions = self.get_content_revisions(content_id).await?;
+        let to = revision_position(&revisions, to)?;
+
+        let old = match from {
+            Some(from) => snapshot_at(&revisions, revision_position(&revisions, from)?),
+            None if to > 0 => snapshot_at(&revisions, to - 1),
+            None => ContentSnapshot::default(),
+        };
+
+        Ok(old.diff(&snapshot_at(&revisions, to)))
+    }
+}
+
+impl Manager {
+    async fn update_content_audited(
+        &self,
+        user_id: Uuid,
+        content_id: Uuid,
+        mut update_content: UpdateContent,
+        create_terms: bool,
+        audit: Option<NewAuditLog>,
+    ) -> InspirerContentResult<()> {
+        let meta = &mut update_content.meta;
+        meta.tags = meta.tags.as_deref().map(normalize_terms);
+        meta.categories = meta.categories.as_deref().map(normalize_terms);
+
+        let update_log_id = generate_v1_uuid();
+
+        self.audited(audit, move |trx| {
+            Box::pin(async move {
+                trx.update_content(content_id, &update_content).await?;
+                trx.update_content_entity(content_id, &update_content)
+                    .await?;
+                if let Some(tags) = &update_content.meta.tags {
+                    trx.set_content_tags(content_id, tags, create_terms).await?;
+                }
+                if let Some(categories) = &update_content.meta.categories {
+                    trx.set_content_categories(content_id, categories, create_terms)
+                        .await?;
+                }
+                trx.create_content_update_log(update_log_id, user_id, content_id, update_content)
+                    .await
+            })
+        })
+        .await?;
+
+        self.render_cache.invalidate(content_id);
+        self.sync_search_index(content_id).await;
 
         Ok(())
     }
 
-    async fn revert_deleted_content(&self, id: Uuid) -> InspirerContentResult<()> {
-        self.database.revert_deleted_content(id).await
+    async fn load_content_terms(&self, mut content: Content) -> InspirerContentResult<Content> {
+        content.tags = self
+            .database
+            .get_content_tags(content.meta.id)
+            .await?
+            .into_iter()
+            .map(|tag| tag.name)
+            .collect();
+        content.categories = self
+            .database
+            .get_content_categories(content.meta.id)
+            .await?
+            .into_iter()
+            .map(|category| category.name)
+            .collect();
+
+        Ok(content)
     }
 }
 
+fn revision_position(revisions: &[ContentRevision], id: Uuid) -> InspirerContentResult<usize> {
+    revisions
+        .iter()
+        .position(|revision| revision.id == id)
+        .ok_or(Error::ContentRevisionNotFound)
+}
+
+/// 回放至第 `position` 个修订记录（包含）
+fn snapshot_at(revisions: &[ContentRevision], position: usize) -> ContentSnapshot {
+    ContentSnapshot::replay(
+        revisions[..=position]
+            .iter()
+            .map(|revision| &revision.update_data),
+    )
+}
+
 fn convert_content(
     res: Option<(contents::Model, Option<content_entities::Model>)>,
 ) -> InspirerContentResult<Content> {
@@ -165,6 +581,578 @@ fn convert_content(
                     .ok()
             })
             .unwrap_or_default();
-        Content { meta, entity }
+        Content {
+            meta,
+            entity,
+            tags: vec![],
+            categories: vec![],
+        }
     })
 }
+
+fn convert_revision(
+    (log, author): (content_update_logs::Model, Option<users::Model>),
+) -> InspirerContentResult<ContentRevision> {
+    let update_data = serde_json::from_value(log.update_data).map_err(|err| {
+        tracing::error!("Format content update log {} error: {}", log.id, err);
+        Error::FormatError(err)
+    })?;
+
+    Ok(ContentRevision {
+        id: log.id,
+        content_id: log.content_id,
+        author,
+        update_data,
+        created_at: log.created_at,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use sea_orm::{sea_query::Expr, ColumnTrait, EntityTrait, QueryFilter};
+
+    use super::*;
+    use crate::{
+        manager::tests::sqlite_manager,
+        model::{
+            content::{ContentEntity, ContentMeta, UpdateContentMeta},
+            user::NewUser,
+        },
+        service::user::UserService,
+    };
+
+    fn new_content(title: &str, name: Option<&str>) -> NewContent {
+        NewContent {
+            meta: ContentMeta {
+                title: title.into(),
+                name: name.map(Into::into),
+                ..Default::default()
+            },
+            entity: ContentEntity::Post(format!("{title} body")),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_content_lifecycle() {
+        let manager = sqlite_manager().await;
+        let owner_id = generate_v1_uuid();
+
+        let content = manager
+            .create_content(owner_id, new_content("hello", Some("hello-world")), true)
+            .await
+            .unwrap();
+        assert_eq!(content.meta.owner_id, owner_id);
+        assert_eq!(content.meta.content_type, ContentType::Post);
+        assert!(!content.meta.is_publish);
+
+        let found = manager
+            .find_content_by_name("hello-world".into())
+            .await
+            .unwrap();
+        assert_eq!(found.meta.id, content.meta.id);
+
+        manager
+            .update_content(
+                owner_id,
+                content.meta.id,
+                UpdateContent {
+                    meta: UpdateContentMeta {
+                        title: Some("updated".into()),
+                        ..Default::default()
+                    },
+                    entity: Some(ContentEntity::Page("page body".into())),
+                },
+                true,
+            )
+            .await
+            .unwrap();
+
+        let found = manager.find_content_by_id(content.meta.id).await.unwrap();
+        assert_eq!(found.meta.title, "updated");
+        assert_eq!(found.meta.content_type, ContentType::Page);
+        assert!(matches!(found.entity, ContentEntity::Page(ref body) if body == "page body"));
+
+        let list = manager
+            .get_list(GetListCondition::published(), Pagination::default())
+            .await
+            .unwrap();
+        assert_eq!(list.total, 0);
+
+        manager
+            .publish_content(content.meta.id, None)
+            .await
+            .unwrap();
+        let list = manager
+            .get_list(GetListCondition::default(), Pagination::default())
+            .await
+            .unwrap();
+        assert_eq!(list.total, 1);
+        assert!(list.data[0].0.published_at.is_some());
+
+        manager
+            .delete_content(content.meta.id, false, None)
+            .await
+            .unwrap();
+        let deleted = manager
+            .get_deleted_content_list(GetListCondition::default(), Pagination::default())
+            .await
+            .unwrap();
+        assert_eq!(deleted.total, 1);
+
+        manager
+            .revert_deleted_content(content.meta.id, None)
+            .await
+            .unwrap();
+        manager
+            .delete_content(content.meta.id, true, None)
+            .await
+            .unwrap();
+        assert!(matches!(
+            manager.find_content_by_id(content.meta.id).await,
+            Err(Error::ContentNotFound)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_content_display() {
+        let manager = sqlite_manager().await;
+        let content = manager
+            .create_content(generate_v1_uuid(), new_content("hidden", None), true)
+            .await
+            .unwrap();
+        manager
+            .publish_content(content.meta.id, None)
+            .await
+            .unwrap();
+
+        manager
+            .set_display(content.meta.id, false, None)
+            .await
+            .unwrap();
+        let found = manager.find_content_by_id(content.meta.id).await.unwrap();
+        assert!(!found.meta.is_display);
+        let list = manager
+            .get_list(GetListCondition::published(), Pagination::default())
+            .await
+            .unwrap();
+        assert_eq!(list.total, 0);
+        let list = manager
+            .get_list(
+                GetListCondition {
+                    with_hidden: true,
+                    ..GetListCondition::published()
+                },
+                Pagination::default(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(list.total, 1);
+
+        manager
+            .set_display(content.meta.id, true, None)
+            .await
+            .unwrap();
+        let list = manager
+            .get_list(GetListCondition::published(), Pagination::default())
+            .await
+            .unwrap();
+        assert_eq!(list.total, 1);
+    }
+
+    #[tokio::test]
+    async fn test_bulk_update_contents() {
+        let manager = sqlite_manager().await;
+        let owner_id = generate_v1_uuid();
+        let mut ids = vec![];
+        for title in ["first", "second"] {
+            ids.push(
+                manager
+                    .create_content(owner_id, new_content(title, None), true)
+                    .await
+                    .unwrap()
+                    .meta
+                    .id,
+            );
+        }
+        let missing = generate_v1_uuid();
+
+        let results = manager
+            .bulk_update_contents(
+                BulkContentAction::Publish,
+                vec![ids[0], missing, ids[1], ids[0]],
+                HashMap::new(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            results.iter().map(|(id, _)| *id).collect::<Vec<_>>(),
+            vec![ids[0], missing, ids[1]]
+        );
+        assert!(results[0].1.is_ok());
+        assert!(matches!(results[1].1, Err(Error::ContentNotFound)));
+        assert!(results[2].1.is_ok());
+        let list = manager
+            .get_list(GetListCondition::published(), Pagination::default())
+            .await
+            .unwrap();
+        assert_eq!(list.total, 2);
+
+        manager
+            .bulk_update_contents(BulkContentAction::Hide, ids.clone(), HashMap::new())
+            .await
+            .unwrap();
+        let list = manager
+            .get_list(GetListCondition::published(), Pagination::default())
+            .await
+            .unwrap();
+        assert_eq!(list.total, 0);
+
+        manager
+            .bulk_update_contents(BulkContentAction::Delete, ids.clone(), HashMap::new())
+            .await
+            .unwrap();
+        let deleted = manager
+            .get_deleted_content_list(
+                GetListCondition {
+                    with_hidden: true,
+                    ..Default::default()
+                },
+                Pagination::default(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(deleted.total, 2);
+
+        // 已删除的内容不能再次删除或隐藏，未删除的内容不能恢复
+        let results = manager
+            .bulk_update_contents(BulkContentAction::Delete, vec![ids[0]], HashMap::new())
+            .await
+            .unwrap();
+        assert!(matches!(results[0].1, Err(Error::ContentNotFound)));
+        let results = manager
+            .bulk_update_contents(BulkContentAction::Show, vec![ids[0]], HashMap::new())
+            .await
+            .unwrap();
+        assert!(matches!(results[0].1, Err(Error::ContentNotFound)));
+
+        manager
+            .bulk_update_contents(BulkContentAction::Restore, vec![ids[1]], HashMap::new())
+            .await
+            .unwrap();
+        let results = manager
+            .bulk_update_contents(BulkContentAction::Restore, vec![ids[1]], HashMap::new())
+            .await
+            .unwrap();
+        assert!(matches!(results[0].1, Err(Error::ContentNotFound)));
+        manager
+            .bulk_update_contents(BulkContentAction::Show, vec![ids[1]], HashMap::new())
+            .await
+            .unwrap();
+        let list = manager
+            .get_list(GetListCondition::published(), Pagination::default())
+            .await
+            .unwrap();
+        assert_eq!(list.total, 1);
+        assert_eq!(list.data[0].0.id, ids[1]);
+    }
+
+    #[tokio::test]
+    async fn test_scheduled_publish() {
+        let manager = sqlite_manager().await;
+        let owner_id = generate_v1_uuid();
+        let content = manager
+            .create_content(owner_id, new_content("scheduled", None), true)
+            .await
+            .unwrap();
+
+        let publish_at = Utc::now() + chrono::Duration::hours(1);
+        manager
+            .publish_content_at(content.meta.id, publish_at, None)
+            .await
+            .unwrap();
+        let found = manager.find_content_by_id(content.meta.id).await.unwrap();
+        assert!(!found.meta.is_publish);
+        assert_eq!(found.meta.scheduled_at, Some(publish_at));
+
+        assert_eq!(manager.publish_scheduled_contents().await.unwrap(), 0);
+        let list = manager
+            .get_list(GetListCondition::published(), Pagination::default())
+            .await
+            .unwrap();
+        assert_eq!(list.total, 0);
+
+        // 模拟预定时间已到
+        let published = manager
+            .database
+            .publish_scheduled_contents(publish_at)
+            .await
+            .unwrap();
+        assert_eq!(published, (1, vec![content.meta.id]));
+        let found = manager.find_content_by_id(content.meta.id).await.unwrap();
+        assert!(found.meta.is_publish);
+        assert_eq!(found.meta.published_at, Some(publish_at));
+        assert!(found.meta.scheduled_at.is_none());
+
+        // 已发布的内容不能预定发布，且保持发布状态
+        assert!(matches!(
+            manager
+                .publish_content_at(
+                    content.meta.id,
+                    publish_at + chrono::Duration::days(1),
+                    None
+                )
+                .await,
+            Err(Error::ContentAlreadyPublished)
+        ));
+        let found = manager.find_content_by_id(content.meta.id).await.unwrap();
+        assert!(found.meta.is_publish);
+        assert!(found.meta.scheduled_at.is_none());
+        assert!(matches!(
+            manager
+                .publish_content_at(generate_v1_uuid(), publish_at, None)
+                .await,
+            Err(Error::ContentNotFound)
+        ));
+
+        // 取消发布后可重新预定，再次取消发布时清除预定时间
+        manager
+            .unpublish_content(content.meta.id, None)
+            .await
+            .unwrap();
+        manager
+            .publish_content_at(
+                content.meta.id,
+                publish_at + chrono::Duration::days(1),
+                None,
+            )
+            .await
+            .unwrap();
+        let found = manager.find_content_by_id(content.meta.id).await.unwrap();
+        assert!(found.meta.scheduled_at.is_some());
+        manager
+            .unpublish_content(content.meta.id, None)
+            .await
+            .unwrap();
+        let found = manager.find_content_by_id(content.meta.id).await.unwrap();
+        assert!(found.meta.scheduled_at.is_none());
+
+        let backdated = Utc::now() - chrono::Duration::days(1);
+        manager
+            .publish_content_at(content.meta.id, backdated, None)
+            .await
+            .unwrap();
+        let found = manager.find_content_by_id(content.meta.id).await.unwrap();
+        assert!(found.meta.is_publish);
+        assert_eq!(found.meta.published_at, Some(backdated));
+    }
+
+    #[tokio::test]
+    async fn test_content_name_conflict() {
+        let manager = sqlite_manager().await;
+        let owner_id = generate_v1_uuid();
+
+        manager
+            .create_content(owner_id, new_content("first", Some("same")), true)
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            manager
+                .create_content(owner_id, new_content("second", Some("same")), true)
+                .await,
+            Err(Error::DatabaseWriteConflict)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_content_revisions() {
+        let manager = sqlite_manager().await;
+        let (owner_id, _) = manager
+            .create_user_simple(
+                NewUser {
+                    username: "chongyi".into(),
+                    password: "password".into(),
+                    ..Default::default()
+                },
+                None,
+            )
+            .await
+            .unwrap();
+
+        let content = manager
+            .create_content(owner_id, new_content("v1", None), true)
+            .await
+            .unwrap();
+
+        for title in ["v2", "v3"] {
+            manager
+                .update_content(
+                    owner_id,
+                    content.meta.id,
+                    UpdateContent {
+                        meta: UpdateContentMeta {
+                            title: Some(title.into()),
+                            ..Default::default()
+                        },
+                        entity: (title == "v3").then(|| ContentEntity::Post("v3 body".into())),
+                    },
+                    true,
+                )
+                .await
+                .unwrap();
+        }
+
+        let revisions = manager
+            .get_content_revisions(content.meta.id)
+            .await
+            .unwrap();
+        assert_eq!(revisions.len(), 3);
+        assert!(revisions
+            .iter()
+            .all(|revision| revision.author.as_ref().map(|user| user.id) == Some(owner_id)));
+        assert_eq!(revisions[1].update_data.meta.title.as_deref(), Some("v2"));
+        assert!(revisions[1].update_data.entity.is_none());
+
+        let snapshot = manager
+            .get_content_snapshot(content.meta.id, revisions[1].id)
+            .await
+            .unwrap();
+        assert_eq!(snapshot.meta.title, "v2");
+        assert!(matches!(snapshot.entity, ContentEntity::Post(ref body) if body == "v1 body"));
+
+        let snapshot = manager
+            .get_content_snapshot(content.meta.id, revisions[2].id)
+            .await
+            .unwrap();
+        assert_eq!(snapshot.meta.title, "v3");
+        assert!(matches!(snapshot.entity, ContentEntity::Post(ref body) if body == "v3 body"));
+
+        assert!(matches!(
+            manager
+                .find_content_revision(content.meta.id, generate_v1_uuid())
+                .await,
+            Err(Error::ContentRevisionNotFound)
+        ));
+        assert!(matches!(
+            manager.get_content_revisions(generate_v1_uuid()).await,
+            Err(Error::ContentNotFound)
+        ));
+
+        // 修订记录损坏时不能当作空修改回放
+        content_update_logs::Entity::update_many()
+            .filter(content_update_logs::Column::Id.eq(revisions[1].id))
+            .col_expr(
+                content_update_logs::Column::UpdateData,
+                Expr::value(serde_json::json!({ "title": 1 })),
+            )
+            .exec(&manager.database)
+            .await
+            .unwrap();
+        assert!(matches!(
+            manager
+                .restore_revision(owner_id, content.meta.id, revisions[1].id, true, None)
+                .await,
+            Err(Error::FormatError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_restore_revision() {
+        let manager = sqlite_manager().await;
+        let owner_id = generate_v1_uuid();
+
+        let content = manager
+            .create_content(owner_id, new_content("v1", None), true)
+            .await
+            .unwrap();
+        manager
+            .update_content(
+                owner_id,
+                content.meta.id,
+                UpdateContent {
+                    meta: UpdateContentMeta {
+                        title: Some("v2".into()),
+                        description: Some("changed".into()),
+                        name: Some(Some("v2".into())),
+                        ..Default::default()
+                    },
+                    entity: Some(ContentEntity::Page("v2 body".into())),
+                },
+                true,
+            )
+            .await
+            .unwrap();
+
+        let revisions = manager
+            .get_content_revisions(content.meta.id)
+            .await
+            .unwrap();
+        manager
+            .restore_revision(owner_id, content.meta.id, revisions[0].id, true, None)
+            .await
+            .unwrap();
+
+        let restored = manager.find_content_by_id(content.meta.id).await.unwrap();
+        assert_eq!(restored.meta.title, "v1");
+        assert_eq!(restored.meta.description, "");
+        assert!(restored.meta.content_name.is_none());
+        assert_eq!(restored.meta.content_type, ContentType::Post);
+        assert!(matches!(restored.entity, ContentEntity::Post(ref body) if body == "v1 body"));
+
+        let revisions = manager
+            .get_content_revisions(content.meta.id)
+            .await
+            .unwrap();
+        assert_eq!(revisions.len(), 3);
+        assert_eq!(revisions[2].update_data.meta.title.as_deref(), Some("v1"));
+        assert_eq!(revisions[2].update_data.meta.name, Some(None));
+    }
+
+    #[tokio::test]
+    async fn test_diff_revisions() {
+        let manager = sqlite_manager().await;
+        let owner_id = generate_v1_uuid();
+
+        let content = manager
+            .create_content(owner_id, new_content("v1", None), true)
+            .await
+            .unwrap();
+        manager
+            .update_content(
+                owner_id,
+                content.meta.id,
+                UpdateContent {
+                    meta: UpdateContentMeta {
+                        keywords: Some("rust".into()),
+                        ..Default::default()
+                    },
+                    entity: Some(ContentEntity::Post("v1 body\nmore".into())),
+                },
+                true,
+            )
+            .await
+            .unwrap();
+
+        let revisions = manager
+            .get_content_revisions(content.meta.id)
+            .await
+            .unwrap();
+
+        let diff = manager
+            .diff_revisions(content.meta.id, None, revisions[1].id)
+            .await
+            .unwrap();
+        assert_eq!(diff.fields.len(), 1);
+        assert_eq!(diff.fields[0].field, "keywords");
+        assert_eq!(diff.entity.insertions, 2);
+        assert_eq!(diff.entity.deletions, 1);
+
+        let diff = manager
+            .diff_revisions(content.meta.id, Some(revisions[1].id), revisions[0].id)
+            .await
+            .unwrap();
+        assert_eq!(diff.fields[0].new.as_deref(), Some(""));
+        assert_eq!(diff.entity.insertions, 1);
+        assert_eq!(diff.entity.deletions, 2);
+    }
+}
@@ -1,2 +1,10 @@
+pub mod audit;
 pub mod content;
+pub mod search;
+pub mod session;
+pub mod signature;
+pub mod taxonomy;
+pub mod throttle;
+pub mod token;
+pub mod two_factor;
 pub mod user;
\ No newline at end of file
@@ -0,0 +1,213 @@
+use std::collections::HashMap;
+
+use uuid::Uuid;
+
+use crate::{
+    dao::content::ContentDao,
+    error::{Error, InspirerContentResult},
+    manager::Manager,
+    model::{
+        paginate::{Paginated, Pagination},
+        search::SearchResult,
+    },
+    search::{SearchDocument, SearchIndex},
+    service::content::ContentService,
+};
+
+#[async_trait::async_trait]
+pub trait SearchService {
+    /// 在已公开的内容中搜索标题、描述、关键词与正文
+    async fn search(
+        &self,
+        query: String,
+        pagination: Pagination,
+    ) -> InspirerContentResult<Paginated<SearchResult>>;
+    async fn rebuild_search_index(&self) -> InspirerContentResult<usize>;
+}
+
+#[async_trait::async_trait]
+impl SearchService for Manager {
+    async fn search(
+        &self,
+        query: String,
+        pagination: Pagination,
+    ) -> InspirerContentResult<Paginated<SearchResult>> {
+        let page_size = pagination.page_size.max(1);
+        let (hits, total) = if query.trim().is_empty() {
+            (vec![], 0)
+        } else {
+            self.search_index
+                .search(&query, (pagination.page.max(1) - 1) * page_size, page_size)?
+        };
+
+        let mut contents = self
+            .database
+            .get_contents_by_ids(hits.iter().map(|hit| hit.id).collect())
+            .await?
+            .into_iter()
+            .map(|(content, owner)| (content.id, (content, owner)))
+            .collect::<HashMap<_, _>>();
+
+        // 以数据库中的状态为准，过滤索引尚未同步的内容
+        let data = hits
+            .into_iter()
+            .filter_map(|hit| {
+                let (content, owner) = contents.remove(&hit.id)?;
+                (content.is_publish && content.is_display && !content.is_deleted).then_some(
+                    SearchResult {
+                        content,
+                        owner,
+                        score: hit.score,
+                        title_highlight: hit.title,
+                        body_highlight: hit.body,
+                    },
+                )
+            })
+            .collect();
+
+        Ok(Paginated {
+            data,
+            page: pagination.page,
+            page_size,
+            total,
+            last_page: total.div_ceil(page_size),
+        })
+    }
+
+    async fn rebuild_search_index(&self) -> InspirerContentResult<usize> {
+        let mut documents = vec![];
+        for id in self.database.get_content_ids().await? {
+            documents.push(SearchDocument::from(&self.find_content_by_id(id).await?));
+        }
+
+        self.write_search_index(move |index| index.rebuild(documents))
+            .await
+    }
+}
+
+impl Manager {
+    /// 将内容的当前状态同步至搜索索引，内容不存在时移除其索引
+    ///
+    /// 索引同步失败不影响内容本身的写入，仅记录错误，可通过重建索引修复。
+    pub(crate) async fn sync_search_index(&self, id: Uuid) {
+        let res = match self.find_content_by_id(id).await {
+            Ok(content) => {
+                let document = SearchDocument::from(&content);
+                self.write_search_index(move |index| index.upsert(document))
+                    .await
+            }
+            Err(Error::ContentNotFound) => {
+                self.write_search_index(move |index| index.remove(id)).await
+            }
+            Err(err) => Err(err),
+        };
+
+        if let Err(err) = res {
+            tracing::error!("Sync search index of content {} error: {}", id, err);
+        }
+    }
+
+    /// 在阻塞线程池中写入索引，避免索引提交阻塞异步运行时
+    async fn write_search_index<F, T>(&self, op: F) -> InspirerContentResult<T>
+    where
+        F: FnOnce(&SearchIndex) -> InspirerContentResult<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let index = self.search_index.clone();
+
+        tokio::task::spawn_blocking(move || op(&index))
+            .await
+            .map_err(|err| tantivy::TantivyError::SystemError(err.to_string()))?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        manager::tests::sqlite_manager,
+        model::content::{
+            ContentEntity, ContentMeta, NewContent, UpdateContent, UpdateContentMeta,
+        },
+        util::uuid::generate_v1_uuid,
+    };
+
+    async fn search_total(manager: &Manager, query: &str) -> usize {
+        manager
+            .search(query.into(), Pagination::default())
+            .await
+            .unwrap()
+            .total
+    }
+
+    #[tokio::test]
+    async fn test_search() {
+        let manager = sqlite_manager().await;
+        let owner_id = generate_v1_uuid();
+
+        let content = manager
+            .create_content(
+                owner_id,
+                NewContent {
+                    meta: ContentMeta {
+                        title: "全文搜索".into(),
+                        description: "tantivy".into(),
+                        ..Default::default()
+                    },
+                    entity: ContentEntity::Post("为博客内容建立索引".into()),
+                },
+                true,
+            )
+            .await
+            .unwrap();
+        let id = content.meta.id;
+
+        // 未发布的内容不可被搜索
+        assert_eq!(search_total(&manager, "搜索").await, 0);
+
+        manager.publish_content(id, None).await.unwrap();
+        let res = manager
+            .search("博客".into(), Pagination::default())
+            .await
+            .unwrap();
+        assert_eq!(res.total, 1);
+        assert_eq!(res.data[0].content.id, id);
+        assert_eq!(
+            res.data[0].body_highlight.as_deref(),
+            Some("为<b>博客</b>内容建立索引")
+        );
+        assert!(res.data[0].title_highlight.is_none());
+
+        manager
+            .update_content(
+                owner_id,
+                id,
+                UpdateContent {
+                    meta: UpdateContentMeta {
+                        title: Some("Rust 笔记".into()),
+                        ..Default::default()
+                    },
+                    entity: None,
+                },
+                true,
+            )
+            .await
+            .unwrap();
+        assert_eq!(search_total(&manager, "搜索").await, 0);
+        assert_eq!(search_total(&manager, "rust").await, 1);
+
+        manager.delete_content(id, false, None).await.unwrap();
+        assert_eq!(search_total(&manager, "rust").await, 0);
+        manager.revert_deleted_content(id, None).await.unwrap();
+        assert_eq!(search_total(&manager, "rust").await, 1);
+
+        manager.search_index.rebuild(vec![]).unwrap();
+        assert_eq!(search_total(&manager, "rust").await, 0);
+        assert_eq!(manager.rebuild_search_index().await.unwrap(), 1);
+        assert_eq!(search_total(&manager, "rust").await, 1);
+
+        manager.delete_content(id, true, None).await.unwrap();
+        assert_eq!(search_total(&manager, "rust").await, 0);
+        assert_eq!(search_total(&manager, "  ").await, 0);
+    }
+}
@@ -0,0 +1,334 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::{
+    dao::session::SessionDao,
+    enumerate::audit::AuditTargetType,
+    error::{Error, InspirerContentResult},
+    manager::Manager,
+    model::{
+        audit::NewAuditLog,
+        session::{IssuedSession, SessionModel},
+    },
+    service::audit::MAX_USER_AGENT_LENGTH,
+    util::{
+        token::{generate_token, token_hash},
+        uuid::{generate_v1_uuid, generate_v4_uuid},
+    },
+};
+
+#[async_trait::async_trait]
+pub trait SessionService {
+    /// 创建登录会话，会话在 `expires_at` 之前未刷新则失效，过长的 `user_agent` 会被截断
+    async fn create_session(
+        &self,
+        user_id: Uuid,
+        user_agent: String,
+        expires_at: DateTime<Utc>,
+        audit: Option<NewAuditLog>,
+    ) -> InspirerContentResult<IssuedSession>;
+    /// 使用刷新令牌轮换会话的访问令牌与刷新令牌，审计日志的操作者为会话所属用户、目标为该会话
+    ///
+    /// 已被轮换掉的刷新令牌再次使用时视为令牌泄露，整个会话将被吊销。
+    async fn refresh_session(
+        &self,
+        refresh_token: String,
+        expires_at: DateTime<Utc>,
+        audit: Option<NewAuditLog>,
+    ) -> InspirerContentResult<IssuedSession>;
+    /// 查找访问令牌对应的有效会话，已吊销、已过期或已轮换的访问令牌均视为无效
+    async fn find_active_session(
+        &self,
+        access_token_id: Uuid,
+    ) -> InspirerContentResult<SessionModel>;
+    async fn get_active_sessions(&self, user_id: Uuid) -> InspirerContentResult<Vec<SessionModel>>;
+    async fn revoke_session(
+        &self,
+        user_id: Uuid,
+        session_id: Uuid,
+        audit: Option<NewAuditLog>,
+    ) -> InspirerContentResult<()>;
+    async fn revoke_user_sessions(&self, user_id: Uuid) -> InspirerContentResult<()>;
+}
+
+#[async_trait::async_trait]
+impl SessionService for Manager {
+    async fn create_session(
+        &self,
+        user_id: Uuid,
+        user_agent: String,
+        expires_at: DateTime<Utc>,
+        audit: Option<NewAuditLog>,
+    ) -> InspirerContentResult<IssuedSession> {
+        let id = generate_v1_uuid();
+        let refresh_token = generate_token();
+        let refresh_token_hash = token_hash(&refresh_token);
+        let user_agent = user_agent.chars().take(MAX_USER_AGENT_LENGTH).collect();
+
+        self.audited(audit, move |trx| {
+            Box::pin(async move {
+                trx.create_session(
+                    id,
+                    user_id,
+                    generate_v4_uuid(),
+                    refresh_token_hash,
+                    user_agent,
+                    expires_at,
+                )
+                .await
+            })
+        })
+        .await?;
+
+        Ok(IssuedSession {
+            session: self
+                .database
+                .find_session_by_id(id)
+                .await?
+                .ok_or(Error::SessionNotFound)?,
+            refresh_token,
+        })
+    }
+
+    async fn refresh_session(
+        &self,
+        refresh_token: String,
+        expires_at: DateTime<Utc>,
+        audit: Option<NewAuditLog>,
+    ) -> InspirerContentResult<IssuedSession> {
+        let hash = token_hash(&refresh_token);
+
+        let session = match self
+            .database
+            .find_session_by_refresh_token(hash.clone())
+            .await?
+        {
+            Some(session) => session,
+            None => {
+                if let Some(session) = self
+                    .database
+                    .find_session_by_previous_refresh_token(hash)
+                    .await?
+                {
+                    tracing::warn!(
+                        "Refresh token reused, revoke session {} of user {}",
+                        session.id,
+                        session.user_id
+                    );
+                    self.database.revoke_session(session.id).await?;
+                }
+
+                return Err(Error::InvalidSession);
+            }
+        };
+
+        if !session.is_active(Utc::now()) {
+            return Err(Error::InvalidSession);
+        }
+
+        let new_refresh_token = generate_token();
+        let new_refresh_token_hash = token_hash(&new_refresh_token);
+        let session_id = session.id;
+        let audit = audit.map(|log| {
+            log.actor(session.user_id)
+                .target(AuditTargetType::Session, session_id)
+        });
+
+        self.audited(audit, move |trx| {
+            Box::pin(async move {
+                let rotated = trx
+                    .rotate_session(
+                        session_id,
+                        hash,
+                        generate_v4_uuid(),
+                        new_refresh_token_hash,
+                        expires_at,
+                    )
+                    .await?;
+
+                // 并发刷新时仅第一个请求成功
+                if !rotated {
+                    return Err(Error::InvalidSession);
+                }
+
+                Ok(())
+            })
+        })
+        .await?;
+
+        Ok(IssuedSession {
+            session: self
+                .database
+                .find_session_by_id(session.id)
+                .await?
+                .ok_or(Error::SessionNotFound)?,
+            refresh_token: new_refresh_token,
+        })
+    }
+
+    async fn find_active_session(
+        &self,
+        access_token_id: Uuid,
+    ) -> InspirerContentResult<SessionModel> {
+        self.database
+            .find_session_by_access_token(access_token_id)
+            .await?
+            .filter(|session| session.is_active(Utc::now()))
+            .ok_or(Error::InvalidSession)
+    }
+
+    async fn get_active_sessions(&self, user_id: Uuid) -> InspirerContentResult<Vec<SessionModel>> {
+        let now = Utc::now();
+
+        Ok(self
+            .database
+            .get_user_sessions(user_id)
+            .await?
+            .into_iter()
+            .filter(|session| session.is_active(now))
+            .collect())
+    }
+
+    async fn revoke_session(
+        &self,
+        user_id: Uuid,
+        session_id: Uuid,
+        audit: Option<NewAuditLog>,
+    ) -> InspirerContentResult<()> {
+        self.database
+            .find_session_by_id(session_id)
+            .await?
+            .filter(|session| session.user_id == user_id)
+            .ok_or(Error::SessionNotFound)?;
+
+        self.audited(audit, move |trx| {
+            Box::pin(async move { trx.revoke_session(session_id).await })
+        })
+        .await
+    }
+
+    async fn revoke_user_sessions(&self, user_id: Uuid) -> InspirerContentResult<()> {
+        self.database.revoke_user_sessions(user_id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+    use crate::manager::tests::sqlite_manager;
+
+    #[tokio::test]
+    async fn test_session_rotation() {
+        let manager = sqlite_manager().await;
+        let user_id = generate_v1_uuid();
+        let expires_at = Utc::now() + Duration::days(1);
+
+        let issued = manager
+            .create_session(user_id, "test".into(), expires_at, None)
+            .await
+            .unwrap();
+        let access_token_id = issued.session.access_token_id;
+        assert_eq!(
+            manager
+                .find_active_session(access_token_id)
+                .await
+                .unwrap()
+                .id,
+            issued.session.id
+        );
+
+        let refreshed = manager
+            .refresh_session(issued.refresh_token.clone(), expires_at, None)
+            .await
+            .unwrap();
+        assert_eq!(refreshed.session.id, issued.session.id);
+        assert_ne!(refreshed.refresh_token, issued.refresh_token);
+
+        // 轮换后旧的访问令牌失效
+        assert!(matches!(
+            manager.find_active_session(access_token_id).await,
+            Err(Error::InvalidSession)
+        ));
+        manager
+            .find_active_session(refreshed.session.access_token_id)
+            .await
+            .unwrap();
+
+        // 重复使用旧的刷新令牌会吊销整个会话
+        assert!(matches!(
+            manager
+                .refresh_session(issued.refresh_token, expires_at, None)
+                .await,
+            Err(Error::InvalidSession)
+        ));
+        assert!(matches!(
+            manager
+                .find_active_session(refreshed.session.access_token_id)
+                .await,
+            Err(Error::InvalidSession)
+        ));
+        assert!(matches!(
+            manager
+                .refresh_session(refreshed.refresh_token, expires_at, None)
+                .await,
+            Err(Error::InvalidSession)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_revoke_session() {
+        let manager = sqlite_manager().await;
+        let user_id = generate_v1_uuid();
+        let expires_at = Utc::now() + Duration::days(1);
+
+        let first = manager
+            .create_session(user_id, "first".into(), expires_at, None)
+            .await
+            .unwrap();
+        let second = manager
+            .create_session(user_id, "a".repeat(600), expires_at, None)
+            .await
+            .unwrap();
+        manager
+            .create_session(
+                user_id,
+                "expired".into(),
+                Utc::now() - Duration::days(1),
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(manager.get_active_sessions(user_id).await.unwrap().len(), 2);
+
+        assert!(matches!(
+            manager
+                .revoke_session(generate_v1_uuid(), first.session.id, None)
+                .await,
+            Err(Error::SessionNotFound)
+        ));
+
+        manager
+            .revoke_session(user_id, first.session.id, None)
+            .await
+            .unwrap();
+        let sessions = manager.get_active_sessions(user_id).await.unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].id, second.session.id);
+        assert_eq!(sessions[0].user_agent.len(), MAX_USER_AGENT_LENGTH);
+
+        manager.revoke_user_sessions(user_id).await.unwrap();
+        assert!(manager
+            .get_active_sessions(user_id)
+            .await
+            .unwrap()
+            .is_empty());
+        assert!(matches!(
+            manager
+                .refresh_session(second.refresh_token, expires_at, None)
+                .await,
+            Err(Error::InvalidSession)
+        ));
+    }
+}
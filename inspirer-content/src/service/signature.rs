@@ -0,0 +1,330 @@
+use chrono::{Duration, TimeZone, Utc};
+
+use crate::{
+    dao::signature::{LoginChallengeDao, NonceDao},
+    entity::users,
+    error::{Error, InspirerContentResult},
+    manager::Manager,
+    model::signature::{LoginChallengeModel, SignedRequest},
+    util::{signature::verify_signature, token::generate_token},
+};
+
+#[async_trait::async_trait]
+pub trait SignatureService {
+    /// 校验请求签名，成功时返回签名的用户
+    ///
+    /// 签名时间与服务器时间相差超过 `max_skew` 的请求会被拒绝，有效期内重复使用的随机数视为重放。
+    async fn verify_signed_request(
+        &self,
+        request: SignedRequest,
+        max_skew: Duration,
+    ) -> InspirerContentResult<users::Model>;
+    /// 为公钥指纹签发登录挑战
+    ///
+    /// 指纹不存在时同样签发，避免通过该接口探测公钥是否已注册。
+    async fn create_login_challenge(
+        &self,
+        fingerprint: Vec<u8>,
+        expiration: Duration,
+    ) -> InspirerContentResult<LoginChallengeModel>;
+    async fn attempt_with_key(
+        &self,
+        challenge: String,
+        signature: Vec<u8>,
+    ) -> InspirerContentResult<users::Model>;
+}
+
+#[async_trait::async_trait]
+impl SignatureService for Manager {
+    async fn verify_signed_request(
+        &self,
+        request: SignedRequest,
+        max_skew: Duration,
+    ) -> InspirerContentResult<users::Model> {
+        let now = Utc::now();
+        let signed_at = Utc
+            .timestamp_opt(request.timestamp, 0)
+            .single()
+            .ok_or(Error::InvalidSignature)?;
+
+        if signed_at < now - max_skew || signed_at > now + max_skew {
+            return Err(Error::SignatureExpired);
+        }
+
+        if !request.is_nonce_valid() {
+            return Err(Error::InvalidSignature);
+        }
+
+        let (user, public_key) = self
+            .find_user_by_fingerprint(&request.fingerprint)
+            .await?
+            .ok_or(Error::InvalidSignature)?;
+
+        if !verify_signature(
+            &public_key,
+            request.message().as_bytes(),
+            &request.signature,
+        ) {
+            return Err(Error::InvalidSignature);
+        }
+
+        // 签名校验通过后才检查账号状态，避免仅凭公钥指纹探测账号是否被禁用
+        if user.is_disabled() {
+            return Err(Error::UserDisabled);
+        }
+
+        // 签名校验通过后才记录随机数，避免未授权的请求占用随机数
+        self.database.delete_expired_nonces(now).await?;
+        match self
+            .database
+            .create_nonce(user.id, request.nonce, signed_at + max_skew)
+            .await
+        {
+            Ok(()) => Ok(user),
+            Err(Error::DatabaseWriteConflict) => Err(Error::ReplayedRequest),
+            Err(err) => Err(err),
+        }
+    }
+
+    async fn create_login_challenge(
+        &self,
+        fingerprint: Vec<u8>,
+        expiration: Duration,
+    ) -> InspirerContentResult<LoginChallengeModel> {
+        let now = Utc::now();
+        let model = LoginChallengeModel {
+            challenge: generate_token(),
+            public_key_fingerprint: fingerprint,
+            expires_at: now + expiration,
+        };
+
+        self.database.delete_expired_login_challenges(now).await?;
+        self.database
+            .create_login_challenge(
+                model.challenge.clone(),
+                model.public_key_fingerprint.clone(),
+                model.expires_at,
+            )
+            .await?;
+
+        Ok(model)
+    }
+
+    async fn attempt_with_key(
+        &self,
+        challenge: String,
+        signature: Vec<u8>,
+    ) -> InspirerContentResult<users::Model> {
+        let challenge = self
+            .database
+            .take_login_challenge(challenge)
+            .await?
+            .filter(|challenge| challenge.expires_at > Utc::now())
+            .ok_or(Error::InvalidSignature)?;
+
+        let (user, public_key) = self
+            .find_user_by_fingerprint(&challenge.public_key_fingerprint)
+            .await?
+            .ok_or(Error::InvalidSignature)?;
+
+        if !verify_signature(&public_key, challenge.challenge.as_bytes(), &signature) {
+            return Err(Error::InvalidSignature);
+        }
+
+        if user.is_disabled() {
+            return Err(Error::UserDisabled);
+        }
+
+        Ok(user)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ring::signature::Ed25519KeyPair;
+
+    use super::*;
+    use crate::{
+        dao::user::UserDao,
+        manager::tests::sqlite_manager,
+        model::user::NewUser,
+        util::{hash::sha256, signature::generate_pkcs8_keypair, uuid::generate_v1_uuid},
+    };
+
+    fn sign(
+        key_pair: &Ed25519KeyPair,
+        fingerprint: Vec<u8>,
+        timestamp: i64,
+        nonce: &str,
+    ) -> SignedRequest {
+        let mut request = SignedRequest {
+            fingerprint,
+            method: "POST".into(),
+            path: "/security/content".into(),
+            timestamp,
+            nonce: nonce.into(),
+            body_hash: sha256(b"{}"),
+            signature: vec![],
+        };
+        request.signature = key_pair
+            .sign(request.message().as_bytes())
+            .as_ref()
+            .to_vec();
+
+        request
+    }
+
+    #[tokio::test]
+    async fn test_verify_signed_request() {
+        let manager = sqlite_manager().await;
+        let key_pair = generate_pkcs8_keypair().unwrap();
+        let fingerprint = key_pair.public_key_fingerprint();
+        let user_id = manager
+            .database
+            .create_user(
+                generate_v1_uuid(),
+                &NewUser {
+                    username: "ci".into(),
+                    ..Default::default()
+                },
+                key_pair.public_key.clone(),
+                fingerprint.clone(),
+            )
+            .await
+            .unwrap();
+        let signer = Ed25519KeyPair::from_pkcs8(key_pair.private_key_bytes()).unwrap();
+        let max_skew = Duration::minutes(5);
+        let now = Utc::now().timestamp();
+
+        let request = sign(&signer, fingerprint.clone(), now, "nonce-0001");
+        let user = manager
+            .verify_signed_request(request.clone(), max_skew)
+            .await
+            .unwrap();
+        assert_eq!(user.id, user_id);
+
+        assert!(matches!(
+            manager
+                .verify_signed_request(request.clone(), max_skew)
+                .await,
+            Err(Error::ReplayedRequest)
+        ));
+
+        let mut tampered = sign(&signer, fingerprint.clone(), now, "nonce-0002");
+        tampered.path = "/security/content/other".into();
+        assert!(matches!(
+            manager.verify_signed_request(tampered, max_skew).await,
+            Err(Error::InvalidSignature)
+        ));
+
+        let expired = sign(&signer, fingerprint.clone(), now - 600, "nonce-0003");
+        assert!(matches!(
+            manager.verify_signed_request(expired, max_skew).await,
+            Err(Error::SignatureExpired)
+        ));
+
+        let unknown = sign(&signer, sha256(b"unknown"), now, "nonce-0004");
+        assert!(matches!(
+            manager.verify_signed_request(unknown, max_skew).await,
+            Err(Error::InvalidSignature)
+        ));
+
+        let short_nonce = sign(&signer, fingerprint.clone(), now, "short");
+        assert!(matches!(
+            manager.verify_signed_request(short_nonce, max_skew).await,
+            Err(Error::InvalidSignature)
+        ));
+
+        // 签名无效时不暴露账号已被禁用
+        manager
+            .database
+            .update_user_disabled_at(user_id, Some(Utc::now()))
+            .await
+            .unwrap();
+        let mut tampered = sign(&signer, fingerprint.clone(), now, "nonce-0005");
+        tampered.path = "/security/content/other".into();
+        assert!(matches!(
+            manager.verify_signed_request(tampered, max_skew).await,
+            Err(Error::InvalidSignature)
+        ));
+        let disabled = sign(&signer, fingerprint, now, "nonce-0006");
+        assert!(matches!(
+            manager.verify_signed_request(disabled, max_skew).await,
+            Err(Error::UserDisabled)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_attempt_with_key() {
+        let manager = sqlite_manager().await;
+        let key_pair = generate_pkcs8_keypair().unwrap();
+        let fingerprint = key_pair.public_key_fingerprint();
+        let user_id = manager
+            .database
+            .create_user(
+                generate_v1_uuid(),
+                &NewUser {
+                    username: "admin".into(),
+                    ..Default::default()
+                },
+                key_pair.public_key.clone(),
+                fingerprint.clone(),
+            )
+            .await
+            .unwrap();
+        let signer = Ed25519KeyPair::from_pkcs8(key_pair.private_key_bytes()).unwrap();
+        let expiration = Duration::minutes(1);
+
+        let challenge = manager
+            .create_login_challenge(fingerprint.clone(), expiration)
+            .await
+            .unwrap()
+            .challenge;
+        let signature = signer.sign(challenge.as_bytes()).as_ref().to_vec();
+        let user = manager
+            .attempt_with_key(challenge.clone(), signature.clone())
+            .await
+            .unwrap();
+        assert_eq!(user.id, user_id);
+
+        // 挑战只能使用一次
+        assert!(matches!(
+            manager.attempt_with_key(challenge, signature).await,
+            Err(Error::InvalidSignature)
+        ));
+
+        let challenge = manager
+            .create_login_challenge(fingerprint.clone(), expiration)
+            .await
+            .unwrap()
+            .challenge;
+        assert!(matches!(
+            manager
+                .attempt_with_key(challenge, signer.sign(b"other").as_ref().to_vec())
+                .await,
+            Err(Error::InvalidSignature)
+        ));
+
+        let expired = manager
+            .create_login_challenge(fingerprint, Duration::seconds(-1))
+            .await
+            .unwrap()
+            .challenge;
+        let signature = signer.sign(expired.as_bytes()).as_ref().to_vec();
+        assert!(matches!(
+            manager.attempt_with_key(expired, signature).await,
+            Err(Error::InvalidSignature)
+        ));
+
+        let unknown = manager
+            .create_login_challenge(sha256(b"unknown"), expiration)
+            .await
+            .unwrap()
+            .challenge;
+        let signature = signer.sign(unknown.as_bytes()).as_ref().to_vec();
+        assert!(matches!(
+            manager.attempt_with_key(unknown, signature).await,
+            Err(Error::InvalidSignature)
+        ));
+    }
+}
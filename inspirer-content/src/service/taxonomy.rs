@@ -0,0 +1,346 @@
+use crate::{
+    dao::taxonomy::{CategoryDao, TagDao},
+    error::{Error, InspirerContentResult},
+    manager::Manager,
+    model::taxonomy::{CategoryModel, NewCategory, TagModel, UpdateCategory},
+    util::uuid::generate_v1_uuid,
+};
+
+#[async_trait::async_trait]
+pub trait TaxonomyService {
+    async fn get_tags(&self) -> InspirerContentResult<Vec<TagModel>>;
+    async fn find_tag_by_name(&self, name: String) -> InspirerContentResult<TagModel>;
+    async fn create_tag(&self, name: String) -> InspirerContentResult<TagModel>;
+    /// 删除标签，同时解除其与内容的关联
+    async fn delete_tag(&self, name: String) -> InspirerContentResult<()>;
+    async fn get_categories(&self) -> InspirerContentResult<Vec<CategoryModel>>;
+    async fn find_category_by_name(&self, name: String) -> InspirerContentResult<CategoryModel>;
+    async fn create_category(
+        &self,
+        new_category: NewCategory,
+    ) -> InspirerContentResult<CategoryModel>;
+    async fn update_category(
+        &self,
+        name: String,
+        update_category: UpdateCategory,
+    ) -> InspirerContentResult<CategoryModel>;
+    /// 删除分类，同时解除其与内容的关联
+    async fn delete_category(&self, name: String) -> InspirerContentResult<()>;
+}
+
+#[async_trait::async_trait]
+impl TaxonomyService for Manager {
+    async fn get_tags(&self) -> InspirerContentResult<Vec<TagModel>> {
+        self.database.get_tags().await
+    }
+
+    async fn find_tag_by_name(&self, name: String) -> InspirerContentResult<TagModel> {
+        self.database
+            .find_tag_by_name(name)
+            .await?
+            .ok_or(Error::TagNotFound)
+    }
+
+    async fn create_tag(&self, name: String) -> InspirerContentResult<TagModel> {
+        let name = term_name(&name)?;
+
+        self.database
+            .create_tag(generate_v1_uuid(), name.clone())
+            .await?;
+
+        self.find_tag_by_name(name).await
+    }
+
+    async fn delete_tag(&self, name: String) -> InspirerContentResult<()> {
+        let tag = self.find_tag_by_name(name).await?;
+
+        self.database.delete_tag(tag.id).await
+    }
+
+    async fn get_categories(&self) -> InspirerContentResult<Vec<CategoryModel>> {
+        self.database.get_categories().await
+    }
+
+    async fn find_category_by_name(&self, name: String) -> InspirerContentResult<CategoryModel> {
+        self.database
+            .find_category_by_name(name)
+            .await?
+            .ok_or(Error::CategoryNotFound)
+    }
+
+    async fn create_category(
+        &self,
+        mut new_category: NewCategory,
+    ) -> InspirerContentResult<CategoryModel> {
+        new_category.name = term_name(&new_category.name)?;
+
+        self.database
+            .create_category(generate_v1_uuid(), &new_category)
+            .await?;
+
+        self.find_category_by_name(new_category.name).await
+    }
+
+    async fn update_category(
+        &self,
+        name: String,
+        mut update_category: UpdateCategory,
+    ) -> InspirerContentResult<CategoryModel> {
+        let category = self.find_category_by_name(name).await?;
+
+        if let Some(name) = &update_category.name {
+            update_category.name = Some(term_name(name)?);
+        }
+
+        self.database
+            .update_category(category.id, &update_category)
+            .await?;
+
+        self.find_category_by_name(update_category.name.unwrap_or(category.name))
+            .await
+    }
+
+    async fn delete_category(&self, name: String) -> InspirerContentResult<()> {
+        let category = self.find_category_by_name(name).await?;
+
+        self.database.delete_category(category.id).await
+    }
+}
+
+fn term_name(name: &str) -> InspirerContentResult<String> {
+    let name = name.trim();
+
+    if name.is_empty() {
+        return Err(Error::EmptyTermName);
+    }
+
+    Ok(name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use sea_orm::{sea_query::Expr, ColumnTrait, EntityTrait, QueryFilter};
+
+    use super::*;
+    use crate::{
+        entity::content_update_logs,
+        manager::tests::sqlite_manager,
+        model::{
+            content::{
+                ContentEntity, ContentMeta, GetListCondition, NewContent, UpdateContent,
+                UpdateContentMeta,
+            },
+            paginate::Pagination,
+        },
+        service::content::ContentService,
+    };
+
+    fn new_content(title: &str, tags: &[&str], categories: &[&str]) -> NewContent {
+        NewContent {
+            meta: ContentMeta {
+                title: title.into(),
+                tags: tags.iter().map(|tag| tag.to_string()).collect(),
+                categories: categories
+                    .iter()
+                    .map(|category| category.to_string())
+                    .collect(),
+                ..Default::default()
+            },
+            entity: ContentEntity::Post(format!("{title} body")),
+        }
+    }
+
+    async fn list_total(manager: &Manager, condition: GetListCondition) -> usize {
+        manager
+            .get_list(condition, Pagination::default())
+            .await
+            .unwrap()
+            .total
+    }
+
+    #[tokio::test]
+    async fn test_taxonomy_crud() {
+        let manager = sqlite_manager().await;
+
+        let tag = manager.create_tag(" rust ".into()).await.unwrap();
+        assert_eq!(tag.name, "rust");
+        assert!(matches!(
+            manager.create_tag("rust".into()).await,
+            Err(Error::DatabaseWriteConflict)
+        ));
+        assert!(matches!(
+            manager.create_tag("  ".into()).await,
+            Err(Error::EmptyTermName)
+        ));
+
+        let category = manager
+            .create_category(NewCategory {
+                name: "notes".into(),
+                description: "随笔".into(),
+            })
+            .await
+            .unwrap();
+        assert_eq!(category.description, "随笔");
+
+        let category = manager
+            .update_category(
+                "notes".into(),
+                UpdateCategory {
+                    name: Some("essays".into()),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(category.name, "essays");
+        assert_eq!(category.description, "随笔");
+
+        manager.delete_tag("rust".into()).await.unwrap();
+        manager.delete_category("essays".into()).await.unwrap();
+        assert!(manager.get_tags().await.unwrap().is_empty());
+        assert!(matches!(
+            manager.delete_category("essays".into()).await,
+            Err(Error::CategoryNotFound)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_content_taxonomy() {
+        let manager = sqlite_manager().await;
+        let owner_id = generate_v1_uuid();
+
+        let first = manager
+            .create_content(
+                owner_id,
+                new_content("first", &["rust", "web", "rust"], &["tech"]),
+                true,
+            )
+            .await
+            .unwrap();
+        assert_eq!(first.tags, vec!["rust", "web"]);
+        assert_eq!(first.categories, vec!["tech"]);
+
+        let second = manager
+            .create_content(owner_id, new_content("second", &["web"], &["life"]), true)
+            .await
+            .unwrap();
+        assert_eq!(manager.get_tags().await.unwrap().len(), 2);
+        assert_eq!(manager.get_categories().await.unwrap().len(), 2);
+
+        let by_tag = |tag: &str| GetListCondition {
+            with_unpublish: true,
+            tag: Some(tag.into()),
+            ..Default::default()
+        };
+        assert_eq!(list_total(&manager, by_tag("web")).await, 2);
+        assert_eq!(list_total(&manager, by_tag("rust")).await, 1);
+
+        manager
+            .update_content(
+                owner_id,
+                second.meta.id,
+                UpdateContent {
+                    meta: UpdateContentMeta {
+                        tags: Some(vec!["rust".into()]),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                true,
+            )
+            .await
+            .unwrap();
+        let second = manager.find_content_by_id(second.meta.id).await.unwrap();
+        assert_eq!(second.tags, vec!["rust"]);
+        assert_eq!(second.categories, vec!["life"]);
+        assert_eq!(list_total(&manager, by_tag("web")).await, 1);
+        assert_eq!(list_total(&manager, by_tag("rust")).await, 2);
+
+        let by_category = GetListCondition {
+            category: Some("tech".into()),
+            ..Default::default()
+        };
+        assert_eq!(list_total(&manager, by_category.clone()).await, 0);
+        manager.publish_content(first.meta.id, None).await.unwrap();
+        assert_eq!(list_total(&manager, by_category).await, 1);
+
+        manager.delete_tag("rust".into()).await.unwrap();
+        let first = manager.find_content_by_id(first.meta.id).await.unwrap();
+        assert_eq!(first.tags, vec!["web"]);
+    }
+
+    #[tokio::test]
+    async fn test_content_taxonomy_without_create_terms() {
+        let manager = sqlite_manager().await;
+        let owner_id = generate_v1_uuid();
+        manager.create_tag("rust".into()).await.unwrap();
+
+        assert!(matches!(
+            manager
+                .create_content(owner_id, new_content("first", &["rust", "web"], &[]), false)
+                .await,
+            Err(Error::CreateTermForbidden(name)) if name == "web"
+        ));
+        assert_eq!(manager.get_tags().await.unwrap().len(), 1);
+
+        let content = manager
+            .create_content(owner_id, new_content("first", &["rust"], &[]), false)
+            .await
+            .unwrap();
+        assert!(matches!(
+            manager
+                .update_content(
+                    owner_id,
+                    content.meta.id,
+                    UpdateContent {
+                        meta: UpdateContentMeta {
+                            categories: Some(vec!["tech".into()]),
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    },
+                    false,
+                )
+                .await,
+            Err(Error::CreateTermForbidden(_))
+        ));
+        assert!(manager.get_categories().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_restore_revision_without_taxonomy() {
+        let manager = sqlite_manager().await;
+        let owner_id = generate_v1_uuid();
+
+        let content = manager
+            .create_content(owner_id, new_content("v1", &["rust"], &["tech"]), true)
+            .await
+            .unwrap();
+        let revisions = manager
+            .get_content_revisions(content.meta.id)
+            .await
+            .unwrap();
+
+        // 早期的修订记录不包含标签与分类
+        content_update_logs::Entity::update_many()
+            .filter(content_update_logs::Column::Id.eq(revisions[0].id))
+            .col_expr(
+                content_update_logs::Column::UpdateData,
+                Expr::value(serde_json::json!({
+                    "title": "v1",
+                    "entity": { "type": "post", "data": "v1 body" },
+                })),
+            )
+            .exec(&manager.database)
+            .await
+            .unwrap();
+
+        manager
+            .restore_revision(owner_id, content.meta.id, revisions[0].id, false, None)
+            .await
+            .unwrap();
+        let restored = manager.find_content_by_id(content.meta.id).await.unwrap();
+        assert_eq!(restored.tags, vec!["rust"]);
+        assert_eq!(restored.categories, vec!["tech"]);
+    }
+}
@@ -0,0 +1,760 @@
+use std::{future::Future, net::IpAddr};
+
+use chrono::{Duration, Utc};
+use uuid::Uuid;
+
+use crate::{
+    dao::throttle::LoginAttemptDao,
+    entity::users,
+    error::{Error, InspirerContentResult},
+    manager::Manager,
+    model::{audit::NewAuditLog, signature::LoginChallengeModel, user::TwoFactorEnrollment},
+    service::{signature::SignatureService, two_factor::TwoFactorService, user::UserService},
+    throttle::{AttemptKey, AttemptRecord},
+};
+
+#[async_trait::async_trait]
+pub trait LoginThrottleService {
+    /// 带失败次数限制的密码登录
+    ///
+    /// 同一用户名或 IP 失败过多时返回 `TooManyLoginAttempts`，登录成功后清除该用户名的失败记录。
+    /// 两步验证及其他需要校验当前密码的操作与密码登录共用失败记录。
+    async fn attempt_throttled(
+        &self,
+        username: String,
+        password: String,
+        ip: Option<IpAddr>,
+    ) -> InspirerContentResult<users::Model>;
+    async fn verify_two_factor_throttled(
+        &self,
+        id: Uuid,
+        code: String,
+        ip: Option<IpAddr>,
+    ) -> InspirerContentResult<users::Model>;
+    async fn confirm_two_factor_throttled(
+        &self,
+        id: Uuid,
+        code: String,
+        ip: Option<IpAddr>,
+        audit: Option<NewAuditLog>,
+    ) -> InspirerContentResult<users::Model>;
+    async fn change_password_throttled(
+        &self,
+        id: Uuid,
+        current_password: String,
+        new_password: String,
+        current_session: Option<Uuid>,
+        ip: Option<IpAddr>,
+        audit: Option<NewAuditLog>,
+    ) -> InspirerContentResult<()>;
+    async fn verify_user_password_throttled(
+        &self,
+        id: Uuid,
+        password: String,
+        ip: Option<IpAddr>,
+    ) -> InspirerContentResult<users::Model>;
+    async fn enroll_two_factor_throttled(
+        &self,
+        id: Uuid,
+        password: String,
+        issuer: &str,
+        ip: Option<IpAddr>,
+    ) -> InspirerContentResult<TwoFactorEnrollment>;
+    async fn disable_two_factor_throttled(
+        &self,
+        id: Uuid,
+        password: String,
+        current_session: Option<Uuid>,
+        ip: Option<IpAddr>,
+        audit: Option<NewAuditLog>,
+    ) -> InspirerContentResult<()>;
+    /// 带次数限制的创建登录挑战
+    ///
+    /// 同一 IP 每创建一个挑战计为一次失败，过多时返回 `TooManyLoginAttempts`，使用密钥登录成功后清除。
+    async fn create_login_challenge_throttled(
+        &self,
+        fingerprint: Vec<u8>,
+        expiration: Duration,
+        ip: Option<IpAddr>,
+    ) -> InspirerContentResult<LoginChallengeModel>;
+    /// 使用密钥登录，登录成功后清除该 IP 创建登录挑战的记录
+    async fn attempt_with_key_throttled(
+        &self,
+        challenge: String,
+        signature: Vec<u8>,
+        ip: Option<IpAddr>,
+    ) -> InspirerContentResult<users::Model>;
+    /// 解除用户名或 IP 的登录限制，返回是否存在失败记录
+    async fn unlock_login(&self, key: AttemptKey) -> InspirerContentResult<bool>;
+}
+
+#[async_trait::async_trait]
+impl LoginThrottleService for Manager {
+    async fn attempt_throttled(
+        &self,
+        username: String,
+        password: String,
+        ip: Option<IpAddr>,
+    ) -> InspirerContentResult<users::Model> {
+        let mut keys = vec![AttemptKey::Username(username.clone())];
+        keys.extend(ip.map(AttemptKey::Ip));
+
+        let user = self
+            .throttled(&keys, self.attempt(username, password))
+            .await?;
+
+        // 开启两步验证时，完成第二步后才清除失败记录
+        if !user.two_factor_enabled() {
+            self.remove_login_attempt(&keys[0]).await?;
+        }
+
+        Ok(user)
+    }
+
+    async fn verify_two_factor_throttled(
+        &self,
+        id: Uuid,
+        code: String,
+        ip: Option<IpAddr>,
+    ) -> InspirerContentResult<users::Model> {
+        let keys = self.user_attempt_keys(id, ip).await?;
+
+        let user = self
+            .throttled(&keys, self.verify_two_factor(id, code))
+            .await?;
+        self.remove_login_attempt(&keys[0]).await?;
+
+        Ok(user)
+    }
+
+    async fn confirm_two_factor_throttled(
+        &self,
+        id: Uuid,
+        code: String,
+        ip: Option<IpAddr>,
+        audit: Option<NewAuditLog>,
+    ) -> InspirerContentResult<users::Model> {
+        let keys = self.user_attempt_keys(id, ip).await?;
+
+        let user = self
+            .throttled(&keys, self.confirm_two_factor(id, code, audit))
+            .await?;
+        self.remove_login_attempt(&keys[0]).await?;
+
+        Ok(user)
+    }
+
+    async fn change_password_throttled(
+        &self,
+        id: Uuid,
+        current_password: String,
+        new_password: String,
+        current_session: Option<Uuid>,
+        ip: Option<IpAddr>,
+        audit: Option<NewAuditLog>,
+    ) -> InspirerContentResult<()> {
+        let keys = self.user_attempt_keys(id, ip).await?;
+
+        self.throttled(
+            &keys,
+            self.change_password(id, current_password, new_password, current_session, audit),
+        )
+        .await?;
+        self.remove_login_attempt(&keys[0]).await?;
+
+        Ok(())
+    }
+
+    async fn verify_user_password_throttled(
+        &self,
+        id: Uuid,
+        password: String,
+        ip: Option<IpAddr>,
+    ) -> InspirerContentResult<users::Model> {
+        let keys = self.user_attempt_keys(id, ip).await?;
+
+        let user = self
+            .throttled(&keys, self.verify_user_password(id, password))
+            .await?;
+        self.remove_login_attempt(&keys[0]).await?;
+
+        Ok(user)
+    }
+
+    async fn enroll_two_factor_throttled(
+        &self,
+        id: Uuid,
+        password: String,
+        issuer: &str,
+        ip: Option<IpAddr>,
+    ) -> InspirerContentResult<TwoFactorEnrollment> {
+        let keys = self.user_attempt_keys(id, ip).await?;
+
+        let enrollment = self
+            .throttled(&keys, self.enroll_two_factor(id, password, issuer))
+            .await?;
+        self.remove_login_attempt(&keys[0]).await?;
+
+        Ok(enrollment)
+    }
+
+    async fn disable_two_factor_throttled(
+        &self,
+        id: Uuid,
+        password: String,
+        current_session: Option<Uuid>,
+        ip: Option<IpAddr>,
+        audit: Option<NewAuditLog>,
+    ) -> InspirerContentResult<()> {
+        let keys = self.user_attempt_keys(id, ip).await?;
+
+        self.throttled(
+            &keys,
+            self.disable_two_factor(id, password, current_session, audit),
+        )
+        .await?;
+        self.remove_login_attempt(&keys[0]).await?;
+
+        Ok(())
+    }
+
+    async fn create_login_challenge_throttled(
+        &self,
+        fingerprint: Vec<u8>,
+        expiration: Duration,
+        ip: Option<IpAddr>,
+    ) -> InspirerContentResult<LoginChallengeModel> {
+        let keys: Vec<_> = ip.map(AttemptKey::Challenge).into_iter().collect();
+
+        self.check_login_attempts(&keys).await?;
+        let challenge = self.create_login_challenge(fingerprint, expiration).await?;
+        self.record_login_failures(&keys).await?;
+
+        Ok(challenge)
+    }
+
+    async fn attempt_with_key_throttled(
+        &self,
+        challenge: String,
+        signature: Vec<u8>,
+        ip: Option<IpAddr>,
+    ) -> InspirerContentResult<users::Model> {
+        let user = self.attempt_with_key(challenge, signature).await?;
+        if let Some(ip) = ip {
+            self.remove_login_attempt(&AttemptKey::Challenge(ip))
+                .await?;
+        }
+
+        Ok(user)
+    }
+
+    async fn unlock_login(&self, key: AttemptKey) -> InspirerContentResult<bool> {
+        let removed = self.remove_login_attempt(&key).await?;
+        tracing::info!("Unlocked login, key = {key}");
+
+        Ok(removed)
+    }
+}
+
+impl Manager {
+    /// 存在锁定的记录时拒绝尝试，尝试失败时为每个 `keys` 记录一次失败
+    async fn throttled<T>(
+        &self,
+        keys: &[AttemptKey],
+        attempt: impl Future<Output = InspirerContentResult<T>>,
+    ) -> InspirerContentResult<T> {
+        self.check_login_attempts(keys).await?;
+
+        match attempt.await {
+            Err(
+                err @ (Error::UserNotFoundOrPasswordError
+                | Error::IncorrectPassword
+                | Error::InvalidTwoFactorCode),
+            ) => {
+                self.record_login_failures(keys).await?;
+                Err(err)
+            }
+            result => result,
+        }
+    }
+
+    /// 用户的用户名及 `ip` 对应的限流对象，用户名为第一个
+    async fn user_attempt_keys(
+        &self,
+        id: Uuid,
+        ip: Option<IpAddr>,
+    ) -> InspirerContentResult<Vec<AttemptKey>> {
+        let user = self.get_user_by_id(id).await?.ok_or(Error::UserNotFound)?;
+
+        let mut keys = vec![AttemptKey::Username(user.username)];
+        keys.extend(ip.map(AttemptKey::Ip));
+
+        Ok(keys)
+    }
+
+    /// 任一 `keys` 需要等待时返回 `TooManyLoginAttempts`
+    async fn check_login_attempts(&self, keys: &[AttemptKey]) -> InspirerContentResult<()> {
+        let now = Utc::now();
+        for key in keys {
+            let record = self.load_login_attempt(key).await?;
+            let retry_after =
+                record.and_then(|record| self.login_throttle_policy.retry_after(&record, now));
+
+            if let Some(retry_after) = retry_after {
+                tracing::info!("Rejected login attempt, key = {key}, retry after = {retry_after}");
+                return Err(too_many_attempts(retry_after));
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn record_login_failures(&self, keys: &[AttemptKey]) -> InspirerContentResult<()> {
+        let policy = &self.login_throttle_policy;
+
+        for key in keys {
+            let now = Utc::now();
+            let record = match &self.login_attempts {
+                Some(store) => store.record_failure(key, policy, now),
+                None => {
+                    self.database
+                        .increase_login_failures(
+                            key.to_string(),
+                            now,
+                            policy.lockout,
+                            policy.max_failures(key),
+                        )
+                        .await?
+                }
+            };
+
+            if let Some(locked_until) = record.locked_until {
+                tracing::warn!(
+                    "Login locked, key = {key}, failures = {}, locked until = {locked_until}",
+                    record.failures
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn load_login_attempt(
+        &self,
+        key: &AttemptKey,
+    ) -> InspirerContentResult<Option<AttemptRecord>> {
+        match &self.login_attempts {
+            Some(store) => Ok(store.get(&key.to_string())),
+            None => self.database.find_login_attempt(key.to_string()).await,
+        }
+    }
+
+    async fn remove_login_attempt(&self, key: &AttemptKey) -> InspirerContentResult<bool> {
+        match &self.login_attempts {
+            Some(store) => Ok(store.remove(&key.to_string())),
+            None => self.database.delete_login_attempt(key.to_string()).await,
+        }
+    }
+}
+
+fn too_many_attempts(retry_after: Duration) -> Error {
+    // 向上取整到秒
+    let millis = retry_after.num_milliseconds().max(1);
+
+    Error::TooManyLoginAttempts {
+        retry_after: ((millis + 999) / 1000) as u64,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ring::signature::Ed25519KeyPair;
+
+    use super::*;
+    use crate::{
+        dao::user::UserDao,
+        manager::tests::sqlite_manager,
+        model::user::NewUser,
+        throttle::LoginThrottlePolicy,
+        util::{signature::generate_pkcs8_keypair, uuid::generate_v1_uuid},
+    };
+
+    async fn assert_throttle(mut manager: Manager) {
+        // 不等待退避时间，仅测试锁定
+        manager.login_throttle_policy = LoginThrottlePolicy {
+            max_username_failures: 2,
+            max_ip_failures: 3,
+            base_delay: Duration::zero(),
+            max_delay: Duration::zero(),
+            ..Default::default()
+        };
+        manager
+            .create_user_simple(
+                NewUser {
+                    username: "chongyi".into(),
+                    password: "password".into(),
+                    ..Default::default()
+                },
+                None,
+            )
+            .await
+            .unwrap();
+        let ip: IpAddr = "10.0.0.1".parse().unwrap();
+
+        for _ in 0..2 {
+            assert!(matches!(
+                manager
+                    .attempt_throttled("chongyi".into(), "wrong".into(), Some(ip))
+                    .await,
+                Err(Error::UserNotFoundOrPasswordError)
+            ));
+        }
+
+        // 锁定后正确的密码同样被拒绝
+        assert!(matches!(
+            manager
+                .attempt_throttled("chongyi".into(), "password".into(), None)
+                .await,
+            Err(Error::TooManyLoginAttempts { retry_after }) if retry_after > 0
+        ));
+
+        assert!(manager
+            .unlock_login(AttemptKey::Username("chongyi".into()))
+            .await
+            .unwrap());
+        assert!(manager
+            .attempt_throttled("chongyi".into(), "password".into(), Some(ip))
+            .await
+            .is_ok());
+        assert!(!manager
+            .unlock_login(AttemptKey::Username("chongyi".into()))
+            .await
+            .unwrap());
+
+        // 同一 IP 尝试不同用户名同样计数
+        assert!(matches!(
+            manager
+                .attempt_throttled("nobody".into(), "wrong".into(), Some(ip))
+                .await,
+            Err(Error::UserNotFoundOrPasswordError)
+        ));
+        assert!(matches!(
+            manager
+                .attempt_throttled("chongyi".into(), "password".into(), Some(ip))
+                .await,
+            Err(Error::TooManyLoginAttempts { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_attempt_throttled_in_memory() {
+        assert_throttle(sqlite_manager().await).await;
+    }
+
+    #[tokio::test]
+    async fn test_attempt_throttled_in_database() {
+        let mut manager = sqlite_manager().await;
+        manager.login_attempts = None;
+
+        assert_throttle(manager).await;
+    }
+
+    #[tokio::test]
+    async fn test_record_login_failures_concurrently() {
+        let mut manager = sqlite_manager().await;
+        manager.login_attempts = None;
+        let key = AttemptKey::Username("chongyi".into());
+        let policy = manager.login_throttle_policy;
+
+        // 并发的首次失败不会因主键冲突而报错，且不丢失计数
+        let keys = [key.clone()];
+        let results = tokio::join!(
+            manager.record_login_failures(&keys),
+            manager.record_login_failures(&keys),
+            manager.record_login_failures(&keys),
+            manager.record_login_failures(&keys),
+        );
+        assert!(results.0.is_ok() && results.1.is_ok() && results.2.is_ok() && results.3.is_ok());
+
+        let record = manager.load_login_attempt(&key).await.unwrap().unwrap();
+        assert_eq!(record.failures, 4);
+        assert!(record.locked_until.is_none());
+
+        let record = manager
+            .database
+            .increase_login_failures(key.to_string(), Utc::now(), policy.lockout, 5)
+            .await
+            .unwrap();
+        assert_eq!(record.failures, 5);
+        assert!(record.locked_until.is_some());
+
+        // 锁定及计数过期后重新计数
+        let later = Utc::now() + policy.lockout + Duration::seconds(1);
+        let record = manager
+            .database
+            .increase_login_failures(key.to_string(), later, policy.lockout, 5)
+            .await
+            .unwrap();
+        assert_eq!(record.failures, 1);
+        assert_eq!(record.last_failure_at, later);
+        assert!(record.locked_until.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_two_factor_throttled() {
+        let mut manager = sqlite_manager().await;
+        manager.login_throttle_policy = LoginThrottlePolicy {
+            max_username_failures: 2,
+            base_delay: Duration::zero(),
+            max_delay: Duration::zero(),
+            ..Default::default()
+        };
+        let (id, _) = manager
+            .create_user_simple(
+                NewUser {
+                    username: "chongyi".into(),
+                    password: "password".into(),
+                    ..Default::default()
+                },
+                None,
+            )
+            .await
+            .unwrap();
+        let enrollment = manager
+            .enroll_two_factor(id, "password".into(), "Inspirer")
+            .await
+            .unwrap();
+        manager
+            .database
+            .enable_user_totp(id, Utc::now())
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            manager
+                .verify_two_factor_throttled(id, "000000".into(), None)
+                .await,
+            Err(Error::InvalidTwoFactorCode)
+        ));
+
+        // 密码正确不会清除两步验证的失败记录
+        manager
+            .attempt_throttled("chongyi".into(), "password".into(), None)
+            .await
+            .unwrap();
+        assert!(matches!(
+            manager
+                .verify_two_factor_throttled(id, "000000".into(), None)
+                .await,
+            Err(Error::InvalidTwoFactorCode)
+        ));
+        assert!(matches!(
+            manager
+                .verify_two_factor_throttled(id, enrollment.recovery_codes[0].clone(), None)
+                .await,
+            Err(Error::TooManyLoginAttempts { .. })
+        ));
+
+        manager
+            .unlock_login(AttemptKey::Username("chongyi".into()))
+            .await
+            .unwrap();
+        manager
+            .verify_two_factor_throttled(id, enrollment.recovery_codes[0].clone(), None)
+            .await
+            .unwrap();
+
+        // 关闭两步验证时密码错误同样计入失败记录
+        for _ in 0..2 {
+            assert!(matches!(
+                manager
+                    .disable_two_factor_throttled(id, "wrong".into(), None, None, None)
+                    .await,
+                Err(Error::IncorrectPassword)
+            ));
+        }
+        assert!(matches!(
+            manager
+                .disable_two_factor_throttled(id, "password".into(), None, None, None)
+                .await,
+            Err(Error::TooManyLoginAttempts { .. })
+        ));
+
+        manager
+            .unlock_login(AttemptKey::Username("chongyi".into()))
+            .await
+            .unwrap();
+        manager
+            .disable_two_factor_throttled(id, "password".into(), None, None, None)
+            .await
+            .unwrap();
+
+        // 确认开启两步验证时验证码错误同样计入失败记录
+        manager
+            .enroll_two_factor(id, "password".into(), "Inspirer")
+            .await
+            .unwrap();
+        for _ in 0..2 {
+            assert!(matches!(
+                manager
+                    .confirm_two_factor_throttled(id, "000000".into(), None, None)
+                    .await,
+                Err(Error::InvalidTwoFactorCode)
+            ));
+        }
+        assert!(matches!(
+            manager
+                .confirm_two_factor_throttled(id, "000000".into(), None, None)
+                .await,
+            Err(Error::TooManyLoginAttempts { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_login_challenge_throttled() {
+        let mut manager = sqlite_manager().await;
+        manager.login_throttle_policy = LoginThrottlePolicy {
+            max_ip_failures: 2,
+            base_delay: Duration::zero(),
+            max_delay: Duration::zero(),
+            ..Default::default()
+        };
+        let key_pair = generate_pkcs8_keypair().unwrap();
+        let fingerprint = key_pair.public_key_fingerprint();
+        manager
+            .database
+            .create_user(
+                generate_v1_uuid(),
+                &NewUser {
+                    username: "chongyi".into(),
+                    ..Default::default()
+                },
+                key_pair.public_key.clone(),
+                fingerprint.clone(),
+            )
+            .await
+            .unwrap();
+        let signer = Ed25519KeyPair::from_pkcs8(key_pair.private_key_bytes()).unwrap();
+        let ip: IpAddr = "10.0.0.1".parse().unwrap();
+        let expiration = Duration::minutes(1);
+
+        let mut challenges = vec![];
+        for _ in 0..2 {
+            let challenge = manager
+                .create_login_challenge_throttled(fingerprint.clone(), expiration, Some(ip))
+                .await
+                .unwrap();
+            challenges.push(challenge.challenge);
+        }
+        assert!(matches!(
+            manager
+                .create_login_challenge_throttled(fingerprint.clone(), expiration, Some(ip))
+                .await,
+            Err(Error::TooManyLoginAttempts { .. })
+        ));
+
+        // 其他 IP 不受影响
+        manager
+            .create_login_challenge_throttled(
+                fingerprint.clone(),
+                expiration,
+                Some("10.0.0.2".parse().unwrap()),
+            )
+            .await
+            .unwrap();
+
+        // 使用已创建的挑战登录成功后解除限制
+        let signature = signer.sign(challenges[0].as_bytes()).as_ref().to_vec();
+        manager
+            .attempt_with_key_throttled(challenges[0].clone(), signature, Some(ip))
+            .await
+            .unwrap();
+        manager
+            .create_login_challenge_throttled(fingerprint, expiration, Some(ip))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_change_password_throttled() {
+        let mut manager = sqlite_manager().await;
+        manager.login_throttle_policy = LoginThrottlePolicy {
+            max_username_failures: 2,
+            base_delay: Duration::zero(),
+            max_delay: Duration::zero(),
+            ..Default::default()
+        };
+        let (id, _) = manager
+            .create_user_simple(
+                NewUser {
+                    username: "chongyi".into(),
+                    password: "password".into(),
+                    ..Default::default()
+                },
+                None,
+            )
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            manager
+                .verify_user_password_throttled(id, "wrong".into(), None)
+                .await,
+            Err(Error::IncorrectPassword)
+        ));
+        assert!(matches!(
+            manager
+                .change_password_throttled(
+                    id,
+                    "wrong".into(),
+                    "new-password".into(),
+                    None,
+                    None,
+                    None
+                )
+                .await,
+            Err(Error::IncorrectPassword)
+        ));
+
+        // 锁定后正确的密码同样被拒绝，且与密码登录共用失败记录
+        assert!(matches!(
+            manager
+                .change_password_throttled(
+                    id,
+                    "password".into(),
+                    "new-password".into(),
+                    None,
+                    None,
+                    None
+                )
+                .await,
+            Err(Error::TooManyLoginAttempts { .. })
+        ));
+        assert!(matches!(
+            manager
+                .attempt_throttled("chongyi".into(), "password".into(), None)
+                .await,
+            Err(Error::TooManyLoginAttempts { .. })
+        ));
+
+        manager
+            .unlock_login(AttemptKey::Username("chongyi".into()))
+            .await
+            .unwrap();
+        manager
+            .change_password_throttled(
+                id,
+                "password".into(),
+                "new-password".into(),
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        manager
+            .verify_user_password_throttled(id, "new-password".into(), None)
+            .await
+            .unwrap();
+    }
+}
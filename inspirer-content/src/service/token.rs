@@ -0,0 +1,385 @@
+use chrono::{Duration, Utc};
+use uuid::Uuid;
+
+use crate::{
+    dao::token::AccessTokenDao,
+    entity::users,
+    enumerate::audit::AuditTargetType,
+    error::{Error, InspirerContentResult},
+    manager::Manager,
+    model::{
+        audit::NewAuditLog,
+        token::{AccessTokenModel, IssuedAccessToken, NewAccessToken, ACCESS_TOKEN_PREFIX},
+    },
+    service::user::UserService,
+    util::{
+        token::{generate_token, token_hash},
+        uuid::generate_v1_uuid,
+    },
+};
+
+/// 令牌名称最大长度（字符数）
+const MAX_NAME_LENGTH: usize = 100;
+/// 最后使用时间的更新间隔，避免每次请求都写入数据库
+const LAST_USED_UPDATE_INTERVAL: i64 = 60;
+
+#[async_trait::async_trait]
+pub trait AccessTokenService {
+    /// 创建个人访问令牌，令牌明文仅在创建时返回，数据库中只保存摘要
+    ///
+    /// 审计日志的目标为新令牌，并记录令牌名称、权限范围及过期时间。
+    async fn create_access_token(
+        &self,
+        user_id: Uuid,
+        new_token: NewAccessToken,
+        audit: Option<NewAuditLog>,
+    ) -> InspirerContentResult<IssuedAccessToken>;
+    /// 获取用户的全部访问令牌（含已过期令牌）
+    async fn get_access_tokens(
+        &self,
+        user_id: Uuid,
+    ) -> InspirerContentResult<Vec<AccessTokenModel>>;
+    async fn revoke_access_token(
+        &self,
+        user_id: Uuid,
+        id: Uuid,
+        audit: Option<NewAuditLog>,
+    ) -> InspirerContentResult<()>;
+    /// 校验访问令牌，返回令牌及其所属用户并记录最后使用时间
+    ///
+    /// 已过期的令牌及已禁用用户的令牌均视为无效。
+    async fn authenticate_access_token(
+        &self,
+        token: &str,
+    ) -> InspirerContentResult<(AccessTokenModel, users::Model)>;
+}
+
+#[async_trait::async_trait]
+impl AccessTokenService for Manager {
+    async fn create_access_token(
+        &self,
+        user_id: Uuid,
+        mut new_token: NewAccessToken,
+        audit: Option<NewAuditLog>,
+    ) -> InspirerContentResult<IssuedAccessToken> {
+        let name = new_token.name.trim().to_string();
+        if name.is_empty() || name.chars().count() > MAX_NAME_LENGTH {
+            return Err(Error::InvalidAccessTokenOptions(format!(
+                "令牌名称不能为空且不能超过 {MAX_NAME_LENGTH} 个字符"
+            )));
+        }
+
+        new_token.scopes.sort();
+        new_token.scopes.dedup();
+        if new_token.scopes.is_empty() {
+            return Err(Error::InvalidAccessTokenOptions(
+                "至少需要指定一个权限范围".into(),
+            ));
+        }
+
+        if new_token
+            .expires_at
+            .is_some_and(|expires_at| expires_at <= Utc::now())
+        {
+            return Err(Error::InvalidAccessTokenOptions(
+                "过期时间必须晚于当前时间".into(),
+            ));
+        }
+
+        self.get_user_by_id(user_id)
+            .await?
+            .ok_or(Error::UserNotFound)?;
+
+        let id = generate_v1_uuid();
+        let token = format!("{ACCESS_TOKEN_PREFIX}{}", generate_token());
+
+        let scopes = serde_json::to_value(&new_token.scopes).map_err(Error::FormatError)?;
+        let audit = audit.map(|mut log| {
+            let detail = log.detail.get_or_insert_with(|| serde_json::json!({}));
+            detail["name"] = name.clone().into();
+            detail["scopes"] = scopes.clone();
+            detail["expires_at"] = serde_json::json!(new_token.expires_at);
+            log.target(AuditTargetType::AccessToken, id)
+        });
+        let hash = token_hash(&token);
+
+        self.audited(audit, move |trx| {
+            Box::pin(async move {
+                trx.create_access_token(id, user_id, name, hash, scopes, new_token.expires_at)
+                    .await
+            })
+        })
+        .await?;
+        tracing::info!("Created personal access token {id}, user = {user_id}");
+
+        Ok(IssuedAccessToken {
+            access_token: self
+                .database
+                .find_access_token_by_id(id)
+                .await?
+                .ok_or(Error::AccessTokenNotFound)?,
+            token,
+        })
+    }
+
+    async fn get_access_tokens(
+        &self,
+        user_id: Uuid,
+    ) -> InspirerContentResult<Vec<AccessTokenModel>> {
+        self.database.get_user_access_tokens(user_id).await
+    }
+
+    async fn revoke_access_token(
+        &self,
+        user_id: Uuid,
+        id: Uuid,
+        audit: Option<NewAuditLog>,
+    ) -> InspirerContentResult<()> {
+        self.audited(audit, move |trx| {
+            Box::pin(async move {
+                if !trx.delete_access_token(user_id, id).await? {
+                    return Err(Error::AccessTokenNotFound);
+                }
+
+                Ok(())
+            })
+        })
+        .await?;
+        tracing::info!("Revoked personal access token {id}, user = {user_id}");
+
+        Ok(())
+    }
+
+    async fn authenticate_access_token(
+        &self,
+        token: &str,
+    ) -> InspirerContentResult<(AccessTokenModel, users::Model)> {
+        if !token.starts_with(ACCESS_TOKEN_PREFIX) {
+            return Err(Error::InvalidAccessToken);
+        }
+
+        let now = Utc::now();
+        let access_token = self
+            .database
+            .find_access_token_by_hash(token_hash(token))
+            .await?
+            .filter(|access_token| access_token.is_active(now))
+            .ok_or(Error::InvalidAccessToken)?;
+
+        let user = self
+            .get_user_by_id(access_token.user_id)
+            .await?
+            .filter(|user| !user.is_disabled())
+            .ok_or(Error::InvalidAccessToken)?;
+
+        if access_token.last_used_at.is_none_or(|last_used_at| {
+            now - last_used_at >= Duration::seconds(LAST_USED_UPDATE_INTERVAL)
+        }) {
+            self.database
+                .update_access_token_last_used(access_token.id, now)
+                .await?;
+        }
+
+        Ok((access_token, user))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sea_orm::{sea_query::Expr, EntityTrait};
+
+    use super::*;
+    use crate::{
+        entity::personal_access_tokens, enumerate::token::TokenScope,
+        manager::tests::sqlite_manager, model::user::NewUser,
+    };
+
+    async fn create_user(manager: &Manager) -> Uuid {
+        manager
+            .create_user_simple(
+                NewUser {
+                    username: "chongyi".into(),
+                    password: "password".into(),
+                    ..Default::default()
+                },
+                None,
+            )
+            .await
+            .unwrap()
+            .0
+    }
+
+    #[tokio::test]
+    async fn test_access_token() {
+        let manager = sqlite_manager().await;
+        let user_id = create_user(&manager).await;
+
+        let issued = manager
+            .create_access_token(
+                user_id,
+                NewAccessToken {
+                    name: " ci ".into(),
+                    scopes: vec![
+                        TokenScope::ContentWrite,
+                        TokenScope::ContentRead,
+                        TokenScope::ContentWrite,
+                    ],
+                    expires_at: None,
+                },
+                None,
+            )
+            .await
+            .unwrap();
+        assert!(issued.token.starts_with(ACCESS_TOKEN_PREFIX));
+        assert_eq!(issued.access_token.name, "ci");
+        assert_eq!(
+            issued.access_token.scopes(),
+            vec![TokenScope::ContentRead, TokenScope::ContentWrite]
+        );
+        assert!(issued.access_token.last_used_at.is_none());
+
+        let (access_token, user) = manager
+            .authenticate_access_token(&issued.token)
+            .await
+            .unwrap();
+        assert_eq!(access_token.id, issued.access_token.id);
+        assert_eq!(user.id, user_id);
+
+        let tokens = manager.get_access_tokens(user_id).await.unwrap();
+        assert_eq!(tokens.len(), 1);
+        assert!(tokens[0].last_used_at.is_some());
+
+        assert!(matches!(
+            manager.authenticate_access_token("ipat_invalid").await,
+            Err(Error::InvalidAccessToken)
+        ));
+        assert!(matches!(
+            manager
+                .authenticate_access_token(issued.token.trim_start_matches(ACCESS_TOKEN_PREFIX))
+                .await,
+            Err(Error::InvalidAccessToken)
+        ));
+
+        // 只能吊销自己的令牌
+        assert!(matches!(
+            manager
+                .revoke_access_token(generate_v1_uuid(), issued.access_token.id, None)
+                .await,
+            Err(Error::AccessTokenNotFound)
+        ));
+        manager
+            .revoke_access_token(user_id, issued.access_token.id, None)
+            .await
+            .unwrap();
+        assert!(matches!(
+            manager.authenticate_access_token(&issued.token).await,
+            Err(Error::InvalidAccessToken)
+        ));
+        assert!(manager.get_access_tokens(user_id).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_access_token_options() {
+        let manager = sqlite_manager().await;
+        let user_id = create_user(&manager).await;
+
+        for new_token in [
+            NewAccessToken {
+                name: " ".into(),
+                scopes: vec![TokenScope::ContentRead],
+                ..Default::default()
+            },
+            NewAccessToken {
+                name: "ci".into(),
+                scopes: vec![],
+                ..Default::default()
+            },
+            NewAccessToken {
+                name: "ci".into(),
+                scopes: vec![TokenScope::ContentRead],
+                expires_at: Some(Utc::now() - Duration::days(1)),
+            },
+        ] {
+            assert!(matches!(
+                manager.create_access_token(user_id, new_token, None).await,
+                Err(Error::InvalidAccessTokenOptions(_))
+            ));
+        }
+
+        assert!(matches!(
+            manager
+                .create_access_token(
+                    generate_v1_uuid(),
+                    NewAccessToken {
+                        name: "ci".into(),
+                        scopes: vec![TokenScope::ContentRead],
+                        ..Default::default()
+                    },
+                    None,
+                )
+                .await,
+            Err(Error::UserNotFound)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_access_token_expired_or_disabled() {
+        let manager = sqlite_manager().await;
+        let user_id = create_user(&manager).await;
+
+        let new_access_token = || NewAccessToken {
+            name: "ci".into(),
+            scopes: vec![TokenScope::ContentRead],
+            expires_at: Some(Utc::now() + Duration::days(1)),
+        };
+        let issued = manager
+            .create_access_token(user_id, new_access_token(), None)
+            .await
+            .unwrap();
+        manager
+            .authenticate_access_token(&issued.token)
+            .await
+            .unwrap();
+
+        // 禁用用户时吊销其令牌，重新启用后旧令牌仍然无效
+        manager
+            .set_user_disabled(user_id, true, None)
+            .await
+            .unwrap();
+        assert!(matches!(
+            manager.authenticate_access_token(&issued.token).await,
+            Err(Error::InvalidAccessToken)
+        ));
+        manager
+            .set_user_disabled(user_id, false, None)
+            .await
+            .unwrap();
+        assert!(matches!(
+            manager.authenticate_access_token(&issued.token).await,
+            Err(Error::InvalidAccessToken)
+        ));
+        assert!(manager.get_access_tokens(user_id).await.unwrap().is_empty());
+
+        let issued = manager
+            .create_access_token(user_id, new_access_token(), None)
+            .await
+            .unwrap();
+
+        personal_access_tokens::Entity::update_many()
+            .col_expr(
+                personal_access_tokens::Column::ExpiresAt,
+                Expr::value(Utc::now() - Duration::seconds(1)),
+            )
+            .exec(&manager.database)
+            .await
+            .unwrap();
+        assert!(matches!(
+            manager.authenticate_access_token(&issued.token).await,
+            Err(Error::InvalidAccessToken)
+        ));
+
+        // 删除用户时一并删除其令牌
+        manager.delete_user(user_id, None, None).await.unwrap();
+        assert!(manager.get_access_tokens(user_id).await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,418 @@
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::{
+    dao::{session::SessionDao, user::UserDao},
+    entity::users,
+    error::{Error, InspirerContentResult},
+    manager::Manager,
+    model::{audit::NewAuditLog, user::TwoFactorEnrollment},
+    service::user::UserService,
+    util::totp::{
+        base32_encode, generate_recovery_codes, generate_secret, otpauth_uri, recovery_code_hash,
+        verify_totp, TOTP_DIGITS,
+    },
+};
+
+/// 每次登记生成的恢复码数量
+const RECOVERY_CODE_COUNT: usize = 10;
+
+#[async_trait::async_trait]
+pub trait TwoFactorService {
+    /// 生成新的 TOTP 密钥及恢复码，需校验当前密码，`issuer` 显示在验证器应用中
+    ///
+    /// 登记后需调用 `confirm_two_factor` 提交验证码才会开启两步验证，重复登记时覆盖尚未确认的登记。
+    async fn enroll_two_factor(
+        &self,
+        id: Uuid,
+        password: String,
+        issuer: &str,
+    ) -> InspirerContentResult<TwoFactorEnrollment>;
+    async fn confirm_two_factor(
+        &self,
+        id: Uuid,
+        code: String,
+        audit: Option<NewAuditLog>,
+    ) -> InspirerContentResult<users::Model>;
+    /// 校验登录时提交的验证码或恢复码，恢复码使用后失效
+    async fn verify_two_factor(
+        &self,
+        id: Uuid,
+        code: String,
+    ) -> InspirerContentResult<users::Model>;
+    /// 用户关闭两步验证，需校验当前密码；除 `current_session` 外的会话随之失效
+    async fn disable_two_factor(
+        &self,
+        id: Uuid,
+        password: String,
+        current_session: Option<Uuid>,
+        audit: Option<NewAuditLog>,
+    ) -> InspirerContentResult<()>;
+    /// 重置用户的两步验证，用于用户丢失验证器及恢复码时由管理员处理
+    async fn reset_two_factor(
+        &self,
+        id: Uuid,
+        audit: Option<NewAuditLog>,
+    ) -> InspirerContentResult<()>;
+}
+
+#[async_trait::async_trait]
+impl TwoFactorService for Manager {
+    async fn enroll_two_factor(
+        &self,
+        id: Uuid,
+        password: String,
+        issuer: &str,
+    ) -> InspirerContentResult<TwoFactorEnrollment> {
+        let user = self.verify_user_password(id, password).await?;
+        if user.two_factor_enabled() {
+            return Err(Error::TwoFactorAlreadyEnabled);
+        }
+
+        let secret = generate_secret();
+        let recovery_codes = generate_recovery_codes(RECOVERY_CODE_COUNT);
+
+        self.database
+            .update_user_totp(
+                id,
+                Some(self.secret_cipher()?.encrypt(&secret, id.as_bytes())?),
+                Some(
+                    recovery_codes
+                        .iter()
+                        .map(|code| recovery_code_hash(code))
+                        .collect(),
+                ),
+            )
+            .await?;
+
+        Ok(TwoFactorEnrollment {
+            secret: base32_encode(&secret),
+            otpauth_uri: otpauth_uri(issuer, &user.username, &secret),
+            recovery_codes,
+        })
+    }
+
+    async fn confirm_two_factor(
+        &self,
+        id: Uuid,
+        code: String,
+        audit: Option<NewAuditLog>,
+    ) -> InspirerContentResult<users::Model> {
+        let user = self.get_user_by_id(id).await?.ok_or(Error::UserNotFound)?;
+        if user.two_factor_enabled() {
+            return Err(Error::TwoFactorAlreadyEnabled);
+        }
+
+        self.verify_totp_code(&user, &code).await?;
+        self.audited(audit, move |trx| {
+            Box::pin(async move { trx.enable_user_totp(id, Utc::now()).await })
+        })
+        .await?;
+        tracing::info!("Enabled two-factor authentication, user = {id}");
+
+        self.get_user_by_id(id).await?.ok_or(Error::UserNotFound)
+    }
+
+    async fn verify_two_factor(
+        &self,
+        id: Uuid,
+        code: String,
+    ) -> InspirerContentResult<users::Model> {
+        let user = self.get_user_by_id(id).await?.ok_or(Error::UserNotFound)?;
+        if !user.two_factor_enabled() {
+            return Err(Error::TwoFactorNotEnabled);
+        }
+
+        if user.is_disabled() {
+            return Err(Error::UserDisabled);
+        }
+
+        if code.trim().len() == TOTP_DIGITS as usize {
+            self.verify_totp_code(&user, &code).await?;
+        } else {
+            let hash = recovery_code_hash(&code);
+            let mut recovery_codes = user.recovery_code_hashes();
+            let before = recovery_codes.len();
+            recovery_codes.retain(|code| *code != hash);
+
+            if recovery_codes.len() == before {
+                return Err(Error::InvalidTwoFactorCode);
+            }
+
+            // 恢复码已被并发使用时更新失败
+            let previous = user.totp_recovery_codes.clone().unwrap_or_default();
+            if !self
+                .database
+                .update_user_recovery_codes(id, previous, recovery_codes.clone())
+                .await?
+            {
+                return Err(Error::InvalidTwoFactorCode);
+            }
+            tracing::info!(
+                "Used recovery code, user = {id}, remaining = {}",
+                recovery_codes.len()
+            );
+        }
+
+        Ok(user)
+    }
+
+    async fn disable_two_factor(
+        &self,
+        id: Uuid,
+        password: String,
+        current_session: Option<Uuid>,
+        audit: Option<NewAuditLog>,
+    ) -> InspirerContentResult<()> {
+        let user = self.verify_user_password(id, password).await?;
+        if !user.two_factor_enabled() {
+            return Err(Error::TwoFactorNotEnabled);
+        }
+
+        self.audited(audit, move |trx| {
+            Box::pin(async move {
+                trx.update_user_totp(id, None, None).await?;
+                match current_session {
+                    Some(session_id) => trx.revoke_other_user_sessions(id, session_id).await,
+                    None => trx.revoke_user_sessions(id).await,
+                }
+            })
+        })
+        .await?;
+        tracing::info!("Disabled two-factor authentication, user = {id}");
+
+        Ok(())
+    }
+
+    async fn reset_two_factor(
+        &self,
+        id: Uuid,
+        audit: Option<NewAuditLog>,
+    ) -> InspirerContentResult<()> {
+        self.audited(audit, move |trx| {
+            Box::pin(async move { trx.update_user_totp(id, None, None).await })
+        })
+        .await?;
+        tracing::info!("Reset two-factor authentication, user = {id}");
+
+        Ok(())
+    }
+}
+
+impl Manager {
+    /// 校验 TOTP 验证码并记录其时间步，同一验证码只能使用一次
+    async fn verify_totp_code(&self, user: &users::Model, code: &str) -> InspirerContentResult<()> {
+        let encrypted = user
+            .totp_secret
+            .as_ref()
+            .ok_or(Error::TwoFactorNotEnabled)?;
+        let secret = self
+            .secret_cipher()?
+            .decrypt(encrypted, user.id.as_bytes())?;
+
+        let step = verify_totp(&secret, code, Utc::now(), user.totp_last_step)
+            .ok_or(Error::InvalidTwoFactorCode)?;
+
+        if !self
+            .database
+            .update_user_totp_last_step(user.id, step)
+            .await?
+        {
+            return Err(Error::InvalidTwoFactorCode);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        manager::tests::sqlite_manager,
+        model::user::NewUser,
+        service::session::SessionService,
+        util::totp::{time_step, totp},
+    };
+
+    fn secret_of(manager: &Manager, user: &users::Model) -> Vec<u8> {
+        manager
+            .secret_cipher()
+            .unwrap()
+            .decrypt(user.totp_secret.as_ref().unwrap(), user.id.as_bytes())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_two_factor() {
+        let manager = sqlite_manager().await;
+        let (id, _) = manager
+            .create_user_simple(
+                NewUser {
+                    username: "chongyi".into(),
+                    password: "password".into(),
+                    ..Default::default()
+                },
+                None,
+            )
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            manager
+                .enroll_two_factor(id, "wrong".into(), "Inspirer")
+                .await,
+            Err(Error::IncorrectPassword)
+        ));
+        let enrollment = manager
+            .enroll_two_factor(id, "password".into(), "Inspirer")
+            .await
+            .unwrap();
+        assert!(enrollment
+            .otpauth_uri
+            .starts_with("otpauth://totp/Inspirer:chongyi?secret="));
+        assert_eq!(enrollment.recovery_codes.len(), RECOVERY_CODE_COUNT);
+
+        // 未确认前不要求两步验证
+        let user = manager.get_user_by_id(id).await.unwrap().unwrap();
+        assert!(!user.two_factor_enabled());
+        assert_ne!(
+            user.totp_secret.as_deref(),
+            Some(enrollment.secret.as_bytes())
+        );
+        assert!(matches!(
+            manager.verify_two_factor(id, "000000".into()).await,
+            Err(Error::TwoFactorNotEnabled)
+        ));
+
+        let secret = secret_of(&manager, &user);
+        assert_eq!(base32_encode(&secret), enrollment.secret);
+
+        let step = time_step(Utc::now());
+        let user = manager
+            .confirm_two_factor(id, totp(&secret, step - 1), None)
+            .await
+            .unwrap();
+        assert!(user.two_factor_enabled());
+        assert!(matches!(
+            manager
+                .enroll_two_factor(id, "password".into(), "Inspirer")
+                .await,
+            Err(Error::TwoFactorAlreadyEnabled)
+        ));
+
+        // 已使用的时间步不能再次使用
+        assert!(matches!(
+            manager.verify_two_factor(id, totp(&secret, step - 1)).await,
+            Err(Error::InvalidTwoFactorCode)
+        ));
+        manager
+            .verify_two_factor(id, totp(&secret, step))
+            .await
+            .unwrap();
+        assert!(matches!(
+            manager.verify_two_factor(id, totp(&secret, step)).await,
+            Err(Error::InvalidTwoFactorCode)
+        ));
+
+        // 恢复码只能使用一次
+        let recovery_code = enrollment.recovery_codes[0].to_uppercase();
+        manager
+            .verify_two_factor(id, recovery_code.clone())
+            .await
+            .unwrap();
+        assert!(matches!(
+            manager.verify_two_factor(id, recovery_code).await,
+            Err(Error::InvalidTwoFactorCode)
+        ));
+        let user = manager.get_user_by_id(id).await.unwrap().unwrap();
+        assert_eq!(user.recovery_code_hashes().len(), RECOVERY_CODE_COUNT - 1);
+
+        // 并发使用同一恢复码时仅有一次成功
+        let recovery_code = enrollment.recovery_codes[1].clone();
+        let results = tokio::join!(
+            manager.verify_two_factor(id, recovery_code.clone()),
+            manager.verify_two_factor(id, recovery_code),
+        );
+        assert!(results.0.is_ok() ^ results.1.is_ok());
+        let user = manager.get_user_by_id(id).await.unwrap().unwrap();
+        assert_eq!(user.recovery_code_hashes().len(), RECOVERY_CODE_COUNT - 2);
+
+        // 恢复码已变化时不覆盖
+        assert!(!manager
+            .database
+            .update_user_recovery_codes(id, serde_json::json!([]), vec![])
+            .await
+            .unwrap());
+
+        let expires_at = Utc::now() + chrono::Duration::days(1);
+        let current = manager
+            .create_session(id, String::new(), expires_at, None)
+            .await
+            .unwrap()
+            .session;
+        manager
+            .create_session(id, String::new(), expires_at, None)
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            manager
+                .disable_two_factor(id, "wrong".into(), Some(current.id), None)
+                .await,
+            Err(Error::IncorrectPassword)
+        ));
+        manager
+            .disable_two_factor(id, "password".into(), Some(current.id), None)
+            .await
+            .unwrap();
+
+        let user = manager.get_user_by_id(id).await.unwrap().unwrap();
+        assert!(!user.two_factor_enabled());
+        assert!(user.totp_secret.is_none());
+        assert!(user.recovery_code_hashes().is_empty());
+
+        // 其他会话随之失效
+        let sessions = manager.get_active_sessions(id).await.unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].id, current.id);
+    }
+
+    #[tokio::test]
+    async fn test_two_factor_secret_key() {
+        let mut manager = sqlite_manager().await;
+        let (id, _) = manager
+            .create_user_simple(
+                NewUser {
+                    username: "chongyi".into(),
+                    password: "password".into(),
+                    ..Default::default()
+                },
+                None,
+            )
+            .await
+            .unwrap();
+
+        manager
+            .enroll_two_factor(id, "password".into(), "Inspirer")
+            .await
+            .unwrap();
+        let user = manager.get_user_by_id(id).await.unwrap().unwrap();
+        let code = totp(&secret_of(&manager, &user), time_step(Utc::now()));
+
+        // 加密密钥变更后无法解密已保存的密钥
+        manager.secret_cipher = Some(crate::util::cipher::SecretCipher::new(b"other"));
+        assert!(matches!(
+            manager.confirm_two_factor(id, code.clone(), None).await,
+            Err(Error::DecryptSecretError)
+        ));
+
+        // 未配置加密密钥时无法使用两步验证，其他功能不受影响
+        manager.secret_cipher = None;
+        assert!(matches!(
+            manager.confirm_two_factor(id, code, None).await,
+            Err(Error::SecretKeyNotConfigured)
+        ));
+        assert!(manager.get_user_by_id(id).await.unwrap().is_some());
+    }
+}
@@ -1,31 +1,107 @@
+use chrono::{DateTime, Duration, Utc};
 use uuid::Uuid;
 
 use crate::{
-    dao::user::UserDao,
+    dao::{
+        content::ContentDao, session::SessionDao, signature::NonceDao, token::AccessTokenDao,
+        user::UserDao,
+    },
     entity::users,
+    enumerate::{audit::AuditTargetType, user::UserRole},
     error::{Error, InspirerContentResult},
     manager::Manager,
-    model::user::NewUser,
+    model::{
+        audit::NewAuditLog,
+        user::{NewUser, UpdateUser},
+    },
+    service::audit::write_audit_log,
     util::{
-        hash::{password_hash, verify_password},
-        signature::{generate_pkcs8_keypair, private_key_to_pem},
+        hash::{password_hash, sha256, verify_password},
+        signature::{generate_pkcs8_keypair, parse_public_key, private_key_to_pem},
         uuid::generate_v1_uuid,
     },
 };
 
 #[async_trait::async_trait]
 pub trait UserService {
-    async fn create_user_simple(&self, new_user: NewUser) -> InspirerContentResult<(Uuid, String)>;
+    /// 创建用户，返回用户 ID 及 PEM 格式的私钥；密码须符合密码策略，审计日志的目标为新用户
+    async fn create_user_simple(
+        &self,
+        new_user: NewUser,
+        audit: Option<NewAuditLog>,
+    ) -> InspirerContentResult<(Uuid, String)>;
+    async fn get_users(&self) -> InspirerContentResult<Vec<users::Model>>;
     async fn get_user_by_id(&self, id: Uuid) -> InspirerContentResult<Option<users::Model>>;
     async fn get_user_by_username(
         &self,
         username: String,
     ) -> InspirerContentResult<Option<users::Model>>;
+    /// 使用密码登录，禁用管理员密码登录时管理员无法通过该方式登录
     async fn attempt(
         &self,
         username: String,
         password: String,
     ) -> InspirerContentResult<users::Model>;
+    async fn verify_user_password(
+        &self,
+        id: Uuid,
+        password: String,
+    ) -> InspirerContentResult<users::Model>;
+    /// 为用户生成新的密钥对并返回 PEM 格式的私钥，原公钥在宽限期 `grace_period` 内仍然有效
+    ///
+    /// 宽限期不大于零时原公钥立即失效。
+    async fn rotate_keypair(
+        &self,
+        id: Uuid,
+        grace_period: Duration,
+        audit: Option<NewAuditLog>,
+    ) -> InspirerContentResult<String>;
+    /// 使用用户自行生成的公钥（PEM 或 OpenSSH 格式）替换原公钥，原公钥在宽限期内仍然有效
+    async fn register_public_key(
+        &self,
+        id: Uuid,
+        public_key: &str,
+        grace_period: Duration,
+        audit: Option<NewAuditLog>,
+    ) -> InspirerContentResult<users::Model>;
+    async fn update_user(
+        &self,
+        id: Uuid,
+        update_user: UpdateUser,
+        audit: Option<NewAuditLog>,
+    ) -> InspirerContentResult<users::Model>;
+    /// 用户修改密码，需校验当前密码，除 `current_session` 外的会话及全部个人访问令牌随之失效
+    async fn change_password(
+        &self,
+        id: Uuid,
+        current_password: String,
+        new_password: String,
+        current_session: Option<Uuid>,
+        audit: Option<NewAuditLog>,
+    ) -> InspirerContentResult<()>;
+    /// 重置密码，用户的全部会话及个人访问令牌随之失效；密码须符合密码策略
+    async fn set_password(
+        &self,
+        id: Uuid,
+        password: String,
+        audit: Option<NewAuditLog>,
+    ) -> InspirerContentResult<()>;
+    /// 禁用或启用用户，禁用时用户的全部会话及个人访问令牌随之失效
+    async fn set_user_disabled(
+        &self,
+        id: Uuid,
+        disabled: bool,
+        audit: Option<NewAuditLog>,
+    ) -> InspirerContentResult<()>;
+    /// 删除用户，用户的内容转移给 `reassign_to`，返回转移的内容数量
+    ///
+    /// 用户仍拥有内容且未指定新所有者时返回 `UserHasContents`，审计日志中记录转移的内容数量。
+    async fn delete_user(
+        &self,
+        id: Uuid,
+        reassign_to: Option<Uuid>,
+        audit: Option<NewAuditLog>,
+    ) -> InspirerContentResult<u64>;
 }
 
 #[async_trait::async_trait]
@@ -33,7 +109,10 @@ impl UserService for Manager {
     async fn create_user_simple(
         &self,
         mut new_user: NewUser,
+        audit: Option<NewAuditLog>,
     ) -> InspirerContentResult<(Uuid, String)> {
+        self.password_policy.check(&new_user.password)?;
+
         // 生成 ID
         let id = generate_v1_uuid();
 
@@ -47,17 +126,23 @@ impl UserService for Manager {
         // 对 Password 进行 hash
         new_user.password = password_hash(new_user.password.as_str())?;
 
-        self.database
-            .create_user(id, &new_user, public_key, public_key_fingerprint)
-            .await?;
+        let audit = audit.map(|log| log.target(AuditTargetType::User, id));
+        self.audited(audit, move |trx| {
+            Box::pin(async move {
+                trx.create_user(id, &new_user, public_key, public_key_fingerprint)
+                    .await
+            })
+        })
+        .await?;
 
         Ok((id, private_key_to_pem(&private_key)?))
     }
 
-    async fn get_user_by_id(
-        &self,
-        id: Uuid,
-    ) -> InspirerContentResult<Option<users::Model>> {
+    async fn get_users(&self) -> InspirerContentResult<Vec<users::Model>> {
+        self.database.get_users().await
+    }
+
+    async fn get_user_by_id(&self, id: Uuid) -> InspirerContentResult<Option<users::Model>> {
         self.database.get_user_by_id(id).await
     }
 
@@ -78,10 +163,706 @@ impl UserService for Manager {
             .await?
             .ok_or(Error::UserNotFoundOrPasswordError)?;
 
-        if verify_password(&password, user.password.as_str())? {
-            Ok(user)
-        } else {
-            Err(Error::UserNotFoundOrPasswordError)
+        if !verify_password(&password, user.password.as_str())? {
+            return Err(Error::UserNotFoundOrPasswordError);
+        }
+
+        if user.is_disabled() {
+            return Err(Error::UserDisabled);
+        }
+
+        if self.disable_admin_password_login && user.role == UserRole::Admin {
+            return Err(Error::PasswordLoginDisabled);
+        }
+
+        Ok(user)
+    }
+
+    async fn verify_user_password(
+        &self,
+        id: Uuid,
+        password: String,
+    ) -> InspirerContentResult<users::Model> {
+        let user = self.get_user_by_id(id).await?.ok_or(Error::UserNotFound)?;
+
+        if !verify_password(&password, user.password.as_str())? {
+            return Err(Error::IncorrectPassword);
         }
+
+        Ok(user)
+    }
+
+    async fn rotate_keypair(
+        &self,
+        id: Uuid,
+        grace_period: Duration,
+        audit: Option<NewAuditLog>,
+    ) -> InspirerContentResult<String> {
+        let key_pair = generate_pkcs8_keypair()?;
+        let public_key = key_pair.public_key_bytes().to_vec();
+        let fingerprint = key_pair.public_key_fingerprint();
+
+        self.audited(audit, move |trx| {
+            Box::pin(async move {
+                trx.update_user_public_key(
+                    id,
+                    public_key,
+                    fingerprint,
+                    previous_key_expires_at(grace_period),
+                )
+                .await
+            })
+        })
+        .await?;
+
+        private_key_to_pem(key_pair.private_key_bytes())
+    }
+
+    async fn register_public_key(
+        &self,
+        id: Uuid,
+        public_key: &str,
+        grace_period: Duration,
+        audit: Option<NewAuditLog>,
+    ) -> InspirerContentResult<users::Model> {
+        let public_key = parse_public_key(public_key)?;
+        let fingerprint = sha256(&public_key);
+
+        // 宽限期内的旧公钥同样不可被重复使用
+        if self.find_user_by_fingerprint(&fingerprint).await?.is_some() {
+            return Err(Error::DatabaseWriteConflict);
+        }
+
+        self.audited(audit, move |trx| {
+            Box::pin(async move {
+                trx.update_user_public_key(
+                    id,
+                    public_key,
+                    fingerprint,
+                    previous_key_expires_at(grace_period),
+                )
+                .await
+            })
+        })
+        .await
+    }
+
+    async fn update_user(
+        &self,
+        id: Uuid,
+        update_user: UpdateUser,
+        audit: Option<NewAuditLog>,
+    ) -> InspirerContentResult<users::Model> {
+        update_user.validate()?;
+
+        self.audited(audit, move |trx| {
+            Box::pin(async move { trx.update_user(id, &update_user).await })
+        })
+        .await
+    }
+
+    async fn change_password(
+        &self,
+        id: Uuid,
+        current_password: String,
+        new_password: String,
+        current_session: Option<Uuid>,
+        audit: Option<NewAuditLog>,
+    ) -> InspirerContentResult<()> {
+        self.verify_user_password(id, current_password).await?;
+
+        self.password_policy.check(&new_password)?;
+        let password = password_hash(new_password.as_str())?;
+
+        self.audited(audit, move |trx| {
+            Box::pin(async move {
+                trx.update_user_password(id, password).await?;
+                match current_session {
+                    Some(session_id) => trx.revoke_other_user_sessions(id, session_id).await?,
+                    None => trx.revoke_user_sessions(id).await?,
+                }
+                trx.delete_user_access_tokens(id).await
+            })
+        })
+        .await
+    }
+
+    async fn set_password(
+        &self,
+        id: Uuid,
+        password: String,
+        audit: Option<NewAuditLog>,
+    ) -> InspirerContentResult<()> {
+        self.password_policy.check(&password)?;
+
+        let password = password_hash(password.as_str())?;
+
+        self.audited(audit, move |trx| {
+            Box::pin(async move {
+                trx.update_user_password(id, password).await?;
+                trx.revoke_user_sessions(id).await?;
+                trx.delete_user_access_tokens(id).await
+            })
+        })
+        .await
+    }
+
+    async fn set_user_disabled(
+        &self,
+        id: Uuid,
+        disabled: bool,
+        audit: Option<NewAuditLog>,
+    ) -> InspirerContentResult<()> {
+        self.audited(audit, move |trx| {
+            Box::pin(async move {
+                if disabled {
+                    trx.update_user_disabled_at(id, Some(Utc::now())).await?;
+                    trx.revoke_user_sessions(id).await?;
+                    trx.delete_user_access_tokens(id).await
+                } else {
+                    trx.update_user_disabled_at(id, None).await
+                }
+            })
+        })
+        .await
+    }
+
+    async fn delete_user(
+        &self,
+        id: Uuid,
+        reassign_to: Option<Uuid>,
+        audit: Option<NewAuditLog>,
+    ) -> InspirerContentResult<u64> {
+        if let Some(to) = reassign_to {
+            let target = self.get_user_by_id(to).await?.ok_or(Error::UserNotFound)?;
+            if target.id == id {
+                return Err(Error::InvalidReassignTarget);
+            }
+        }
+
+        self.audited(None, move |trx| {
+            Box::pin(async move {
+                let reassigned = match reassign_to {
+                    Some(to) => trx.reassign_contents(id, to).await?,
+                    None if trx.count_owner_contents(id).await? > 0 => {
+                        return Err(Error::UserHasContents)
+                    }
+                    None => 0,
+                };
+
+                trx.delete_user_sessions(id).await?;
+                trx.delete_user_access_tokens(id).await?;
+                trx.delete_user_nonces(id).await?;
+                trx.delete_user(id).await?;
+
+                if let Some(mut log) = audit {
+                    log.detail.get_or_insert_with(|| serde_json::json!({}))["reassigned"] =
+                        reassigned.into();
+                    write_audit_log(trx, log).await?;
+                }
+
+                Ok(reassigned)
+            })
+        })
+        .await
+    }
+}
+
+impl Manager {
+    /// 根据公钥指纹查找用户，返回用户及匹配的公钥
+    pub(crate) async fn find_user_by_fingerprint(
+        &self,
+        fingerprint: &[u8],
+    ) -> InspirerContentResult<Option<(users::Model, Vec<u8>)>> {
+        let now = Utc::now();
+
+        if let Some(user) = self
+            .database
+            .get_user_by_public_key(fingerprint.to_vec())
+            .await?
+        {
+            let public_key = user.public_key.clone();
+            return Ok(Some((user, public_key)));
+        }
+
+        let users = self
+            .database
+            .get_users_by_previous_public_key(fingerprint.to_vec())
+            .await?;
+
+        Ok(users.into_iter().find_map(|user| {
+            let public_key = user.public_key_for(fingerprint, now)?.to_vec();
+            Some((user, public_key))
+        }))
+    }
+}
+
+/// 原公钥的失效时间，没有宽限期时为空
+fn previous_key_expires_at(grace_period: Duration) -> Option<DateTime<Utc>> {
+    (grace_period > Duration::zero()).then(|| Utc::now() + grace_period)
+}
+
+#[cfg(test)]
+mod tests {
+    use sea_orm::{sea_query::Expr, ColumnTrait, EntityTrait, QueryFilter};
+
+    use super::*;
+    use crate::{manager::tests::sqlite_manager, model::profile::UserProfileData};
+
+    fn new_user(username: &str) -> NewUser {
+        NewUser {
+            username: username.into(),
+            password: "password".into(),
+            nickname: "nickname".into(),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_user_and_attempt() {
+        let manager = sqlite_manager().await;
+
+        let (id, _) = manager
+            .create_user_simple(new_user("chongyi"), None)
+            .await
+            .unwrap();
+
+        let user = manager
+            .attempt("chongyi".into(), "password".into())
+            .await
+            .unwrap();
+        assert_eq!(user.id, id);
+        assert_eq!(user.nickname, "nickname");
+        assert_eq!(user.role, UserRole::Author);
+
+        assert!(matches!(
+            manager.attempt("chongyi".into(), "wrong".into()).await,
+            Err(Error::UserNotFoundOrPasswordError)
+        ));
+        assert!(matches!(
+            manager.attempt("nobody".into(), "password".into()).await,
+            Err(Error::UserNotFoundOrPasswordError)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_disable_admin_password_login() {
+        let mut manager = sqlite_manager().await;
+        manager.disable_admin_password_login = true;
+
+        manager
+            .create_user_simple(
+                NewUser {
+                    role: UserRole::Admin,
+                    ..new_user("admin")
+                },
+                None,
+            )
+            .await
+            .unwrap();
+        manager
+            .create_user_simple(new_user("author"), None)
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            manager.attempt("admin".into(), "password".into()).await,
+            Err(Error::PasswordLoginDisabled)
+        ));
+        assert!(matches!(
+            manager.attempt("admin".into(), "wrong".into()).await,
+            Err(Error::UserNotFoundOrPasswordError)
+        ));
+        assert!(manager
+            .attempt("author".into(), "password".into())
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_rotate_keypair() {
+        let manager = sqlite_manager().await;
+        let (id, _) = manager
+            .create_user_simple(new_user("chongyi"), None)
+            .await
+            .unwrap();
+        let user = manager.get_user_by_id(id).await.unwrap().unwrap();
+        let old_fingerprint = user.public_key_fingerprint.clone();
+
+        manager
+            .rotate_keypair(id, Duration::hours(1), None)
+            .await
+            .unwrap();
+        let (found, public_key) = manager
+            .find_user_by_fingerprint(&old_fingerprint)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(found.id, id);
+        assert_eq!(public_key, user.public_key);
+        assert_ne!(found.public_key_fingerprint, old_fingerprint);
+
+        let (found, _) = manager
+            .find_user_by_fingerprint(&found.public_key_fingerprint)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(found.id, id);
+
+        // 没有宽限期时旧公钥立即失效
+        manager
+            .rotate_keypair(id, Duration::zero(), None)
+            .await
+            .unwrap();
+        assert!(manager
+            .find_user_by_fingerprint(&found.public_key_fingerprint)
+            .await
+            .unwrap()
+            .is_none());
+        let user = manager.get_user_by_id(id).await.unwrap().unwrap();
+        assert!(user.previous_public_key_fingerprint.is_none());
+        assert!(user.previous_key_expires_at.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_verify_user_password() {
+        let manager = sqlite_manager().await;
+        let (id, _) = manager
+            .create_user_simple(new_user("chongyi"), None)
+            .await
+            .unwrap();
+
+        let user = manager
+            .verify_user_password(id, "password".into())
+            .await
+            .unwrap();
+        assert_eq!(user.id, id);
+        assert!(matches!(
+            manager.verify_user_password(id, "wrong".into()).await,
+            Err(Error::IncorrectPassword)
+        ));
+        assert!(matches!(
+            manager
+                .verify_user_password(Uuid::new_v4(), "password".into())
+                .await,
+            Err(Error::UserNotFound)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_register_public_key() {
+        let manager = sqlite_manager().await;
+        let (id, _) = manager
+            .create_user_simple(new_user("chongyi"), None)
+            .await
+            .unwrap();
+        let (other, _) = manager
+            .create_user_simple(new_user("other"), None)
+            .await
+            .unwrap();
+        let openssh = "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIAbDPd9/lthJz88b1oqB0ueRWizBp8Sm/SAiTZQqz/d0 ci@example";
+
+        let user = manager
+            .register_public_key(id, openssh, Duration::hours(1), None)
+            .await
+            .unwrap();
+        assert_eq!(user.public_key, parse_public_key(openssh).unwrap());
+
+        assert!(matches!(
+            manager
+                .register_public_key(other, openssh, Duration::hours(1), None)
+                .await,
+            Err(Error::DatabaseWriteConflict)
+        ));
+        assert!(matches!(
+            manager
+                .register_public_key(id, "ssh-ed25519 invalid", Duration::hours(1), None)
+                .await,
+            Err(Error::InvalidPublicKey)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_update_user() {
+        let manager = sqlite_manager().await;
+        let (id, _) = manager
+            .create_user_simple(new_user("chongyi"), None)
+            .await
+            .unwrap();
+
+        let user = manager
+            .update_user(
+                id,
+                UpdateUser {
+                    nickname: Some("崇义".into()),
+                    ..Default::default()
+                },
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(user.nickname, "崇义");
+        assert_eq!(user.avatar, "");
+
+        manager
+            .set_password(id, "new-password".into(), None)
+            .await
+            .unwrap();
+        assert!(matches!(
+            manager.attempt("chongyi".into(), "password".into()).await,
+            Err(Error::UserNotFoundOrPasswordError)
+        ));
+        assert!(manager
+            .attempt("chongyi".into(), "new-password".into())
+            .await
+            .is_ok());
+
+        manager.set_user_disabled(id, true, None).await.unwrap();
+        assert!(matches!(
+            manager
+                .attempt("chongyi".into(), "new-password".into())
+                .await,
+            Err(Error::UserDisabled)
+        ));
+        manager.set_user_disabled(id, false, None).await.unwrap();
+        assert!(manager
+            .attempt("chongyi".into(), "new-password".into())
+            .await
+            .is_ok());
+
+        assert!(matches!(
+            manager
+                .update_user(Uuid::nil(), UpdateUser::default(), None)
+                .await,
+            Err(Error::UserNotFound)
+        ));
+
+        let mut profile = UserProfileData {
+            bio: "Rustacean".into(),
+            ..Default::default()
+        };
+        profile.social.insert("github".into(), "chongyi".into());
+        let user = manager
+            .update_user(
+                id,
+                UpdateUser {
+                    avatar: Some("https://example.com/avatar.png".into()),
+                    profile: Some(profile.clone()),
+                    ..Default::default()
+                },
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(user.profile().unwrap(), profile);
+        assert_eq!(user.nickname, "崇义");
+
+        // 无法解析的资料返回错误而不是空资料
+        users::Entity::update_many()
+            .col_expr(users::Column::UserProfile, Expr::value(r#"{"bio": 1}"#))
+            .filter(users::Column::Id.eq(id))
+            .exec(&manager.database)
+            .await
+            .unwrap();
+        let user = manager.get_user_by_id(id).await.unwrap().unwrap();
+        assert!(matches!(user.profile(), Err(Error::FormatError(_))));
+
+        assert!(matches!(
+            manager
+                .update_user(
+                    id,
+                    UpdateUser {
+                        avatar: Some("javascript:alert(1)".into()),
+                        ..Default::default()
+                    },
+                    None,
+                )
+                .await,
+            Err(Error::InvalidProfile(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_change_password() {
+        use crate::{
+            enumerate::token::TokenScope,
+            model::token::NewAccessToken,
+            service::{session::SessionService, token::AccessTokenService},
+        };
+
+        let manager = sqlite_manager().await;
+        let (id, _) = manager
+            .create_user_simple(new_user("chongyi"), None)
+            .await
+            .unwrap();
+        let expires_at = Utc::now() + Duration::days(1);
+        let current = manager
+            .create_session(id, String::new(), expires_at, None)
+            .await
+            .unwrap()
+            .session;
+        let other = manager
+            .create_session(id, String::new(), expires_at, None)
+            .await
+            .unwrap()
+            .session;
+        let new_access_token = || NewAccessToken {
+            name: "ci".into(),
+            scopes: vec![TokenScope::ContentRead],
+            expires_at: None,
+        };
+        manager
+            .create_access_token(id, new_access_token(), None)
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            manager
+                .change_password(
+                    id,
+                    "wrong".into(),
+                    "new-password".into(),
+                    Some(current.id),
+                    None
+                )
+                .await,
+            Err(Error::IncorrectPassword)
+        ));
+        assert!(matches!(
+            manager
+                .change_password(
+                    id,
+                    "password".into(),
+                    "short".into(),
+                    Some(current.id),
+                    None
+                )
+                .await,
+            Err(Error::InvalidPassword(_))
+        ));
+
+        manager
+            .change_password(
+                id,
+                "password".into(),
+                "new-password".into(),
+                Some(current.id),
+                None,
+            )
+            .await
+            .unwrap();
+        assert!(manager
+            .attempt("chongyi".into(), "new-password".into())
+            .await
+            .is_ok());
+
+        let sessions = manager.get_active_sessions(id).await.unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].id, current.id);
+        assert_ne!(sessions[0].id, other.id);
+
+        // 个人访问令牌随之失效，重置密码时同样如此
+        assert!(manager.get_access_tokens(id).await.unwrap().is_empty());
+        manager
+            .create_access_token(id, new_access_token(), None)
+            .await
+            .unwrap();
+        manager
+            .set_password(id, "other-password".into(), None)
+            .await
+            .unwrap();
+        assert!(manager.get_access_tokens(id).await.unwrap().is_empty());
+        assert!(manager.get_active_sessions(id).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_delete_user() {
+        use crate::{model::content::NewContent, service::content::ContentService};
+
+        let manager = sqlite_manager().await;
+        let (id, _) = manager
+            .create_user_simple(new_user("chongyi"), None)
+            .await
+            .unwrap();
+        let (other, _) = manager
+            .create_user_simple(new_user("other"), None)
+            .await
+            .unwrap();
+        let content = manager
+            .create_content(id, NewContent::default(), true)
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            manager.delete_user(id, None, None).await,
+            Err(Error::UserHasContents)
+        ));
+        assert!(matches!(
+            manager.delete_user(id, Some(id), None).await,
+            Err(Error::InvalidReassignTarget)
+        ));
+
+        assert_eq!(manager.delete_user(id, Some(other), None).await.unwrap(), 1);
+        assert!(manager.get_user_by_id(id).await.unwrap().is_none());
+        assert_eq!(
+            manager
+                .find_content_by_id(content.meta.id)
+                .await
+                .unwrap()
+                .meta
+                .owner_id,
+            other
+        );
+
+        assert!(matches!(
+            manager.delete_user(other, None, None).await,
+            Err(Error::UserHasContents)
+        ));
+        assert!(matches!(
+            manager.delete_user(Uuid::nil(), None, None).await,
+            Err(Error::UserNotFound)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_password_policy() {
+        let manager = sqlite_manager().await;
+
+        assert!(matches!(
+            manager
+                .create_user_simple(
+                    NewUser {
+                        password: String::new(),
+                        ..new_user("empty")
+                    },
+                    None
+                )
+                .await,
+            Err(Error::InvalidPassword(_))
+        ));
+
+        let (id, _) = manager
+            .create_user_simple(new_user("chongyi"), None)
+            .await
+            .unwrap();
+        assert!(matches!(
+            manager.set_password(id, "short".into(), None).await,
+            Err(Error::InvalidPassword(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_create_user_conflict() {
+        let manager = sqlite_manager().await;
+
+        manager
+            .create_user_simple(new_user("chongyi"), None)
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            manager.create_user_simple(new_user("chongyi"), None).await,
+            Err(Error::DatabaseWriteConflict)
+        ));
     }
 }
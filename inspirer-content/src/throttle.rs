@@ -0,0 +1,267 @@
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    fmt,
+    net::IpAddr,
+    sync::{Arc, Mutex},
+};
+
+use chrono::{DateTime, Duration, Utc};
+
+/// 内存中的记录数超过该值时清理过期记录
+const MEMORY_PRUNE_THRESHOLD: usize = 10_000;
+
+/// 登录限流策略
+///
+/// 每次失败后需等待一段时间才能再次尝试，等待时间从 `base_delay` 开始逐次翻倍，最长为 `max_delay`；
+/// 连续失败次数达到上限后锁定 `lockout`。超过 `lockout` 没有新的失败时失败次数清零。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LoginThrottlePolicy {
+    /// 同一用户名允许的连续失败次数
+    pub max_username_failures: u32,
+    /// 同一 IP 允许的连续失败次数
+    pub max_ip_failures: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    pub lockout: Duration,
+}
+
+impl Default for LoginThrottlePolicy {
+    fn default() -> Self {
+        LoginThrottlePolicy {
+            max_username_failures: 5,
+            max_ip_failures: 20,
+            base_delay: Duration::seconds(1),
+            max_delay: Duration::seconds(60),
+            lockout: Duration::minutes(15),
+        }
+    }
+}
+
+/// 限流对象
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AttemptKey {
+    Username(String),
+    Ip(IpAddr),
+    /// 同一 IP 创建的登录挑战，每次创建计为一次失败
+    Challenge(IpAddr),
+}
+
+impl fmt::Display for AttemptKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AttemptKey::Username(username) => write!(f, "user:{username}"),
+            AttemptKey::Ip(ip) => write!(f, "ip:{ip}"),
+            AttemptKey::Challenge(ip) => write!(f, "challenge:{ip}"),
+        }
+    }
+}
+
+/// 登录失败记录
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AttemptRecord {
+    pub failures: u32,
+    pub last_failure_at: DateTime<Utc>,
+    pub locked_until: Option<DateTime<Utc>>,
+}
+
+impl LoginThrottlePolicy {
+    pub fn max_failures(&self, key: &AttemptKey) -> u32 {
+        match key {
+            AttemptKey::Username(_) => self.max_username_failures,
+            AttemptKey::Ip(_) | AttemptKey::Challenge(_) => self.max_ip_failures,
+        }
+    }
+
+    /// 记录是否已过期，过期的记录视为不存在
+    pub fn is_stale(&self, record: &AttemptRecord, now: DateTime<Utc>) -> bool {
+        let active_until = match record.locked_until {
+            Some(locked_until) => locked_until.max(record.last_failure_at + self.lockout),
+            None => record.last_failure_at + self.lockout,
+        };
+
+        active_until <= now
+    }
+
+    /// 第 `failures` 次失败后需要等待的时间
+    pub fn backoff(&self, failures: u32) -> Duration {
+        if failures == 0 {
+            return Duration::zero();
+        }
+
+        // 超过 2^20 倍时必然已达到上限，避免溢出
+        let factor = 1i32 << (failures - 1).min(20);
+        (self.base_delay * factor).min(self.max_delay)
+    }
+
+    /// 距离允许再次尝试的剩余时间，为空表示允许尝试
+    pub fn retry_after(&self, record: &AttemptRecord, now: DateTime<Utc>) -> Option<Duration> {
+        if self.is_stale(record, now) {
+            return None;
+        }
+
+        let allowed_at = record
+            .locked_until
+            .unwrap_or(record.last_failure_at + self.backoff(record.failures));
+
+        (allowed_at > now).then(|| allowed_at - now)
+    }
+
+    /// 记录一次失败，返回新的记录
+    pub fn record_failure(
+        &self,
+        key: &AttemptKey,
+        record: Option<AttemptRecord>,
+        now: DateTime<Utc>,
+    ) -> AttemptRecord {
+        let failures = match record {
+            Some(record) if !self.is_stale(&record, now) => record.failures + 1,
+            _ => 1,
+        };
+
+        AttemptRecord {
+            failures,
+            last_failure_at: now,
+            locked_until: (failures >= self.max_failures(key)).then(|| now + self.lockout),
+        }
+    }
+}
+
+/// 内存中的登录失败记录，仅对当前进程有效
+#[derive(Debug, Clone, Default)]
+pub struct MemoryAttemptStore {
+    inner: Arc<Mutex<MemoryRecords>>,
+}
+
+#[derive(Debug, Default)]
+struct MemoryRecords {
+    records: HashMap<String, AttemptRecord>,
+    /// 记录数达到该值时清理过期记录，清理后按剩余记录数翻倍，避免每次写入都遍历
+    prune_at: usize,
+}
+
+impl MemoryAttemptStore {
+    pub fn get(&self, key: &str) -> Option<AttemptRecord> {
+        self.inner.lock().unwrap().records.get(key).cloned()
+    }
+
+    /// 在同一把锁内读取并更新记录，返回新的记录
+    pub fn record_failure(
+        &self,
+        key: &AttemptKey,
+        policy: &LoginThrottlePolicy,
+        now: DateTime<Utc>,
+    ) -> AttemptRecord {
+        let mut inner = self.inner.lock().unwrap();
+
+        if inner.records.len() >= inner.prune_at.max(MEMORY_PRUNE_THRESHOLD) {
+            inner
+                .records
+                .retain(|_, record| !policy.is_stale(record, now));
+            inner.prune_at = inner.records.len() * 2;
+        }
+
+        match inner.records.entry(key.to_string()) {
+            Entry::Occupied(mut entry) => {
+                let record = policy.record_failure(key, Some(entry.get().clone()), now);
+                entry.insert(record.clone());
+                record
+            }
+            Entry::Vacant(entry) => entry.insert(policy.record_failure(key, None, now)).clone(),
+        }
+    }
+
+    pub fn remove(&self, key: &str) -> bool {
+        self.inner.lock().unwrap().records.remove(key).is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_login_throttle_policy() {
+        let policy = LoginThrottlePolicy {
+            max_username_failures: 3,
+            ..Default::default()
+        };
+        let key = AttemptKey::Username("chongyi".into());
+        let now = Utc::now();
+
+        assert_eq!(policy.backoff(1), Duration::seconds(1));
+        assert_eq!(policy.backoff(3), Duration::seconds(4));
+        assert_eq!(policy.backoff(100), Duration::seconds(60));
+
+        let record = policy.record_failure(&key, None, now);
+        assert_eq!(record.failures, 1);
+        assert_eq!(policy.retry_after(&record, now), Some(Duration::seconds(1)));
+        assert_eq!(
+            policy.retry_after(&record, now + Duration::seconds(1)),
+            None
+        );
+
+        let record = policy.record_failure(&key, Some(record), now + Duration::seconds(1));
+        assert_eq!(record.failures, 2);
+        assert!(record.locked_until.is_none());
+
+        let locked_at = now + Duration::seconds(3);
+        let record = policy.record_failure(&key, Some(record), locked_at);
+        assert_eq!(record.locked_until, Some(locked_at + policy.lockout));
+        assert_eq!(
+            policy.retry_after(&record, locked_at + Duration::minutes(5)),
+            Some(Duration::minutes(10))
+        );
+        assert_eq!(
+            policy.retry_after(&record, locked_at + policy.lockout),
+            None
+        );
+
+        // 长时间没有失败后重新计数
+        let record = policy.record_failure(&key, Some(record), locked_at + Duration::hours(1));
+        assert_eq!(record.failures, 1);
+
+        assert_eq!(key.to_string(), "user:chongyi");
+        assert_eq!(
+            AttemptKey::Ip("127.0.0.1".parse().unwrap()).to_string(),
+            "ip:127.0.0.1"
+        );
+        assert_eq!(
+            AttemptKey::Challenge("127.0.0.1".parse().unwrap()).to_string(),
+            "challenge:127.0.0.1"
+        );
+    }
+
+    #[test]
+    fn test_memory_attempt_store() {
+        let policy = LoginThrottlePolicy {
+            max_username_failures: 2,
+            ..Default::default()
+        };
+        let store = MemoryAttemptStore::default();
+        let key = AttemptKey::Username("chongyi".into());
+        let now = Utc::now();
+
+        assert_eq!(store.record_failure(&key, &policy, now).failures, 1);
+        let record = store.record_failure(&key, &policy, now);
+        assert_eq!(record.failures, 2);
+        assert_eq!(record.locked_until, Some(now + policy.lockout));
+        assert_eq!(store.get(&key.to_string()), Some(record));
+
+        // 超过阈值后清理过期记录，且不会在之后的每次写入时都遍历
+        let stale_at = now - Duration::hours(1);
+        for i in 1..MEMORY_PRUNE_THRESHOLD {
+            let key = AttemptKey::Username(format!("stale-{i}"));
+            store.record_failure(&key, &policy, stale_at);
+        }
+        store.record_failure(&AttemptKey::Username("other".into()), &policy, now);
+        {
+            let inner = store.inner.lock().unwrap();
+            assert_eq!(inner.records.len(), 2);
+            assert_eq!(inner.prune_at, 2);
+        }
+        assert!(store.get(&key.to_string()).is_some());
+
+        assert!(store.remove(&key.to_string()));
+        assert!(!store.remove(&key.to_string()));
+    }
+}
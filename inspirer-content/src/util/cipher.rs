@@ -0,0 +1,82 @@
+use ring::{
+    aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN},
+    rand::{SecureRandom, SystemRandom},
+};
+
+use crate::error::{Error, InspirerContentResult};
+
+use super::hash::sha256;
+
+/// 加密保存在数据库中的敏感数据（AES-256-GCM）
+///
+/// 密文格式为 `nonce || ciphertext || tag`，`aad` 用于将密文绑定到所属记录。
+#[derive(Clone)]
+pub struct SecretCipher {
+    key: [u8; 32],
+}
+
+impl SecretCipher {
+    /// 由任意长度的密钥材料派生加密密钥
+    pub fn new(secret: &[u8]) -> Self {
+        let mut key = [0u8; 32];
+        key.copy_from_slice(&sha256(secret));
+
+        SecretCipher { key }
+    }
+
+    fn less_safe_key(&self) -> LessSafeKey {
+        LessSafeKey::new(UnboundKey::new(&AES_256_GCM, &self.key).expect("AES-256 密钥长度错误"))
+    }
+
+    pub fn encrypt(&self, plaintext: &[u8], aad: &[u8]) -> InspirerContentResult<Vec<u8>> {
+        let mut nonce = [0u8; NONCE_LEN];
+        SystemRandom::new().fill(&mut nonce)?;
+
+        let mut in_out = plaintext.to_vec();
+        self.less_safe_key().seal_in_place_append_tag(
+            Nonce::assume_unique_for_key(nonce),
+            Aad::from(aad),
+            &mut in_out,
+        )?;
+
+        let mut output = nonce.to_vec();
+        output.append(&mut in_out);
+
+        Ok(output)
+    }
+
+    pub fn decrypt(&self, data: &[u8], aad: &[u8]) -> InspirerContentResult<Vec<u8>> {
+        if data.len() < NONCE_LEN {
+            return Err(Error::DecryptSecretError);
+        }
+
+        let (nonce, ciphertext) = data.split_at(NONCE_LEN);
+        let nonce = Nonce::try_assume_unique_for_key(nonce).or(Err(Error::DecryptSecretError))?;
+        let mut in_out = ciphertext.to_vec();
+        let plaintext = self
+            .less_safe_key()
+            .open_in_place(nonce, Aad::from(aad), &mut in_out)
+            .or(Err(Error::DecryptSecretError))?;
+
+        Ok(plaintext.to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_secret_cipher() {
+        let cipher = SecretCipher::new(b"secret");
+        let encrypted = cipher.encrypt(b"plaintext", b"aad").unwrap();
+
+        assert_ne!(&encrypted[NONCE_LEN..], b"plaintext");
+        assert_eq!(cipher.decrypt(&encrypted, b"aad").unwrap(), b"plaintext");
+        assert!(cipher.decrypt(&encrypted, b"other").is_err());
+        assert!(SecretCipher::new(b"other")
+            .decrypt(&encrypted, b"aad")
+            .is_err());
+        assert!(cipher.decrypt(&encrypted[..4], b"aad").is_err());
+    }
+}
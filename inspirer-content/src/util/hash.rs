@@ -13,6 +13,23 @@ pub fn sha256(msg: &[u8]) -> Vec<u8> {
     hasher.finalize().as_slice().to_vec()
 }
 
+/// 转换为小写十六进制文本
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// 解析十六进制文本，格式非法时返回空
+pub fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) || !hex.is_ascii() {
+        return None;
+    }
+
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect()
+}
+
 pub fn password_hash(password: &str) -> InspirerContentResult<String> {
     let salt = SaltString::generate(&mut OsRng);
 
@@ -29,3 +46,16 @@ pub fn verify_password(password: &str, hash: &str) -> InspirerContentResult<bool
         .verify_password(password.as_ref(), &parsed_hash)
         .is_ok())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hex() {
+        assert_eq!(to_hex(&[0, 15, 255]), "000fff");
+        assert_eq!(from_hex("000fFF"), Some(vec![0, 15, 255]));
+        assert_eq!(from_hex("abc"), None);
+        assert_eq!(from_hex("zz"), None);
+    }
+}
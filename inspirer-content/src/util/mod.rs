@@ -1,3 +1,6 @@
 pub mod uuid;
 pub mod signature;
-pub mod hash;
\ No newline at end of file
+pub mod hash;
+pub mod token;
+pub mod totp;
+pub mod cipher;
\ No newline at end of file
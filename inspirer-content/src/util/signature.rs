@@ -1,11 +1,11 @@
 use crate::error::{Error, InspirerContentResult};
-use base64ct::LineEnding;
+use base64ct::{Base64, Encoding, LineEnding};
 use ring::{
     rand,
     signature::{self, KeyPair},
 };
 
-use super::hash::sha256;
+use super::hash::{sha256, to_hex};
 
 pub fn generate_pkcs8_keypair() -> InspirerContentResult<Pkcs8KeyPair> {
     let rng = rand::SystemRandom::new();
@@ -45,10 +45,96 @@ impl Pkcs8KeyPair {
     }
 }
 
+/// Ed25519 公钥 SubjectPublicKeyInfo 结构的 DER 前缀，其后为 32 字节公钥
+pub const ED25519_SPKI_PREFIX: [u8; 12] = [
+    0x30, 0x2a, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70, 0x03, 0x21, 0x00,
+];
+const OPENSSH_ED25519_KEY_TYPE: &str = "ssh-ed25519";
+const ED25519_PUBLIC_KEY_LEN: usize = 32;
+
+/// 解析 PEM（`BEGIN PUBLIC KEY`）或 OpenSSH（`ssh-ed25519 ...`）格式的 Ed25519 公钥，返回原始公钥
+pub fn parse_public_key(text: &str) -> InspirerContentResult<Vec<u8>> {
+    let text = text.trim();
+
+    let public_key = if text.starts_with("-----BEGIN") {
+        parse_pem_public_key(text)
+    } else {
+        parse_openssh_public_key(text)
+    };
+
+    public_key
+        .filter(|key| key.len() == ED25519_PUBLIC_KEY_LEN)
+        .ok_or(Error::InvalidPublicKey)
+}
+
+fn parse_pem_public_key(text: &str) -> Option<Vec<u8>> {
+    let (label, document) = der::Document::from_pem(text).ok()?;
+    if label != "PUBLIC KEY" {
+        return None;
+    }
+
+    document
+        .as_bytes()
+        .strip_prefix(&ED25519_SPKI_PREFIX)
+        .map(<[u8]>::to_vec)
+}
+
+fn parse_openssh_public_key(text: &str) -> Option<Vec<u8>> {
+    let mut parts = text.split_whitespace();
+    if parts.next()? != OPENSSH_ED25519_KEY_TYPE {
+        return None;
+    }
+
+    let blob = Base64::decode_vec(parts.next()?).ok()?;
+    let (key_type, rest) = read_ssh_string(&blob)?;
+    if key_type != OPENSSH_ED25519_KEY_TYPE.as_bytes() {
+        return None;
+    }
+
+    let (public_key, rest) = read_ssh_string(rest)?;
+    rest.is_empty().then(|| public_key.to_vec())
+}
+
+/// 读取 SSH 线路格式中以 4 字节长度开头的字符串，返回内容及剩余部分
+fn read_ssh_string(data: &[u8]) -> Option<(&[u8], &[u8])> {
+    let len = u32::from_be_bytes(data.get(..4)?.try_into().ok()?) as usize;
+    let data = &data[4..];
+
+    Some((data.get(..len)?, data.get(len..)?))
+}
+
 pub fn unparsed_public_key(public_key: &[u8]) -> signature::UnparsedPublicKey<&[u8]> {
     signature::UnparsedPublicKey::new(&signature::ED25519, public_key)
 }
 
+/// 生成请求签名的待签名内容
+///
+/// 由请求方法（大写）、路径（含查询参数）、时间戳（Unix 秒）、随机数及请求体 SHA-256 摘要（小写十六进制）
+/// 依次以换行符连接而成。
+pub fn signing_message(
+    method: &str,
+    path: &str,
+    timestamp: i64,
+    nonce: &str,
+    body_hash: &[u8],
+) -> String {
+    format!(
+        "{}\n{}\n{}\n{}\n{}",
+        method.to_ascii_uppercase(),
+        path,
+        timestamp,
+        nonce,
+        to_hex(body_hash)
+    )
+}
+
+/// 使用 Ed25519 公钥校验签名
+pub fn verify_signature(public_key: &[u8], message: &[u8], signature: &[u8]) -> bool {
+    unparsed_public_key(public_key)
+        .verify(message, signature)
+        .is_ok()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -78,4 +164,58 @@ mod tests {
 
         assert!(pub_key.verify(MESSAGE, sig.as_ref()).is_ok())
     }
+
+    #[test]
+    fn test_signing_message() {
+        let raw_key_pair = generate_pkcs8_keypair().unwrap();
+        let key_pair =
+            signature::Ed25519KeyPair::from_pkcs8(raw_key_pair.private_key_bytes()).unwrap();
+
+        let message = signing_message(
+            "post",
+            "/security/content?a=1",
+            1660000000,
+            "nonce",
+            &sha256(b""),
+        );
+        assert_eq!(
+            message,
+            "POST\n/security/content?a=1\n1660000000\nnonce\ne3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+
+        let sig = key_pair.sign(message.as_bytes());
+        assert!(verify_signature(
+            raw_key_pair.public_key_bytes(),
+            message.as_bytes(),
+            sig.as_ref()
+        ));
+        assert!(!verify_signature(
+            raw_key_pair.public_key_bytes(),
+            b"tampered",
+            sig.as_ref()
+        ));
+    }
+
+    #[test]
+    fn test_parse_public_key() {
+        let pem = "-----BEGIN PUBLIC KEY-----
+MCowBQYDK2VwAyEAsHeOAMeqmusKor4KITQgntt6yl+H3a7s4OB79xGppx4=
+-----END PUBLIC KEY-----
+";
+        assert_eq!(
+            to_hex(&parse_public_key(pem).unwrap()),
+            "b0778e00c7aa9aeb0aa2be0a2134209edb7aca5f87ddaeece0e07bf711a9a71e"
+        );
+
+        let openssh = "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIAbDPd9/lthJz88b1oqB0ueRWizBp8Sm/SAiTZQqz/d0 ci@example";
+        assert_eq!(
+            to_hex(&parse_public_key(openssh).unwrap()),
+            "06c33ddf7f96d849cfcf1bd68a81d2e7915a2cc1a7c4a6fd20224d942acff774"
+        );
+
+        assert!(parse_public_key("ssh-rsa AAAAB3NzaC1yc2EAAAADAQABAAABAQ").is_err());
+        assert!(parse_public_key("ssh-ed25519 AAAAC3NzaC1lZDI1NTE5").is_err());
+        assert!(parse_public_key(&pem.replace("PUBLIC KEY", "PRIVATE KEY")).is_err());
+        assert!(parse_public_key("").is_err());
+    }
 }
@@ -0,0 +1,31 @@
+use base64ct::{Base64UrlUnpadded, Encoding};
+use rand_core::{OsRng, RngCore};
+
+use crate::util::hash::sha256;
+
+/// 生成 256 位随机令牌，以 URL 安全的 Base64 编码
+pub fn generate_token() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+
+    Base64UrlUnpadded::encode_string(&bytes)
+}
+
+/// 令牌摘要，数据库中仅保存令牌的摘要
+pub fn token_hash(token: &str) -> Vec<u8> {
+    sha256(token.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_token() {
+        let token = generate_token();
+
+        assert_eq!(token.len(), 43);
+        assert_ne!(token, generate_token());
+        assert_eq!(token_hash(&token).len(), 32);
+    }
+}
@@ -0,0 +1,230 @@
+//! 基于时间的一次性密码（TOTP，RFC 6238）
+use chrono::{DateTime, Utc};
+use rand_core::{OsRng, RngCore};
+use ring::hmac;
+
+use super::hash::{sha256, to_hex};
+
+/// 时间步长（秒）
+pub const TOTP_PERIOD: i64 = 30;
+/// 验证码位数
+pub const TOTP_DIGITS: u32 = 6;
+/// 允许客户端与服务器时间前后相差的时间步数
+const TOTP_SKEW: i64 = 1;
+/// 密钥长度，与 HMAC-SHA1 输出长度一致
+const SECRET_LEN: usize = 20;
+/// 恢复码字符数，不含分隔符
+const RECOVERY_CODE_LEN: usize = 10;
+
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// 生成随机 TOTP 密钥
+pub fn generate_secret() -> Vec<u8> {
+    let mut secret = vec![0u8; SECRET_LEN];
+    OsRng.fill_bytes(&mut secret);
+
+    secret
+}
+
+/// 时间对应的时间步
+pub fn time_step(now: DateTime<Utc>) -> i64 {
+    now.timestamp().div_euclid(TOTP_PERIOD)
+}
+
+/// 计算指定时间步的验证码
+pub fn totp(secret: &[u8], step: i64) -> String {
+    let key = hmac::Key::new(hmac::HMAC_SHA1_FOR_LEGACY_USE_ONLY, secret);
+    let digest = hmac::sign(&key, &step.to_be_bytes());
+    let digest = digest.as_ref();
+
+    // RFC 4226 动态截断
+    let offset = (digest[digest.len() - 1] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([
+        digest[offset] & 0x7f,
+        digest[offset + 1],
+        digest[offset + 2],
+        digest[offset + 3],
+    ]);
+
+    format!(
+        "{:0width$}",
+        binary % 10u32.pow(TOTP_DIGITS),
+        width = TOTP_DIGITS as usize
+    )
+}
+
+/// 校验验证码，返回验证码所属的时间步
+///
+/// 只接受晚于 `last_step` 的时间步，同一验证码不能重复使用。
+pub fn verify_totp(
+    secret: &[u8],
+    code: &str,
+    now: DateTime<Utc>,
+    last_step: Option<i64>,
+) -> Option<i64> {
+    let code = code.trim();
+    if code.len() != TOTP_DIGITS as usize || !code.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    let current = time_step(now);
+    (current - TOTP_SKEW..=current + TOTP_SKEW)
+        .filter(|step| last_step.is_none_or(|last| *step > last))
+        .find(|step| {
+            let expected = totp(secret, *step);
+            ring::constant_time::verify_slices_are_equal(expected.as_bytes(), code.as_bytes())
+                .is_ok()
+        })
+}
+
+/// 生成验证器应用使用的 `otpauth://` 地址
+pub fn otpauth_uri(issuer: &str, account: &str, secret: &[u8]) -> String {
+    format!(
+        "otpauth://totp/{}:{}?secret={}&issuer={}&algorithm=SHA1&digits={TOTP_DIGITS}&period={TOTP_PERIOD}",
+        percent_encode(issuer),
+        percent_encode(account),
+        base32_encode(secret),
+        percent_encode(issuer),
+    )
+}
+
+/// 不带填充的 Base32 编码（RFC 4648）
+pub fn base32_encode(bytes: &[u8]) -> String {
+    let mut output = String::with_capacity((bytes.len() * 8).div_ceil(5));
+    let mut buffer = 0u16;
+    let mut bits = 0;
+
+    for byte in bytes {
+        buffer = (buffer << 8) | *byte as u16;
+        bits += 8;
+
+        while bits >= 5 {
+            bits -= 5;
+            output.push(BASE32_ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+        }
+    }
+
+    if bits > 0 {
+        output.push(BASE32_ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+    }
+
+    output
+}
+
+fn percent_encode(text: &str) -> String {
+    text.bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{byte:02X}"),
+        })
+        .collect()
+}
+
+/// 生成恢复码，格式为 `xxxxx-xxxxx`
+pub fn generate_recovery_codes(count: usize) -> Vec<String> {
+    (0..count)
+        .map(|_| {
+            let mut bytes = [0u8; (RECOVERY_CODE_LEN * 5).div_ceil(8)];
+            OsRng.fill_bytes(&mut bytes);
+
+            let code = base32_encode(&bytes).to_lowercase();
+            let (left, right) = code[..RECOVERY_CODE_LEN].split_at(RECOVERY_CODE_LEN / 2);
+            format!("{left}-{right}")
+        })
+        .collect()
+}
+
+/// 恢复码摘要，忽略大小写及分隔符，数据库中仅保存恢复码的摘要
+pub fn recovery_code_hash(code: &str) -> String {
+    let normalized: String = code
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+
+    to_hex(&sha256(normalized.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    /// RFC 6238 附录 B 中 SHA1 的测试向量（取后 6 位）
+    #[test]
+    fn test_totp() {
+        let secret = b"12345678901234567890";
+
+        for (timestamp, code) in [
+            (59, "287082"),
+            (1111111109, "081804"),
+            (1111111111, "050471"),
+            (1234567890, "005924"),
+            (2000000000, "279037"),
+        ] {
+            let now = Utc.timestamp_opt(timestamp, 0).unwrap();
+            assert_eq!(totp(secret, time_step(now)), code);
+        }
+    }
+
+    #[test]
+    fn test_verify_totp() {
+        let secret = generate_secret();
+        let now = Utc::now();
+        let step = time_step(now);
+
+        assert_eq!(
+            verify_totp(&secret, &totp(&secret, step), now, None),
+            Some(step)
+        );
+        assert_eq!(
+            verify_totp(&secret, &totp(&secret, step - 1), now, None),
+            Some(step - 1)
+        );
+        assert_eq!(
+            verify_totp(&secret, &totp(&secret, step - 2), now, None),
+            None
+        );
+        // 已使用过的验证码
+        assert_eq!(
+            verify_totp(&secret, &totp(&secret, step), now, Some(step)),
+            None
+        );
+        assert_eq!(verify_totp(&secret, "12345", now, None), None);
+        assert_eq!(verify_totp(&secret, "abcdef", now, None), None);
+    }
+
+    #[test]
+    fn test_base32_encode() {
+        assert_eq!(base32_encode(b""), "");
+        assert_eq!(base32_encode(b"f"), "MY");
+        assert_eq!(base32_encode(b"foobar"), "MZXW6YTBOI");
+        assert_eq!(base32_encode(&[0u8; 20]).len(), 32);
+    }
+
+    #[test]
+    fn test_otpauth_uri() {
+        assert_eq!(
+            otpauth_uri("My Blog", "chongyi", b"foobar"),
+            "otpauth://totp/My%20Blog:chongyi?secret=MZXW6YTBOI&issuer=My%20Blog&algorithm=SHA1&digits=6&period=30"
+        );
+    }
+
+    #[test]
+    fn test_recovery_codes() {
+        let codes = generate_recovery_codes(10);
+
+        assert_eq!(codes.len(), 10);
+        assert!(codes
+            .iter()
+            .all(|code| code.len() == 11 && code.as_bytes()[5] == b'-'));
+        assert_eq!(
+            recovery_code_hash(&codes[0]),
+            recovery_code_hash(&codes[0].to_uppercase().replace('-', " "))
+        );
+        assert_ne!(recovery_code_hash(&codes[0]), recovery_code_hash(&codes[1]));
+    }
+}
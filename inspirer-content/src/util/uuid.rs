@@ -24,11 +24,7 @@ pub fn generate_v1_uuid() -> Uuid {
     let ctx: &Context = &CLOCK_SEQUENCE;
 
     Uuid::new_v1(
-        Timestamp::from_unix(
-            ctx,
-            now.timestamp() as u64,
-            now.timestamp_subsec_nanos() as u32,
-        ),
+        Timestamp::from_unix(ctx, now.timestamp() as u64, now.timestamp_subsec_nanos()),
         &MAC_ADDRESS,
     )
 }
@@ -0,0 +1,4 @@
+drop table if exists content_categories;
+drop table if exists content_tags;
+drop table if exists categories;
+drop table if exists tags;
@@ -0,0 +1,32 @@
+create table if not exists tags (
+    id binary(16) not null primary key,
+    name varchar(100) not null,
+    created_at timestamp not null default current_timestamp
+);
+
+create unique index unique_tag_name on tags (name);
+
+create table if not exists categories (
+    id binary(16) not null primary key,
+    name varchar(100) not null,
+    description varchar(500) not null default '',
+    created_at timestamp not null default current_timestamp
+);
+
+create unique index unique_category_name on categories (name);
+
+create table if not exists content_tags (
+    content_id binary(16) not null,
+    tag_id binary(16) not null,
+    primary key (content_id, tag_id)
+);
+
+create index index_tag on content_tags (tag_id);
+
+create table if not exists content_categories (
+    content_id binary(16) not null,
+    category_id binary(16) not null,
+    primary key (content_id, category_id)
+);
+
+create index index_category on content_categories (category_id);
@@ -0,0 +1,4 @@
+alter table users add column role int unsigned not null default 3 after avatar;
+
+-- 已有用户均拥有全部权限，迁移后统一设为管理员
+update users set role = 1;
@@ -0,0 +1,17 @@
+create table if not exists user_sessions (
+    id binary(16) not null primary key,
+    user_id binary(16) not null,
+    access_token_id binary(16) not null,
+    refresh_token_hash binary(32) not null,
+    previous_refresh_token_hash binary(32) null,
+    user_agent varchar(500) not null default '',
+    created_at timestamp not null default current_timestamp,
+    refreshed_at timestamp not null default current_timestamp,
+    expires_at timestamp not null,
+    revoked_at timestamp null
+);
+
+create unique index unique_session_access_token on user_sessions (access_token_id);
+create unique index unique_session_refresh_token on user_sessions (refresh_token_hash);
+create index index_session_previous_refresh_token on user_sessions (previous_refresh_token_hash);
+create index index_session_user on user_sessions (user_id);
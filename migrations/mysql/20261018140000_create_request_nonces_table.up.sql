@@ -0,0 +1,8 @@
+create table if not exists request_nonces (
+    user_id binary(16) not null,
+    nonce varchar(64) not null,
+    expires_at timestamp not null,
+    primary key (user_id, nonce)
+);
+
+create index index_nonce_expires_at on request_nonces (expires_at);
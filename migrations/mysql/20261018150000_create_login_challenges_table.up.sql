@@ -0,0 +1,7 @@
+create table if not exists login_challenges (
+    challenge varchar(64) not null primary key,
+    public_key_fingerprint binary(32) not null,
+    expires_at timestamp not null
+);
+
+create index index_challenge_expires_at on login_challenges (expires_at);
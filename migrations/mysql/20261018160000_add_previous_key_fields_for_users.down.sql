@@ -0,0 +1,5 @@
+drop index index_previous_pubkey on users;
+
+alter table users drop column previous_key_expires_at;
+alter table users drop column previous_public_key_fingerprint;
+alter table users drop column previous_public_key;
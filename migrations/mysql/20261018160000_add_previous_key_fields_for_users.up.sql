@@ -0,0 +1,6 @@
+-- 轮换密钥后旧公钥在宽限期内仍然有效
+alter table users add column previous_public_key varbinary(500) null after public_key_fingerprint;
+alter table users add column previous_public_key_fingerprint binary(32) null after previous_public_key;
+alter table users add column previous_key_expires_at timestamp null after previous_public_key_fingerprint;
+
+create index index_previous_pubkey on users (previous_public_key_fingerprint);
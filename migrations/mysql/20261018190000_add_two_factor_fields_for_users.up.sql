@@ -0,0 +1,5 @@
+-- TOTP 两步验证，密钥加密保存，恢复码仅保存摘要
+alter table users add column totp_secret varbinary(255) null after previous_key_expires_at;
+alter table users add column totp_enabled_at timestamp null after totp_secret;
+alter table users add column totp_last_step bigint null after totp_enabled_at;
+alter table users add column totp_recovery_codes json null after totp_last_step;
@@ -0,0 +1,14 @@
+-- 个人访问令牌，仅保存令牌摘要
+create table if not exists personal_access_tokens (
+    id binary(16) not null primary key,
+    user_id binary(16) not null,
+    name varchar(100) not null,
+    token_hash binary(32) not null,
+    scopes json not null,
+    expires_at timestamp null,
+    last_used_at timestamp null,
+    created_at timestamp not null default current_timestamp
+);
+
+create unique index unique_personal_access_token on personal_access_tokens (token_hash);
+create index index_personal_access_token_user on personal_access_tokens (user_id);
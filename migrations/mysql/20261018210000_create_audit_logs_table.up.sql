@@ -0,0 +1,17 @@
+-- 安全相关操作的审计日志
+create table if not exists audit_logs (
+    id binary(16) not null primary key,
+    actor_id binary(16) null,
+    action varchar(50) not null,
+    target_type varchar(20) null,
+    target_id binary(16) null,
+    detail json null,
+    ip varchar(45) null,
+    user_agent varchar(500) not null default '',
+    created_at timestamp not null default current_timestamp
+);
+
+create index index_audit_log_actor on audit_logs (actor_id);
+create index index_audit_log_target on audit_logs (target_id);
+create index index_audit_log_action on audit_logs (action);
+create index index_audit_log_created_at on audit_logs (created_at);
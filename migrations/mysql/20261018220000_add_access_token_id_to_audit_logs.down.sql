@@ -0,0 +1 @@
+alter table audit_logs drop column access_token_id;
@@ -0,0 +1,2 @@
+-- 使用个人访问令牌执行的操作记录令牌 ID
+alter table audit_logs add column access_token_id binary(16) null after actor_id;
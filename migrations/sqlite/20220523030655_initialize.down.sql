@@ -0,0 +1,2 @@
+drop table if exists contents;
+drop table if exists content_entities;
@@ -0,0 +1,33 @@
+create table if not exists contents (
+    id blob not null primary key,
+    content_name varchar(240) default null,
+    content_type integer not null,
+    title varchar(240) not null,
+    keywords varchar(500) not null,
+    description varchar(500) not null,
+    created_at timestamp not null default current_timestamp,
+    updated_at timestamp not null default current_timestamp,
+    is_publish boolean not null default false,
+    is_display boolean not null default true,
+    published_at timestamp null
+);
+
+create unique index unique_content_name on contents (content_name);
+
+create trigger if not exists contents_updated_at after update on contents
+for each row when new.updated_at = old.updated_at
+begin
+    update contents set updated_at = current_timestamp where id = old.id;
+end;
+
+create table if not exists content_entities (
+    id blob not null primary key,
+    entity text not null,
+    updated_at timestamp not null default current_timestamp
+);
+
+create trigger if not exists content_entities_updated_at after update on content_entities
+for each row when new.updated_at = old.updated_at
+begin
+    update content_entities set updated_at = current_timestamp where id = old.id;
+end;
@@ -0,0 +1,6 @@
+drop table if exists users;
+
+drop index if exists index_content_owner;
+
+alter table contents drop column owner_id;
+alter table contents drop column authors;
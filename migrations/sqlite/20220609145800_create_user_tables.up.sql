@@ -0,0 +1,25 @@
+create table if not exists users (
+    id blob not null primary key,
+    username varchar(40) not null,
+    nickname varchar(40) not null,
+    avatar varchar(500) not null default '',
+    user_profile text not null default '{}',
+    public_key blob not null,
+    public_key_fingerprint blob not null,
+    created_at timestamp not null default current_timestamp,
+    updated_at timestamp not null default current_timestamp
+);
+
+create unique index unique_username on users (username);
+create unique index unique_pubkey on users (public_key_fingerprint);
+
+create trigger if not exists users_updated_at after update on users
+for each row when new.updated_at = old.updated_at
+begin
+    update users set updated_at = current_timestamp where id = old.id;
+end;
+
+alter table contents add column owner_id blob not null default x'00000000000000000000000000000000';
+alter table contents add column authors text not null default '[]';
+
+create index index_content_owner on contents (owner_id);
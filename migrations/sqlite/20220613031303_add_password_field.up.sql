@@ -0,0 +1 @@
+alter table users add column password varchar(200) not null default '';
@@ -0,0 +1,37 @@
+create table if not exists content_update_logs (
+    id blob not null primary key,
+    user_id blob not null,
+    content_id blob not null,
+    update_data text not null,
+    app_version integer not null default 1,
+    created_at timestamp not null default current_timestamp
+);
+
+create index index_user on content_update_logs (user_id);
+
+create index index_content on content_update_logs (content_id);
+
+insert into
+    content_update_logs (id, user_id, content_id, update_data, created_at)
+select
+    randomblob(16),
+    owner_id,
+    contents.id,
+    json_object(
+        'title',
+        title,
+        'description',
+        description,
+        'keywords',
+        keywords,
+        'content_name',
+        content_name,
+        'content_type',
+        content_type,
+        'entity',
+        json(content_entities.entity)
+    ),
+    contents.created_at
+from
+    contents
+    left join content_entities on content_entities.id = contents.id;
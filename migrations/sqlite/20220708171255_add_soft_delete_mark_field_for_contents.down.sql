@@ -0,0 +1,3 @@
+alter table contents drop column is_deleted;
+alter table contents drop column deleted_at;
+alter table contents drop column modified_at;
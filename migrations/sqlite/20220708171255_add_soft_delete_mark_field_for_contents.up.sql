@@ -0,0 +1,43 @@
+-- SQLite 不支持以 current_timestamp 为默认值新增字段，这里通过重建表完成
+create table contents_new (
+    id blob not null primary key,
+    owner_id blob not null default x'00000000000000000000000000000000',
+    authors text not null default '[]',
+    content_name varchar(240) default null,
+    content_type integer not null,
+    title varchar(240) not null,
+    keywords varchar(500) not null,
+    description varchar(500) not null,
+    created_at timestamp not null default current_timestamp,
+    updated_at timestamp not null default current_timestamp,
+    modified_at timestamp not null default current_timestamp,
+    is_publish boolean not null default false,
+    is_display boolean not null default true,
+    is_deleted boolean not null default false,
+    published_at timestamp null,
+    deleted_at timestamp null
+);
+
+insert into
+    contents_new (
+        id, owner_id, authors, content_name, content_type, title, keywords, description,
+        created_at, updated_at, modified_at, is_publish, is_display, published_at
+    )
+select
+    id, owner_id, authors, content_name, content_type, title, keywords, description,
+    created_at, updated_at, updated_at, is_publish, is_display, published_at
+from
+    contents;
+
+drop table contents;
+
+alter table contents_new rename to contents;
+
+create unique index unique_content_name on contents (content_name);
+create index index_content_owner on contents (owner_id);
+
+create trigger if not exists contents_updated_at after update on contents
+for each row when new.updated_at = old.updated_at
+begin
+    update contents set updated_at = current_timestamp where id = old.id;
+end;
@@ -0,0 +1,2 @@
+drop index if exists index_content_scheduled_at;
+alter table contents drop column scheduled_at;
\ No newline at end of file
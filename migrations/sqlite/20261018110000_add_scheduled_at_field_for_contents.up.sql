@@ -0,0 +1,3 @@
+alter table contents add column scheduled_at timestamp null;
+
+create index index_content_scheduled_at on contents (scheduled_at);
\ No newline at end of file
@@ -0,0 +1,5 @@
+drop index if exists index_previous_pubkey;
+
+alter table users drop column previous_key_expires_at;
+alter table users drop column previous_public_key_fingerprint;
+alter table users drop column previous_public_key;
@@ -0,0 +1,6 @@
+-- 轮换密钥后旧公钥在宽限期内仍然有效
+alter table users add column previous_public_key blob null;
+alter table users add column previous_public_key_fingerprint blob null;
+alter table users add column previous_key_expires_at timestamp null;
+
+create index index_previous_pubkey on users (previous_public_key_fingerprint);
@@ -0,0 +1 @@
+alter table users add column disabled_at timestamp null;
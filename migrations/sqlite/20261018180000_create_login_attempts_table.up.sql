@@ -0,0 +1,6 @@
+create table if not exists login_attempts (
+    attempt_key varchar(255) not null primary key,
+    failures integer not null default 0,
+    last_failure_at timestamp not null,
+    locked_until timestamp null
+);
@@ -0,0 +1,5 @@
+-- TOTP 两步验证，密钥加密保存，恢复码仅保存摘要
+alter table users add column totp_secret blob null;
+alter table users add column totp_enabled_at timestamp null;
+alter table users add column totp_last_step bigint null;
+alter table users add column totp_recovery_codes text null;
@@ -0,0 +1,14 @@
+-- 个人访问令牌，仅保存令牌摘要
+create table if not exists personal_access_tokens (
+    id blob not null primary key,
+    user_id blob not null,
+    name varchar(100) not null,
+    token_hash blob not null,
+    scopes text not null,
+    expires_at timestamp null,
+    last_used_at timestamp null,
+    created_at timestamp not null default current_timestamp
+);
+
+create unique index unique_personal_access_token on personal_access_tokens (token_hash);
+create index index_personal_access_token_user on personal_access_tokens (user_id);
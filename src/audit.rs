@@ -0,0 +1,166 @@
+//! 审计日志
+//!
+//! 登录、令牌签发、内容发布与删除以及用户变更等安全相关操作的审计日志与操作在同一事务中写入，
+//! 日志写入失败时操作一并回滚，避免操作没有留下记录；登录失败等未改变数据的事件单独记录。
+use std::net::{IpAddr, SocketAddr};
+
+use axum::{
+    extract::{ConnectInfo, FromRequest, RequestParts},
+    http::{header, HeaderMap},
+};
+use inspirer_content::{manager::Manager, model::audit::NewAuditLog, service::audit::AuditService};
+use lazy_static::lazy_static;
+
+use crate::error::{InspirerError, InspirerResult};
+
+/// 命令行操作的审计日志使用的 User-Agent
+const CLI_USER_AGENT: &str = "inspirer-rs manage";
+
+lazy_static! {
+    /// 部署在反向代理之后时可信的代理层数，为 0 时不读取 `X-Forwarded-For`
+    ///
+    /// 兼容旧配置：未配置时 `TRUST_FORWARDED_FOR=true` 视为 1 层代理。
+    static ref TRUSTED_PROXY_HOPS: usize = {
+        std::env::var("TRUSTED_PROXY_HOPS")
+            .map(|v| v.parse::<usize>().expect("TRUSTED_PROXY_HOPS 参数格式化错误"))
+            .unwrap_or_else(|_| {
+                std::env::var("TRUST_FORWARDED_FOR")
+                    .map(|s| usize::from(s == "true" || s == "1"))
+                    .unwrap_or(0)
+            })
+    };
+}
+
+/// 客户端 IP
+fn client_ip(headers: &HeaderMap, addr: SocketAddr) -> IpAddr {
+    forwarded_client_ip(headers, *TRUSTED_PROXY_HOPS).unwrap_or_else(|| addr.ip())
+}
+
+/// 从 `X-Forwarded-For` 中读取客户端 IP
+///
+/// 每层代理在末尾追加其收到请求的来源地址，最左侧的地址可被客户端任意伪造，
+/// 因此取从右往左第 `hops` 个地址，即最外层可信代理记录的来源地址。
+fn forwarded_client_ip(headers: &HeaderMap, hops: usize) -> Option<IpAddr> {
+    if hops == 0 {
+        return None;
+    }
+
+    let entries = headers
+        .get_all("x-forwarded-for")
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .collect::<Vec<_>>();
+
+    entries.iter().rev().nth(hops - 1)?.parse().ok()
+}
+
+/// 请求来源，用于登录限流及审计日志
+#[derive(Debug, Clone)]
+pub struct ClientInfo {
+    pub ip: IpAddr,
+    pub user_agent: String,
+}
+
+impl ClientInfo {
+    /// 附带请求来源的审计日志，交由服务与操作在同一事务中写入
+    pub fn audit_log(&self, log: NewAuditLog) -> NewAuditLog {
+        NewAuditLog {
+            ip: Some(self.ip.to_string()),
+            user_agent: self.user_agent.clone(),
+            ..log
+        }
+    }
+
+    /// 记录审计日志，附带请求来源
+    pub async fn audit(&self, manager: &Manager, log: NewAuditLog) -> InspirerResult<()> {
+        record(manager, self.audit_log(log)).await
+    }
+}
+
+#[async_trait::async_trait]
+impl<B> FromRequest<B> for ClientInfo
+where
+    B: Send,
+{
+    type Rejection = InspirerError;
+
+    async fn from_request(req: &mut RequestParts<B>) -> Result<Self, Self::Rejection> {
+        let ConnectInfo(addr) = ConnectInfo::<SocketAddr>::from_request(req)
+            .await
+            .map_err(|err| InspirerError::InvalidRequest(err.to_string()))?;
+
+        let headers = req.headers();
+        let user_agent = headers
+            .get(header::USER_AGENT)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+
+        Ok(ClientInfo {
+            ip: client_ip(headers, addr),
+            user_agent,
+        })
+    }
+}
+
+/// 记录审计日志，写入失败时记录错误并返回
+pub async fn record(manager: &Manager, log: NewAuditLog) -> InspirerResult<()> {
+    let action = log.action;
+
+    manager.record_audit(log).await.map_err(|err| {
+        tracing::error!("Failed to record audit log {action}: {err}");
+        err.into()
+    })
+}
+
+/// 命令行操作的审计日志，命令行操作没有执行操作的用户及来源 IP
+pub fn cli_log(log: NewAuditLog) -> NewAuditLog {
+    NewAuditLog {
+        user_agent: CLI_USER_AGENT.into(),
+        ..log
+    }
+}
+
+/// 记录命令行操作的审计日志，用于不修改数据库的操作，其余操作的日志与操作在同一事务中写入
+pub async fn record_cli(manager: &Manager, log: NewAuditLog) -> InspirerResult<()> {
+    record(manager, cli_log(log)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(values: &[&str]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for value in values {
+            headers.append("x-forwarded-for", value.parse().unwrap());
+        }
+
+        headers
+    }
+
+    #[test]
+    fn test_forwarded_client_ip() {
+        let forged = headers(&["1.1.1.1, 10.0.0.1", "10.0.0.2"]);
+
+        assert_eq!(forwarded_client_ip(&forged, 0), None);
+        assert_eq!(
+            forwarded_client_ip(&forged, 1),
+            Some("10.0.0.2".parse().unwrap())
+        );
+        assert_eq!(
+            forwarded_client_ip(&forged, 2),
+            Some("10.0.0.1".parse().unwrap())
+        );
+        assert_eq!(
+            forwarded_client_ip(&forged, 3),
+            Some("1.1.1.1".parse().unwrap())
+        );
+        assert_eq!(forwarded_client_ip(&forged, 4), None);
+
+        assert_eq!(forwarded_client_ip(&headers(&["unknown"]), 1), None);
+        assert_eq!(forwarded_client_ip(&HeaderMap::new(), 1), None);
+    }
+}
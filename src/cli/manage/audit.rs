@@ -0,0 +1,260 @@
+use std::{
+    fs::OpenOptions,
+    io::{BufWriter, Write},
+    path::PathBuf,
+};
+
+use chrono::{DateTime, Utc};
+use clap::{Args, Subcommand, ValueEnum};
+use inspirer_content::{
+    enumerate::audit::AuditAction,
+    manager::Manager,
+    model::{audit::AuditLogCondition, paginate::Pagination},
+    service::{audit::AuditService, user::UserService},
+    util::uuid::{base62_to_uuid, Uuid},
+};
+
+use crate::response::audit::AuditLog;
+
+/// 导出时每次查询的日志条数
+const EXPORT_PAGE_SIZE: usize = 500;
+
+#[derive(Args)]
+pub struct AuditManage {
+    #[clap(subcommand)]
+    commands: AuditManageCommands,
+}
+
+#[derive(Subcommand)]
+pub enum AuditManageCommands {
+    /// 按时间倒序导出审计日志
+    Export {
+        /// 导出文件路径，文件已存在时不会覆盖
+        #[clap(short, long)]
+        output: PathBuf,
+        #[clap(long, value_enum, default_value = "jsonl")]
+        format: ExportFormat,
+        /// 起始时间（含），RFC 3339 格式
+        #[clap(long)]
+        since: Option<DateTime<Utc>>,
+        /// 截止时间（不含），RFC 3339 格式
+        #[clap(long)]
+        until: Option<DateTime<Utc>>,
+        /// 操作类型，如 login-failed、content-force-deleted
+        #[clap(long)]
+        action: Option<AuditAction>,
+        /// 执行操作的用户（用户名或用户 ID），已删除的用户须使用用户 ID
+        #[clap(long)]
+        actor: Option<String>,
+        /// 操作对象 ID
+        #[clap(long)]
+        target: Option<String>,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum ExportFormat {
+    /// 每行一条 JSON 记录
+    Jsonl,
+    /// CSV，`detail` 列为 JSON 文本
+    Csv,
+}
+
+/// 解析 Base62 或 UUID 格式的 ID
+fn parse_id(id: &str) -> Option<Uuid> {
+    Uuid::parse_str(id).ok().or_else(|| base62_to_uuid(id).ok())
+}
+
+/// 转义 CSV 字段
+///
+/// 以 `=`、`+`、`-`、`@`、制表符或回车开头的字段在表格软件中会被当作公式执行，前置单引号使其按文本显示。
+fn csv_field(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        format!("'{value}")
+    } else {
+        value.to_string()
+    };
+
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+fn write_csv_row<W: Write>(writer: &mut W, fields: &[&str]) -> std::io::Result<()> {
+    let row = fields
+        .iter()
+        .map(|field| csv_field(field))
+        .collect::<Vec<_>>()
+        .join(",");
+
+    writeln!(writer, "{row}")
+}
+
+fn write_log<W: Write>(
+    writer: &mut W,
+    format: ExportFormat,
+    log: &AuditLog,
+) -> std::io::Result<()> {
+    match format {
+        ExportFormat::Jsonl => {
+            serde_json::to_writer(&mut *writer, log)?;
+            writeln!(writer)
+        }
+        ExportFormat::Csv => write_csv_row(
+            writer,
+            &[
+                &log.id,
+                &log.created_at.to_rfc3339(),
+                &log.action.to_string(),
+                log.actor_id.as_deref().unwrap_or_default(),
+                log.actor.as_deref().unwrap_or_default(),
+                log.access_token_id.as_deref().unwrap_or_default(),
+                &log.target_type
+                    .map(|target| target.to_string())
+                    .unwrap_or_default(),
+                log.target_id.as_deref().unwrap_or_default(),
+                log.ip.as_deref().unwrap_or_default(),
+                &log.user_agent,
+                &log.detail
+                    .as_ref()
+                    .map(|detail| detail.to_string())
+                    .unwrap_or_default(),
+            ],
+        ),
+    }
+}
+
+impl AuditManage {
+    pub async fn run(self, manager: Manager) {
+        match self.commands {
+            AuditManageCommands::Export {
+                output,
+                format,
+                since,
+                until,
+                action,
+                actor,
+                target,
+            } => {
+                println!("=> Export audit logs");
+
+                let actor_id = match actor {
+                    Some(actor) => Some(match parse_id(&actor) {
+                        Some(id) => id,
+                        None => {
+                            manager
+                                .get_user_by_username(actor)
+                                .await
+                                .expect("查询用户失败")
+                                .expect("用户不存在")
+                                .id
+                        }
+                    }),
+                    None => None,
+                };
+                let condition = AuditLogCondition {
+                    actor_id,
+                    action,
+                    target_id: target
+                        .map(|target| parse_id(&target).expect("操作对象 ID 格式错误")),
+                    since,
+                    until,
+                };
+
+                let file = OpenOptions::new()
+                    .write(true)
+                    .create_new(true)
+                    .open(&output)
+                    .expect("创建导出文件失败");
+                let mut writer = BufWriter::new(file);
+
+                if let ExportFormat::Csv = format {
+                    write_csv_row(
+                        &mut writer,
+                        &[
+                            "id",
+                            "created_at",
+                            "action",
+                            "actor_id",
+                            "actor",
+                            "access_token_id",
+                            "target_type",
+                            "target_id",
+                            "ip",
+                            "user_agent",
+                            "detail",
+                        ],
+                    )
+                    .expect("写入导出文件失败");
+                }
+
+                let mut page = 1;
+                let mut count = 0;
+                loop {
+                    let logs = manager
+                        .get_audit_logs(
+                            condition.clone(),
+                            Pagination {
+                                page,
+                                page_size: EXPORT_PAGE_SIZE,
+                            },
+                        )
+                        .await
+                        .expect("查询审计日志失败");
+
+                    for log in logs.data {
+                        write_log(&mut writer, format, &AuditLog::from(log))
+                            .expect("写入导出文件失败");
+                        count += 1;
+                    }
+
+                    if page >= logs.last_page {
+                        break;
+                    }
+                    page += 1;
+                }
+
+                writer.flush().expect("写入导出文件失败");
+
+                println!("-> Path = {}", output.display());
+                println!("-> Exported {count} logs");
+                println!("-> Done");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_csv_field() {
+        assert_eq!(csv_field("login"), "login");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\"\n"), "\"say \"\"hi\"\"\n\"");
+
+        // 防止公式注入
+        assert_eq!(csv_field("=1+1"), "'=1+1");
+        assert_eq!(csv_field("+1"), "'+1");
+        assert_eq!(csv_field("-1"), "'-1");
+        assert_eq!(csv_field("@SUM(A1)"), "'@SUM(A1)");
+        assert_eq!(csv_field("\t=1+1"), "'\t=1+1");
+        assert_eq!(csv_field("\r=1+1"), "\"'\r=1+1\"");
+        assert_eq!(
+            csv_field("=HYPERLINK(\"x\",\"y\")"),
+            "\"'=HYPERLINK(\"\"x\"\",\"\"y\"\")\""
+        );
+        assert_eq!(csv_field(""), "");
+    }
+
+    #[test]
+    fn test_write_csv_row() {
+        let mut buffer = Vec::new();
+        write_csv_row(&mut buffer, &["id", "=cmd", "a,b"]).unwrap();
+
+        assert_eq!(String::from_utf8(buffer).unwrap(), "id,'=cmd,\"a,b\"\n");
+    }
+}
@@ -0,0 +1,90 @@
+use std::{fs::OpenOptions, io::Write, path::PathBuf};
+
+use clap::{Args, Subcommand, ValueEnum};
+use inspirer_content::util::signature::private_key_to_pem;
+use ring::{
+    rand::SystemRandom,
+    signature::{EcdsaKeyPair, Ed25519KeyPair, ECDSA_P256_SHA256_FIXED_SIGNING},
+};
+
+use crate::jwk::KeyMaterial;
+
+#[derive(Args)]
+pub struct KeyManage {
+    #[clap(subcommand)]
+    commands: KeyManageCommands,
+}
+
+#[derive(Subcommand)]
+pub enum KeyManageCommands {
+    /// 生成令牌签名私钥（PKCS#8 PEM），用于 JWT_SIGNING_KEY
+    Generate {
+        #[clap(long, value_enum, default_value = "ed25519")]
+        algorithm: KeyAlgorithm,
+        /// 私钥保存路径，文件已存在时不会覆盖
+        #[clap(short, long)]
+        output: PathBuf,
+    },
+    /// 显示密钥文件的密钥 ID 及公开的 JWK
+    Show {
+        /// PEM 格式的私钥或公钥文件
+        path: PathBuf,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum KeyAlgorithm {
+    /// EdDSA（Ed25519）
+    Ed25519,
+    /// ES256（ECDSA P-256）
+    Es256,
+}
+
+impl KeyManage {
+    /// 密钥管理不依赖数据库
+    pub fn run(self) {
+        match self.commands {
+            KeyManageCommands::Generate { algorithm, output } => {
+                println!("=> Generate token signing key");
+
+                let rng = SystemRandom::new();
+                let pkcs8 = match algorithm {
+                    KeyAlgorithm::Ed25519 => Ed25519KeyPair::generate_pkcs8(&rng),
+                    KeyAlgorithm::Es256 => {
+                        EcdsaKeyPair::generate_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, &rng)
+                    }
+                }
+                .expect("生成密钥失败");
+                let pem = private_key_to_pem(pkcs8.as_ref()).expect("生成密钥失败");
+
+                let mut options = OpenOptions::new();
+                options.write(true).create_new(true);
+                #[cfg(unix)]
+                {
+                    use std::os::unix::fs::OpenOptionsExt;
+                    options.mode(0o600);
+                }
+                options
+                    .open(&output)
+                    .and_then(|mut file| file.write_all(pem.as_bytes()))
+                    .expect("写入密钥文件失败");
+
+                let key = KeyMaterial::from_pem(&pem).expect("解析密钥失败");
+                println!("-> Path = {}", output.display());
+                println!("-> Key ID = {}", key.verification_key().kid);
+                println!("-> Done");
+            }
+            KeyManageCommands::Show { path } => {
+                let key = KeyMaterial::from_file(&path)
+                    .unwrap_or_else(|err| panic!("读取密钥失败：{err:#}"))
+                    .verification_key();
+
+                println!("-> Key ID = {}", key.kid);
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&key.to_jwk()).expect("序列化 JWK 失败")
+                );
+            }
+        }
+    }
+}
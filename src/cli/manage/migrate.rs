@@ -0,0 +1,66 @@
+use clap::{Args, Subcommand};
+use inspirer_content::manager::Manager;
+
+#[derive(Args)]
+pub struct MigrateManage {
+    #[clap(subcommand)]
+    commands: MigrateManageCommands,
+}
+
+#[derive(Subcommand)]
+pub enum MigrateManageCommands {
+    /// 执行所有未执行的迁移
+    Up,
+    /// 回滚最近执行的迁移
+    Down {
+        #[clap(short, long, default_value_t = 1)]
+        steps: usize,
+    },
+    /// 查看迁移执行状态
+    Status,
+    /// 回滚全部迁移后重新执行（会清空所有数据）
+    Fresh {
+        #[clap(long)]
+        force: bool,
+    },
+}
+
+impl MigrateManage {
+    pub async fn run(self, manager: Manager) {
+        match self.commands {
+            MigrateManageCommands::Up => {
+                println!("=> Run migrations");
+                manager.migrate().await.expect("执行迁移失败");
+                println!("-> Done");
+            }
+            MigrateManageCommands::Down { steps } => {
+                println!("=> Rollback migrations");
+                println!("-> Steps = {steps}");
+                manager.rollback(steps).await.expect("回滚迁移失败");
+                println!("-> Done");
+            }
+            MigrateManageCommands::Status => {
+                println!("=> Migration status");
+                for status in manager.migration_status().await.expect("获取迁移状态失败") {
+                    let state = match (status.applied, status.checksum_mismatch) {
+                        (true, false) => "applied",
+                        (true, true) => "modified",
+                        (false, _) => "pending",
+                    };
+
+                    println!("-> {} {:<8} {}", status.version, state, status.description);
+                }
+            }
+            MigrateManageCommands::Fresh { force } => {
+                println!("=> Fresh migrations");
+                if !force {
+                    println!("-> This will drop all data, rerun with --force to continue");
+                    return;
+                }
+
+                manager.migrate_fresh().await.expect("重新执行迁移失败");
+                println!("-> Done");
+            }
+        }
+    }
+}
@@ -1,10 +1,21 @@
+use std::future::Future;
+
 use clap::{Args, Subcommand};
+use inspirer_content::manager::Manager;
 use tokio::runtime::Runtime;
 
 use crate::manager::create_manager;
 
-use self::user::UserManage;
+use self::{
+    audit::AuditManage, key::KeyManage, migrate::MigrateManage, search::SearchManage,
+    token::TokenManage, user::UserManage,
+};
 
+pub mod audit;
+pub mod key;
+pub mod migrate;
+pub mod search;
+pub mod token;
 pub mod user;
 
 
@@ -16,19 +27,41 @@ pub struct  Manage {
 
 #[derive(Subcommand)]
 pub enum ManageCommands {
-    User(UserManage)
+    User(UserManage),
+    Migrate(MigrateManage),
+    Search(SearchManage),
+    /// 审计日志
+    Audit(AuditManage),
+    /// 个人访问令牌
+    Token(TokenManage),
+    /// 令牌签名密钥
+    Key(KeyManage),
 }
 
 impl Manage {
     pub fn run(self) {
-        let rt = Runtime::new().expect("创建运行时失败");
+        match self.commands {
+            ManageCommands::User(command) => block_on_manager(|manager| command.run(manager)),
+            ManageCommands::Migrate(command) => block_on_manager(|manager| command.run(manager)),
+            ManageCommands::Search(command) => block_on_manager(|manager| command.run(manager)),
+            ManageCommands::Audit(command) => block_on_manager(|manager| command.run(manager)),
+            ManageCommands::Token(command) => block_on_manager(|manager| command.run(manager)),
+            // 密钥管理不需要连接数据库
+            ManageCommands::Key(command) => command.run(),
+        }
+    }
+}
 
-        rt.block_on(async move {
-            let manager = create_manager().await.expect("创建 Manager 失败");
+/// 创建 Manager 后在新的运行时中执行命令
+fn block_on_manager<F, Fut>(run: F)
+where
+    F: FnOnce(Manager) -> Fut,
+    Fut: Future<Output = ()>,
+{
+    let rt = Runtime::new().expect("创建运行时失败");
 
-            match self.commands {
-                ManageCommands::User(command) => command.run(manager).await,
-            }
-        });
-    }
+    rt.block_on(async move {
+        let manager = create_manager().await.expect("创建 Manager 失败");
+        run(manager).await
+    });
 }
@@ -0,0 +1,30 @@
+use clap::{Args, Subcommand};
+use inspirer_content::{manager::Manager, service::search::SearchService};
+
+#[derive(Args)]
+pub struct SearchManage {
+    #[clap(subcommand)]
+    commands: SearchManageCommands,
+}
+
+#[derive(Subcommand)]
+pub enum SearchManageCommands {
+    /// 重建全部内容的搜索索引
+    Reindex,
+}
+
+impl SearchManage {
+    pub async fn run(self, manager: Manager) {
+        match self.commands {
+            SearchManageCommands::Reindex => {
+                println!("=> Rebuild search index");
+                let count = manager
+                    .rebuild_search_index()
+                    .await
+                    .expect("重建搜索索引失败");
+                println!("-> Indexed {count} contents");
+                println!("-> Done");
+            }
+        }
+    }
+}
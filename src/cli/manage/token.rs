@@ -0,0 +1,142 @@
+use chrono::{Duration, Utc};
+use clap::{Args, Subcommand};
+use inspirer_content::{
+    enumerate::{
+        audit::{AuditAction, AuditTargetType},
+        token::TokenScope,
+    },
+    manager::Manager,
+    model::{audit::NewAuditLog, token::NewAccessToken},
+    service::token::AccessTokenService,
+    util::uuid::{base62_to_uuid, uuid_to_base62},
+};
+
+use serde_json::json;
+
+use super::user::find_user;
+use crate::audit::cli_log;
+
+#[derive(Args)]
+pub struct TokenManage {
+    #[clap(subcommand)]
+    commands: TokenManageCommands,
+}
+
+#[derive(Subcommand)]
+pub enum TokenManageCommands {
+    /// 列出用户的个人访问令牌
+    List {
+        /// 用户名或用户 ID
+        user: String,
+    },
+    /// 为用户创建个人访问令牌，令牌仅显示一次
+    Create {
+        /// 用户名或用户 ID
+        user: String,
+        #[clap(short, long)]
+        name: String,
+        /// 权限范围：content:read、content:write、content:publish、content:delete，可指定多次
+        #[clap(short, long = "scope", required = true)]
+        scopes: Vec<TokenScope>,
+        /// 有效期（天），为空时永不过期
+        #[clap(long)]
+        expires_in: Option<i64>,
+    },
+    /// 吊销个人访问令牌
+    Revoke {
+        /// 用户名或用户 ID
+        user: String,
+        /// 令牌 ID
+        id: String,
+    },
+}
+
+impl TokenManage {
+    pub async fn run(self, manager: Manager) {
+        match self.commands {
+            TokenManageCommands::List { user } => {
+                let user = find_user(&manager, &user).await;
+                let tokens = manager
+                    .get_access_tokens(user.id)
+                    .await
+                    .expect("查询访问令牌失败");
+
+                println!(
+                    "{:<22}  {:<20}  {:<50}  {:<25}  LAST USED AT",
+                    "ID", "NAME", "SCOPES", "EXPIRES AT"
+                );
+                for token in tokens {
+                    let scopes = token
+                        .scopes()
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                        .join(",");
+
+                    println!(
+                        "{:<22}  {:<20}  {:<50}  {:<25}  {}",
+                        uuid_to_base62(token.id),
+                        token.name,
+                        scopes,
+                        token
+                            .expires_at
+                            .map(|time| time.to_rfc3339())
+                            .unwrap_or_else(|| "never".into()),
+                        token
+                            .last_used_at
+                            .map(|time| time.to_rfc3339())
+                            .unwrap_or_else(|| "never".into()),
+                    );
+                }
+            }
+            TokenManageCommands::Create {
+                user,
+                name,
+                scopes,
+                expires_in,
+            } => {
+                let user = find_user(&manager, &user).await;
+                println!("=> Create personal access token");
+                println!("-> Username = {}", user.username);
+
+                let issued = manager
+                    .create_access_token(
+                        user.id,
+                        NewAccessToken {
+                            name,
+                            scopes,
+                            expires_at: expires_in.map(|days| Utc::now() + Duration::days(days)),
+                        },
+                        Some(cli_log(
+                            NewAuditLog::new(AuditAction::AccessTokenCreated)
+                                .detail(json!({ "user_id": uuid_to_base62(user.id) })),
+                        )),
+                    )
+                    .await
+                    .expect("创建访问令牌失败");
+
+                println!("-> ID = {}", uuid_to_base62(issued.access_token.id));
+                if let Some(expires_at) = issued.access_token.expires_at {
+                    println!("-> Expires at = {}", expires_at.to_rfc3339());
+                }
+                println!("-> Token = {}", issued.token);
+            }
+            TokenManageCommands::Revoke { user, id } => {
+                let user = find_user(&manager, &user).await;
+                println!("=> Revoke personal access token");
+                println!("-> Username = {}", user.username);
+
+                let id = base62_to_uuid(&id).expect("令牌 ID 格式错误");
+                let log = NewAuditLog::new(AuditAction::AccessTokenRevoked)
+                    .target(AuditTargetType::AccessToken, id)
+                    .detail(json!({ "user_id": uuid_to_base62(user.id) }));
+                manager
+                    .revoke_access_token(user.id, id, Some(cli_log(log)))
+                    .await
+                    .expect("吊销访问令牌失败");
+
+                println!("-> Done");
+            }
+        }
+    }
+}
@@ -1,5 +1,29 @@
+use std::{net::IpAddr, path::PathBuf};
+
+use chrono::{DateTime, Duration, Utc};
 use clap::{Args, Subcommand};
-use inspirer_content::{manager::Manager, model, service::user::UserService};
+use inspirer_content::{
+    enumerate::{
+        audit::{AuditAction, AuditTargetType},
+        user::UserRole,
+    },
+    manager::Manager,
+    model::{self, audit::NewAuditLog, user::UserModel},
+    service::{throttle::LoginThrottleService, two_factor::TwoFactorService, user::UserService},
+    throttle::AttemptKey,
+    util::{
+        hash::to_hex,
+        uuid::{base62_to_uuid, uuid_to_base62, Uuid},
+    },
+};
+use serde::Serialize;
+use serde_json::json;
+
+use crate::{
+    audit::{cli_log, record_cli},
+    cli::prompt,
+    session::key_rotation_grace_period,
+};
 
 #[derive(Args)]
 pub struct UserManage {
@@ -14,25 +38,222 @@ pub enum UserManageCommands {
         username: String,
         #[clap(short, long)]
         nickname: Option<String>,
-        #[clap(short, long)]
+        /// 密码，会被记录在 shell 历史中，建议使用 --password-stdin 或交互输入
+        #[clap(short, long, conflicts_with = "password-stdin")]
+        password: Option<String>,
+        /// 从标准输入读取密码
+        #[clap(long)]
+        password_stdin: bool,
+        /// 用户角色：admin、editor、author、contributor
+        #[clap(short, long, default_value = "author")]
+        role: UserRole,
+    },
+    /// 列出全部用户
+    List {
+        /// 以 JSON 格式输出
+        #[clap(long)]
+        json: bool,
+    },
+    /// 查看用户详情
+    Show {
+        /// 用户名或用户 ID
+        user: String,
+        /// 以 JSON 格式输出
+        #[clap(long)]
+        json: bool,
+    },
+    /// 重置用户密码，用户的全部会话随之失效
+    SetPassword {
+        /// 用户名或用户 ID
+        user: String,
+        /// 密码，会被记录在 shell 历史中，建议使用 --password-stdin 或交互输入
+        #[clap(short, long, conflicts_with = "password-stdin")]
         password: Option<String>,
+        /// 从标准输入读取密码
+        #[clap(long)]
+        password_stdin: bool,
+    },
+    SetNickname {
+        /// 用户名或用户 ID
+        user: String,
+        nickname: String,
+    },
+    SetAvatar {
+        /// 用户名或用户 ID
+        user: String,
+        avatar: String,
+    },
+    /// 禁用用户，用户的全部会话随之失效
+    Disable {
+        /// 用户名或用户 ID
+        user: String,
     },
+    Enable {
+        /// 用户名或用户 ID
+        user: String,
+    },
+    /// 删除用户
+    Delete {
+        /// 用户名或用户 ID
+        user: String,
+        /// 接收被删除用户内容的用户（用户名或用户 ID），被删除用户仍拥有内容时必须指定
+        #[clap(long)]
+        reassign_to: Option<String>,
+    },
+    /// 重置用户的两步验证，用户丢失验证器及恢复码时使用
+    #[clap(name = "reset-2fa")]
+    ResetTwoFactor {
+        /// 用户名或用户 ID
+        user: String,
+    },
+    /// 解除用户名或 IP 的登录锁定
+    ///
+    /// 仅在 LOGIN_ATTEMPT_STORE=database 时对运行中的服务生效，内存中的失败记录只属于服务进程本身。
+    Unlock {
+        /// 登录时使用的用户名
+        #[clap(required_unless_present = "ip")]
+        user: Option<String>,
+        /// 同时解除该 IP 创建登录挑战的限制
+        #[clap(long)]
+        ip: Option<IpAddr>,
+    },
+    /// 重新生成用户密钥对，或登记用户自行生成的公钥
+    RotateKey {
+        /// 用户名或用户 ID
+        user: String,
+        /// PEM 或 OpenSSH 格式的 Ed25519 公钥文件，为空时生成新的密钥对
+        #[clap(long)]
+        public_key: Option<PathBuf>,
+        /// 旧公钥仍然有效的时间（分钟），为 0 时旧公钥立即失效，默认使用 KEY_ROTATION_GRACE_PERIOD
+        #[clap(long)]
+        grace_period: Option<i64>,
+    },
+}
+
+/// 命令行输出的用户信息
+#[derive(Serialize)]
+struct UserView {
+    id: String,
+    uuid: Uuid,
+    username: String,
+    nickname: String,
+    avatar: String,
+    role: UserRole,
+    public_key_fingerprint: String,
+    disabled_at: Option<DateTime<Utc>>,
+    two_factor_enabled: bool,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl From<UserModel> for UserView {
+    fn from(model: UserModel) -> Self {
+        UserView {
+            two_factor_enabled: model.two_factor_enabled(),
+            id: uuid_to_base62(model.id),
+            uuid: model.id,
+            username: model.username,
+            nickname: model.nickname,
+            avatar: model.avatar,
+            role: model.role,
+            public_key_fingerprint: to_hex(&model.public_key_fingerprint),
+            disabled_at: model.disabled_at,
+            created_at: model.created_at,
+            updated_at: model.updated_at,
+        }
+    }
+}
+
+impl UserView {
+    fn status(&self) -> &'static str {
+        if self.disabled_at.is_some() {
+            "disabled"
+        } else {
+            "active"
+        }
+    }
+}
+
+/// 对用户执行的操作的审计日志
+fn audit_user(action: AuditAction, id: Uuid) -> NewAuditLog {
+    NewAuditLog::new(action).target(AuditTargetType::User, id)
+}
+
+/// 根据用户名或用户 ID（Base62 或 UUID 格式）查找用户
+pub(super) async fn find_user(manager: &Manager, user: &str) -> UserModel {
+    if let Some(model) = manager
+        .get_user_by_username(user.to_string())
+        .await
+        .expect("查询用户失败")
+    {
+        return model;
+    }
+
+    let id = Uuid::parse_str(user)
+        .ok()
+        .or_else(|| base62_to_uuid(user).ok())
+        .expect("用户不存在");
+
+    manager
+        .get_user_by_id(id)
+        .await
+        .expect("查询用户失败")
+        .expect("用户不存在")
+}
+
+/// 读取密码：优先使用命令行参数，其次为标准输入，否则在终端中提示输入并确认
+fn read_password(password: Option<String>, password_stdin: bool) -> String {
+    if let Some(password) = password {
+        return password;
+    }
+
+    if password_stdin {
+        return prompt::read_line(&mut std::io::stdin().lock()).expect("读取密码失败");
+    }
+
+    let password = prompt::read_hidden("Password: ").expect("读取密码失败");
+    let confirmation = prompt::read_hidden("Confirm password: ").expect("读取密码失败");
+    assert!(password == confirmation, "两次输入的密码不一致");
+
+    password
+}
+
+fn print_json<T: Serialize>(value: &T) {
+    println!(
+        "{}",
+        serde_json::to_string_pretty(value).expect("序列化用户信息失败")
+    );
 }
 
 impl UserManage {
     pub async fn run(self, manager: Manager) {
         match self.commands {
-            UserManageCommands::Create { username, nickname, password } => {
+            UserManageCommands::Create {
+                username,
+                nickname,
+                password,
+                password_stdin,
+                role,
+            } => {
                 println!("=> Create user");
                 println!("-> Username = {username}");
-                
+                println!("-> Role = {role}");
+
+                let password = read_password(password, password_stdin);
+
+                let log = NewAuditLog::new(AuditAction::UserCreated)
+                    .detail(json!({ "username": username, "role": role }));
                 let (uuid, pkey) = manager
-                    .create_user_simple(model::user::NewUser {
-                        username,
-                        nickname: nickname.unwrap_or_default(),
-                        password: password.unwrap_or_default(),
-                        avatar: Default::default(),
-                    })
+                    .create_user_simple(
+                        model::user::NewUser {
+                            username: username.clone(),
+                            nickname: nickname.unwrap_or_default(),
+                            password,
+                            avatar: Default::default(),
+                            role,
+                        },
+                        Some(cli_log(log)),
+                    )
                     .await
                     .expect("创建用户失败");
 
@@ -40,6 +261,266 @@ impl UserManage {
                 println!("-> Private key:");
                 println!("{pkey}");
             }
+            UserManageCommands::List { json } => {
+                let users: Vec<UserView> = manager
+                    .get_users()
+                    .await
+                    .expect("查询用户失败")
+                    .into_iter()
+                    .map(UserView::from)
+                    .collect();
+
+                if json {
+                    print_json(&users);
+                    return;
+                }
+
+                println!(
+                    "{:<22}  {:<20}  {:<20}  {:<11}  {:<8}  CREATED AT",
+                    "ID", "USERNAME", "NICKNAME", "ROLE", "STATUS"
+                );
+                for user in &users {
+                    println!(
+                        "{:<22}  {:<20}  {:<20}  {:<11}  {:<8}  {}",
+                        user.id,
+                        user.username,
+                        user.nickname,
+                        user.role.to_string(),
+                        user.status(),
+                        user.created_at.to_rfc3339(),
+                    );
+                }
+            }
+            UserManageCommands::Show { user, json } => {
+                let user = UserView::from(find_user(&manager, &user).await);
+
+                if json {
+                    print_json(&user);
+                    return;
+                }
+
+                println!("-> ID = {}", user.id);
+                println!("-> UUID = {}", user.uuid);
+                println!("-> Username = {}", user.username);
+                println!("-> Nickname = {}", user.nickname);
+                println!("-> Avatar = {}", user.avatar);
+                println!("-> Role = {}", user.role);
+                println!("-> Status = {}", user.status());
+                println!("-> Fingerprint = {}", user.public_key_fingerprint);
+                println!("-> Two-factor = {}", user.two_factor_enabled);
+                println!("-> Created at = {}", user.created_at.to_rfc3339());
+                println!("-> Updated at = {}", user.updated_at.to_rfc3339());
+            }
+            UserManageCommands::SetPassword {
+                user,
+                password,
+                password_stdin,
+            } => {
+                let user = find_user(&manager, &user).await;
+                println!("=> Reset password");
+                println!("-> Username = {}", user.username);
+
+                let password = read_password(password, password_stdin);
+                manager
+                    .set_password(
+                        user.id,
+                        password,
+                        Some(cli_log(audit_user(
+                            AuditAction::UserPasswordChanged,
+                            user.id,
+                        ))),
+                    )
+                    .await
+                    .expect("重置密码失败");
+
+                println!("-> Done");
+            }
+            UserManageCommands::SetNickname { user, nickname } => {
+                let user = find_user(&manager, &user).await;
+                manager
+                    .update_user(
+                        user.id,
+                        model::user::UpdateUser {
+                            nickname: Some(nickname),
+                            ..Default::default()
+                        },
+                        Some(cli_log(audit_user(AuditAction::UserUpdated, user.id))),
+                    )
+                    .await
+                    .expect("更新用户失败");
+
+                println!("-> Done");
+            }
+            UserManageCommands::SetAvatar { user, avatar } => {
+                let user = find_user(&manager, &user).await;
+                manager
+                    .update_user(
+                        user.id,
+                        model::user::UpdateUser {
+                            avatar: Some(avatar),
+                            ..Default::default()
+                        },
+                        Some(cli_log(audit_user(AuditAction::UserUpdated, user.id))),
+                    )
+                    .await
+                    .expect("更新用户失败");
+
+                println!("-> Done");
+            }
+            UserManageCommands::Disable { user } => {
+                let user = find_user(&manager, &user).await;
+                println!("=> Disable user");
+                println!("-> Username = {}", user.username);
+
+                manager
+                    .set_user_disabled(
+                        user.id,
+                        true,
+                        Some(cli_log(audit_user(AuditAction::UserDisabled, user.id))),
+                    )
+                    .await
+                    .expect("禁用用户失败");
+
+                println!("-> Done");
+            }
+            UserManageCommands::Enable { user } => {
+                let user = find_user(&manager, &user).await;
+                println!("=> Enable user");
+                println!("-> Username = {}", user.username);
+
+                manager
+                    .set_user_disabled(
+                        user.id,
+                        false,
+                        Some(cli_log(audit_user(AuditAction::UserEnabled, user.id))),
+                    )
+                    .await
+                    .expect("启用用户失败");
+
+                println!("-> Done");
+            }
+            UserManageCommands::Delete { user, reassign_to } => {
+                let user = find_user(&manager, &user).await;
+                let reassign_to = match reassign_to {
+                    Some(target) => Some(find_user(&manager, &target).await),
+                    None => None,
+                };
+
+                println!("=> Delete user");
+                println!("-> Username = {}", user.username);
+                if let Some(target) = &reassign_to {
+                    println!("-> Reassign contents to = {}", target.username);
+                }
+
+                let log = audit_user(AuditAction::UserDeleted, user.id).detail(json!({
+                    "username": user.username,
+                    "reassign_to": reassign_to.as_ref().map(|target| uuid_to_base62(target.id)),
+                }));
+                let reassigned = manager
+                    .delete_user(
+                        user.id,
+                        reassign_to.map(|target| target.id),
+                        Some(cli_log(log)),
+                    )
+                    .await
+                    .expect("删除用户失败");
+
+                println!("-> Reassigned {reassigned} contents");
+                println!("-> Done");
+            }
+            UserManageCommands::ResetTwoFactor { user } => {
+                let user = find_user(&manager, &user).await;
+                println!("=> Reset two-factor authentication");
+                println!("-> Username = {}", user.username);
+
+                let log = audit_user(AuditAction::UserTwoFactorDisabled, user.id)
+                    .detail(json!({ "reset": true }));
+                manager
+                    .reset_two_factor(user.id, Some(cli_log(log)))
+                    .await
+                    .expect("重置两步验证失败");
+
+                println!("-> Done");
+            }
+            UserManageCommands::Unlock { user, ip } => {
+                println!("=> Unlock login");
+
+                let ip_keys = ip
+                    .into_iter()
+                    .flat_map(|ip| [AttemptKey::Ip(ip), AttemptKey::Challenge(ip)]);
+                let keys = user.map(AttemptKey::Username).into_iter().chain(ip_keys);
+
+                for key in keys {
+                    let removed = manager
+                        .unlock_login(key.clone())
+                        .await
+                        .expect("解除锁定失败");
+                    // 失败记录可能保存在内存中，无法与审计日志在同一事务中写入，记录失败时仅提示
+                    if removed {
+                        let log = NewAuditLog::new(AuditAction::UserUnlocked)
+                            .detail(json!({ "key": key.to_string() }));
+                        if let Err(err) = record_cli(&manager, log).await {
+                            eprintln!("-> Failed to record audit log: {err}");
+                        }
+                    }
+
+                    println!(
+                        "-> {key} {}",
+                        if removed {
+                            "unlocked"
+                        } else {
+                            "has no failed attempts"
+                        }
+                    );
+                }
+
+                println!("-> Done");
+            }
+            UserManageCommands::RotateKey {
+                user,
+                public_key,
+                grace_period,
+            } => {
+                let user = find_user(&manager, &user).await;
+                println!("=> Rotate user key");
+                println!("-> Username = {}", user.username);
+
+                let grace_period = grace_period
+                    .map(Duration::minutes)
+                    .unwrap_or_else(key_rotation_grace_period);
+
+                match public_key {
+                    Some(path) => {
+                        let public_key = std::fs::read_to_string(path).expect("读取公钥文件失败");
+                        let log = Some(cli_log(audit_user(AuditAction::UserKeyChanged, user.id)));
+                        let user = manager
+                            .register_public_key(user.id, &public_key, grace_period, log)
+                            .await
+                            .expect("登记公钥失败");
+
+                        println!("-> Fingerprint = {}", to_hex(&user.public_key_fingerprint));
+                    }
+                    None => {
+                        let log = Some(cli_log(audit_user(AuditAction::UserKeyChanged, user.id)));
+                        let pkey = manager
+                            .rotate_keypair(user.id, grace_period, log)
+                            .await
+                            .expect("生成密钥对失败");
+
+                        println!("-> Private key:");
+                        println!("{pkey}");
+                    }
+                }
+
+                if grace_period > Duration::zero() {
+                    println!(
+                        "-> Previous key expires in {} minutes",
+                        grace_period.num_minutes()
+                    );
+                } else {
+                    println!("-> Previous key revoked");
+                }
+            }
         }
     }
 }
@@ -5,6 +5,7 @@ use clap::{Parser, Subcommand};
 use self::manage::Manage;
 
 pub mod manage;
+pub mod prompt;
 
 #[derive(Parser)]
 #[clap(version, about)]
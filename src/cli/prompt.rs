@@ -0,0 +1,16 @@
+//! 命令行交互输入
+
+use std::io::{self, BufRead};
+
+/// 在终端中提示并读取一行输入，输入内容不回显
+pub fn read_hidden(prompt: &str) -> io::Result<String> {
+    rpassword::prompt_password(prompt)
+}
+
+/// 读取一行输入，去除末尾的换行符
+pub fn read_line(reader: &mut impl BufRead) -> io::Result<String> {
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+
+    Ok(line.trim_end_matches(['\r', '\n']).to_string())
+}
@@ -0,0 +1,28 @@
+use axum::{extract::Query, Extension, Json};
+use inspirer_content::{
+    manager::Manager,
+    model::paginate::{Paginated, Pagination},
+    service::audit::AuditService,
+};
+
+use crate::{
+    error::InspirerResult, request::audit::AuditLogQuery, response::audit::AuditLog,
+    session::SessionInfo,
+};
+
+/// 查询审计日志（仅管理员），按时间倒序排列
+pub async fn get_audit_logs(
+    Extension(manager): Extension<Manager>,
+    session: SessionInfo,
+    Query(pagination): Query<Pagination>,
+    Query(query): Query<AuditLogQuery>,
+) -> InspirerResult<Json<Paginated<AuditLog>>> {
+    session.authorize_audit()?;
+
+    manager
+        .get_audit_logs(query.try_into()?, pagination)
+        .await
+        .map(|res| res.map(|data| data.into_iter().map(AuditLog::from).collect()))
+        .map_err(Into::into)
+        .map(Json)
+}
@@ -1,22 +1,286 @@
-use axum::{Extension, Json};
-use inspirer_content::{manager::Manager, service::user::UserService, util::uuid::uuid_to_base62};
+use axum::{extract::Path, http::header, response::IntoResponse, Extension, Json};
+use inspirer_content::{
+    enumerate::audit::{AuditAction, AuditTargetType},
+    error::{Error as InspirerContentError, InspirerContentResult},
+    manager::Manager,
+    model::{audit::NewAuditLog, session::IssuedSession, user::UserModel},
+    service::{
+        session::SessionService, throttle::LoginThrottleService, token::AccessTokenService,
+        user::UserService,
+    },
+    util::{
+        hash::from_hex,
+        uuid::{base62_to_uuid, Uuid},
+    },
+};
 
 use crate::{
+    audit::ClientInfo,
     error::{InspirerError, InspirerResult},
-    request::auth::LoginPayload,
-    response::auth::{AccessToken, UserProfile},
-    session::{Claims, SessionInfo},
+    jwk::TOKEN_KEYS,
+    request::auth::{
+        ChangePasswordPayload, ConfirmPasswordPayload, CreateAccessTokenPayload,
+        DisableTwoFactorPayload, KeyLoginPayload, LoginChallengePayload, LoginPayload,
+        RefreshTokenPayload, RegisterPublicKeyPayload, TwoFactorCodePayload, TwoFactorLoginPayload,
+        UpdateProfilePayload,
+    },
+    response::auth::{
+        AccessToken, CreatedAccessToken, LoginChallenge, LoginResult, PersonalAccessToken,
+        RotatedKeyPair, TwoFactorChallenge, TwoFactorEnrollment, UserProfile, UserSession,
+    },
+    session::{
+        key_rotation_grace_period, login_challenge_expiration, refresh_token_expires_at, Claims,
+        SessionInfo, TwoFactorClaims, EXPIRATION,
+    },
+    site::SITE,
 };
 
+/// 登录失败时记录的用户名最大长度，与数据库字段长度一致
+const MAX_AUDIT_USERNAME_LENGTH: usize = 40;
+
+/// 记录登录失败，`detail` 中记录登录方式并追加失败原因，登录成功在创建会话时一并记录
+///
+/// 因尝试次数过多被拒绝的登录不记录，避免暴力尝试时产生大量日志，限流本身会记录日志。
+async fn audit_login(
+    manager: &Manager,
+    client: &ClientInfo,
+    result: &InspirerContentResult<UserModel>,
+    target: Option<Uuid>,
+    mut detail: serde_json::Value,
+) -> InspirerResult<()> {
+    let log = match result {
+        Ok(_) | Err(InspirerContentError::TooManyLoginAttempts { .. }) => return Ok(()),
+        Err(err) => {
+            detail["reason"] = err.to_string().into();
+
+            let log = NewAuditLog::new(AuditAction::LoginFailed);
+            match target {
+                Some(id) => log.target(AuditTargetType::User, id),
+                None => log,
+            }
+        }
+    };
+
+    client.audit(manager, log.detail(detail)).await
+}
+
+/// 校验访问令牌的公钥，其他服务可据此校验令牌而无需共享密钥
+pub async fn jwks() -> impl IntoResponse {
+    (
+        [(header::CACHE_CONTROL, "public, max-age=300")],
+        Json(TOKEN_KEYS.jwks()),
+    )
+}
+
+fn issue_access_token(issued: IssuedSession) -> InspirerResult<AccessToken> {
+    Ok(AccessToken {
+        access_token: Claims::from(&issued.session).to_token()?,
+        token_type: "Bearer",
+        expires_in: *EXPIRATION * 60,
+        refresh_token: issued.refresh_token,
+    })
+}
+
+/// 为登录成功的用户创建会话并签发令牌，`detail` 中记录登录方式
+async fn start_session(
+    manager: &Manager,
+    user: UserModel,
+    client: &ClientInfo,
+    detail: serde_json::Value,
+) -> InspirerResult<AccessToken> {
+    let log = NewAuditLog::new(AuditAction::Login)
+        .actor(user.id)
+        .target(AuditTargetType::User, user.id)
+        .detail(detail);
+
+    let issued = manager
+        .create_session(
+            user.id,
+            client.user_agent.clone(),
+            refresh_token_expires_at(),
+            Some(client.audit_log(log)),
+        )
+        .await?;
+
+    issue_access_token(issued)
+}
+
+/// 已开启两步验证的用户返回两步验证令牌，否则直接创建会话
+async fn complete_login(
+    manager: &Manager,
+    user: UserModel,
+    client: &ClientInfo,
+    detail: serde_json::Value,
+) -> InspirerResult<LoginResult> {
+    if user.two_factor_enabled() {
+        return Ok(LoginResult::TwoFactorRequired(TwoFactorChallenge {
+            two_factor_token: TwoFactorClaims::new(user.id).to_token()?,
+            expires_in: TwoFactorClaims::expires_in(),
+        }));
+    }
+
+    // 开启两步验证的用户在完成两步验证后记录登录成功
+    start_session(manager, user, client, detail)
+        .await
+        .map(LoginResult::AccessToken)
+}
+
+/// 使用密码登录，已开启两步验证时返回两步验证令牌
 pub async fn login(
     Extension(manager): Extension<Manager>,
+    client: ClientInfo,
     Json(payload): Json<LoginPayload>,
+) -> InspirerResult<Json<LoginResult>> {
+    // 用户名由客户端提交，截断后记录
+    let username: String = payload
+        .username
+        .chars()
+        .take(MAX_AUDIT_USERNAME_LENGTH)
+        .collect();
+    let detail = serde_json::json!({ "method": "password", "username": username });
+    let result = manager
+        .attempt_throttled(payload.username, payload.password, Some(client.ip))
+        .await;
+    audit_login(&manager, &client, &result, None, detail.clone()).await?;
+
+    complete_login(&manager, result?, &client, detail)
+        .await
+        .map(Json)
+}
+
+pub async fn login_two_factor(
+    Extension(manager): Extension<Manager>,
+    client: ClientInfo,
+    Json(payload): Json<TwoFactorLoginPayload>,
 ) -> InspirerResult<Json<AccessToken>> {
-    let user = manager.attempt(payload.username, payload.password).await?;
+    let claims = TwoFactorClaims::from_token(&payload.two_factor_token)?;
+
+    let result = manager
+        .verify_two_factor_throttled(claims.user_id(), payload.code, Some(client.ip))
+        .await;
+    let detail = serde_json::json!({ "method": "two-factor" });
+    audit_login(
+        &manager,
+        &client,
+        &result,
+        Some(claims.user_id()),
+        detail.clone(),
+    )
+    .await?;
+
+    start_session(&manager, result?, &client, detail)
+        .await
+        .map(Json)
+}
+
+pub async fn login_challenge(
+    Extension(manager): Extension<Manager>,
+    client: ClientInfo,
+    Json(payload): Json<LoginChallengePayload>,
+) -> InspirerResult<Json<LoginChallenge>> {
+    let fingerprint = from_hex(&payload.fingerprint)
+        .ok_or_else(|| InspirerError::InvalidRequest("公钥指纹格式错误".into()))?;
+
+    manager
+        .create_login_challenge_throttled(
+            fingerprint,
+            login_challenge_expiration(),
+            Some(client.ip),
+        )
+        .await
+        .map(LoginChallenge::from)
+        .map(Json)
+        .map_err(Into::into)
+}
+
+/// 使用密钥登录，已开启两步验证时返回两步验证令牌
+pub async fn login_with_key(
+    Extension(manager): Extension<Manager>,
+    client: ClientInfo,
+    Json(payload): Json<KeyLoginPayload>,
+) -> InspirerResult<Json<LoginResult>> {
+    let signature = from_hex(&payload.signature)
+        .ok_or_else(|| InspirerError::InvalidRequest("签名格式错误".into()))?;
+
+    let result = manager
+        .attempt_with_key_throttled(payload.challenge, signature, Some(client.ip))
+        .await;
+    let detail = serde_json::json!({ "method": "key" });
+    audit_login(&manager, &client, &result, None, detail.clone()).await?;
+
+    complete_login(&manager, result?, &client, detail)
+        .await
+        .map(Json)
+}
+
+/// 使用刷新令牌换取新的访问令牌，刷新令牌同时被轮换
+pub async fn refresh_token(
+    Extension(manager): Extension<Manager>,
+    client: ClientInfo,
+    Json(payload): Json<RefreshTokenPayload>,
+) -> InspirerResult<Json<AccessToken>> {
+    let issued = manager
+        .refresh_session(
+            payload.refresh_token,
+            refresh_token_expires_at(),
+            Some(client.audit_log(NewAuditLog::new(AuditAction::TokenRefreshed))),
+        )
+        .await?;
+
+    issue_access_token(issued).map(Json)
+}
+
+pub async fn logout(
+    Extension(manager): Extension<Manager>,
+    session: SessionInfo,
+    client: ClientInfo,
+) -> InspirerResult<Json<()>> {
+    let session_id = session
+        .session_id()
+        .ok_or_else(|| InspirerError::InvalidRequest("当前请求未使用登录会话".into()))?;
+
+    let log = session
+        .audit_log(AuditAction::Logout)
+        .target(AuditTargetType::Session, session_id);
+    manager
+        .revoke_session(session.uuid(), session_id, Some(client.audit_log(log)))
+        .await?;
+
+    Ok(Json(()))
+}
+
+pub async fn get_sessions(
+    Extension(manager): Extension<Manager>,
+    session: SessionInfo,
+) -> InspirerResult<Json<Vec<UserSession>>> {
+    manager
+        .get_active_sessions(session.uuid())
+        .await
+        .map(|sessions| {
+            sessions
+                .into_iter()
+                .map(|model| UserSession::new(model, session.session_id()))
+                .collect()
+        })
+        .map(Json)
+        .map_err(Into::into)
+}
+
+pub async fn revoke_session(
+    Extension(manager): Extension<Manager>,
+    Path((id,)): Path<(String,)>,
+    session: SessionInfo,
+    client: ClientInfo,
+) -> InspirerResult<Json<()>> {
+    let id = base62_to_uuid(&id)?;
+    let log = session
+        .audit_log(AuditAction::SessionRevoked)
+        .target(AuditTargetType::Session, id);
+    manager
+        .revoke_session(session.uuid(), id, Some(client.audit_log(log)))
+        .await?;
 
-    Ok(Json(AccessToken {
-        access_token: Claims::from(user).to_token()?,
-    }))
+    Ok(Json(()))
 }
 
 pub async fn get_profile(
@@ -27,11 +291,184 @@ pub async fn get_profile(
         .get_user_by_id(session.uuid())
         .await?
         .ok_or(InspirerError::Unauthorized)
-        .map(|model| UserProfile {
-            id: uuid_to_base62(model.id),
-            nickname: model.nickname,
-            username: model.username,
-            avatar: model.avatar,
-        })
+        .and_then(|user| Ok(Json(UserProfile::try_from(user)?)))
+}
+
+pub async fn update_profile(
+    Extension(manager): Extension<Manager>,
+    session: SessionInfo,
+    client: ClientInfo,
+    Json(payload): Json<UpdateProfilePayload>,
+) -> InspirerResult<Json<UserProfile>> {
+    let log = Some(client.audit_log(session.audit_user(AuditAction::UserUpdated)));
+    let user = manager
+        .update_user(session.uuid(), payload.into(), log)
+        .await?;
+
+    Ok(Json(UserProfile::try_from(user)?))
+}
+
+/// 修改当前用户的密码，当前会话以外的会话随之失效
+pub async fn change_password(
+    Extension(manager): Extension<Manager>,
+    session: SessionInfo,
+    client: ClientInfo,
+    Json(payload): Json<ChangePasswordPayload>,
+) -> InspirerResult<Json<()>> {
+    manager
+        .change_password_throttled(
+            session.uuid(),
+            payload.current_password,
+            payload.new_password,
+            session.session_id(),
+            Some(client.ip),
+            Some(client.audit_log(session.audit_user(AuditAction::UserPasswordChanged))),
+        )
+        .await?;
+
+    Ok(Json(()))
+}
+
+/// 重新生成当前用户的密钥对，原公钥在宽限期内仍然有效，需确认当前密码
+pub async fn rotate_keypair(
+    Extension(manager): Extension<Manager>,
+    session: SessionInfo,
+    client: ClientInfo,
+    Json(payload): Json<ConfirmPasswordPayload>,
+) -> InspirerResult<Json<RotatedKeyPair>> {
+    manager
+        .verify_user_password_throttled(session.uuid(), payload.password, Some(client.ip))
+        .await?;
+    let private_key = manager
+        .rotate_keypair(
+            session.uuid(),
+            key_rotation_grace_period(),
+            Some(client.audit_log(session.audit_user(AuditAction::UserKeyChanged))),
+        )
+        .await?;
+
+    Ok(Json(RotatedKeyPair { private_key }))
+}
+
+/// 登记用户自行生成的公钥，原公钥在宽限期内仍然有效，需确认当前密码
+pub async fn register_public_key(
+    Extension(manager): Extension<Manager>,
+    session: SessionInfo,
+    client: ClientInfo,
+    Json(payload): Json<RegisterPublicKeyPayload>,
+) -> InspirerResult<Json<UserProfile>> {
+    manager
+        .verify_user_password_throttled(session.uuid(), payload.password, Some(client.ip))
+        .await?;
+    let user = manager
+        .register_public_key(
+            session.uuid(),
+            &payload.public_key,
+            key_rotation_grace_period(),
+            Some(client.audit_log(session.audit_user(AuditAction::UserKeyChanged))),
+        )
+        .await?;
+
+    Ok(Json(UserProfile::try_from(user)?))
+}
+
+/// 登记两步验证，需确认当前密码，使用验证码确认后才会开启
+pub async fn enroll_two_factor(
+    Extension(manager): Extension<Manager>,
+    session: SessionInfo,
+    client: ClientInfo,
+    Json(payload): Json<ConfirmPasswordPayload>,
+) -> InspirerResult<Json<TwoFactorEnrollment>> {
+    manager
+        .enroll_two_factor_throttled(
+            session.uuid(),
+            payload.password,
+            &SITE.title,
+            Some(client.ip),
+        )
+        .await
+        .map(TwoFactorEnrollment::from)
         .map(Json)
+        .map_err(Into::into)
+}
+
+pub async fn confirm_two_factor(
+    Extension(manager): Extension<Manager>,
+    session: SessionInfo,
+    client: ClientInfo,
+    Json(payload): Json<TwoFactorCodePayload>,
+) -> InspirerResult<Json<UserProfile>> {
+    let log = Some(client.audit_log(session.audit_user(AuditAction::UserTwoFactorEnabled)));
+    let user = manager
+        .confirm_two_factor_throttled(session.uuid(), payload.code, Some(client.ip), log)
+        .await?;
+
+    Ok(Json(UserProfile::try_from(user)?))
+}
+
+/// 关闭两步验证，需提供当前密码，其他会话随之失效
+pub async fn disable_two_factor(
+    Extension(manager): Extension<Manager>,
+    session: SessionInfo,
+    client: ClientInfo,
+    Json(payload): Json<DisableTwoFactorPayload>,
+) -> InspirerResult<Json<()>> {
+    manager
+        .disable_two_factor_throttled(
+            session.uuid(),
+            payload.password,
+            session.session_id(),
+            Some(client.ip),
+            Some(client.audit_log(session.audit_user(AuditAction::UserTwoFactorDisabled))),
+        )
+        .await?;
+
+    Ok(Json(()))
+}
+
+pub async fn get_access_tokens(
+    Extension(manager): Extension<Manager>,
+    session: SessionInfo,
+) -> InspirerResult<Json<Vec<PersonalAccessToken>>> {
+    manager
+        .get_access_tokens(session.uuid())
+        .await
+        .map(|tokens| tokens.into_iter().map(PersonalAccessToken::from).collect())
+        .map(Json)
+        .map_err(Into::into)
+}
+
+/// 创建个人访问令牌，需确认当前密码，令牌明文仅在创建时返回
+pub async fn create_access_token(
+    Extension(manager): Extension<Manager>,
+    session: SessionInfo,
+    client: ClientInfo,
+    Json(payload): Json<CreateAccessTokenPayload>,
+) -> InspirerResult<Json<CreatedAccessToken>> {
+    manager
+        .verify_user_password_throttled(session.uuid(), payload.password.clone(), Some(client.ip))
+        .await?;
+    let log = Some(client.audit_log(session.audit_log(AuditAction::AccessTokenCreated)));
+    let issued = manager
+        .create_access_token(session.uuid(), payload.into(), log)
+        .await?;
+
+    Ok(Json(CreatedAccessToken::from(issued)))
+}
+
+pub async fn revoke_access_token(
+    Extension(manager): Extension<Manager>,
+    Path((id,)): Path<(String,)>,
+    session: SessionInfo,
+    client: ClientInfo,
+) -> InspirerResult<Json<()>> {
+    let id = base62_to_uuid(&id)?;
+    let log = session
+        .audit_log(AuditAction::AccessTokenRevoked)
+        .target(AuditTargetType::AccessToken, id);
+    manager
+        .revoke_access_token(session.uuid(), id, Some(client.audit_log(log)))
+        .await?;
+
+    Ok(Json(()))
 }
@@ -1,48 +1,50 @@
+use std::collections::HashMap;
+
 use crate::{
-    error::InspirerResult,
-    request::content::{CreateContent, ForceDelete, UpdateContent},
-    response::{
-        content::{
-            ContentBase, ContentConfig, ContentFull, ContentFullWithEntity, ContentWithEntity,
-            DeletedContent,
-        },
+    audit::ClientInfo,
+    error::{InspirerError, InspirerResult},
+    request::content::{
+        BulkContent, BulkContentAction, CreateContent, ForceDelete, PublishContent, RenderContent,
+        RenderFormat, RevisionDiff, UpdateContent,
+    },
+    response::content::{
+        BulkContentResult, ContentBase, ContentConfig, ContentDiff, ContentFull,
+        ContentFullWithEntity, ContentRevision, ContentRevisionWithSnapshot, ContentWithEntity,
+        DeletedContent,
     },
     session::SessionInfo,
 };
 use axum::{
-    extract::{Path, Query},
+    extract::{rejection::JsonRejection, Path, Query},
     Extension, Json,
 };
 use inspirer_content::{
+    enumerate::{
+        audit::{AuditAction, AuditTargetType},
+        token::TokenScope,
+    },
     error::Error,
     manager::Manager,
     model::{
-        content::{GetListCondition, SortField},
+        audit::NewAuditLog,
+        content::{Content, GetListCondition, SortField},
         paginate::{Paginated, Pagination},
+        user::ContentAction,
         Order,
     },
     service::content::ContentService,
-    util::uuid::base62_to_uuid,
+    util::uuid::{base62_to_uuid, uuid_to_base62},
 };
 
+/// 单次批量操作的最大内容数量
+const MAX_BULK_CONTENTS: usize = 100;
+
 pub async fn get_content_list_simple(
     Query(pagination): Query<Pagination>,
     Extension(manager): Extension<Manager>,
 ) -> InspirerResult<Json<Paginated<ContentBase>>> {
     manager
-        .get_list(
-            GetListCondition {
-                with_hidden: false,
-                with_unpublish: false,
-                without_page: true,
-                list_deleted: false,
-                sort: vec![
-                    Order::Desc(SortField::PublishedAt),
-                    Order::Desc(SortField::CreatedAt),
-                ],
-            },
-            pagination,
-        )
+        .get_list(GetListCondition::published(), pagination)
         .await
         .map(|res| res.map(|data| data.into_iter().map(ContentBase::from).collect()))
         .map_err(Into::into)
@@ -51,6 +53,7 @@ pub async fn get_content_list_simple(
 
 pub async fn find_content(
     Path((id,)): Path<(String,)>,
+    Query(option): Query<RenderContent>,
     Extension(manager): Extension<Manager>,
 ) -> InspirerResult<Json<ContentWithEntity>> {
     let content = match manager.find_content_by_name(id.clone()).await {
@@ -59,7 +62,14 @@ pub async fn find_content(
         Err(err) => Err(err)?,
     };
 
-    Ok(Json(ContentWithEntity::from(content)))
+    let html = option
+        .render
+        .map(|RenderFormat::Html| manager.render_content(&content));
+
+    Ok(Json(ContentWithEntity {
+        html,
+        ..ContentWithEntity::from(content)
+    }))
 }
 
 pub async fn create_content(
@@ -68,17 +78,40 @@ pub async fn create_content(
     Json(payload): Json<CreateContent>,
 ) -> InspirerResult<Json<ContentWithEntity>> {
     manager
-        .create_content(session.uuid(), payload)
+        .create_content(session.uuid(), payload, session.can_create_terms())
         .await
         .map_err(Into::into)
         .map(ContentWithEntity::from)
         .map(Json)
 }
 
-/// 获取内容列表（需要授权登录）
+/// 内容操作的审计日志，记录标题及所有者，便于内容被彻底删除后追溯
+fn audit_content(session: &SessionInfo, action: AuditAction, content: &Content) -> NewAuditLog {
+    session
+        .audit_log(action)
+        .target(AuditTargetType::Content, content.meta.id)
+        .detail(serde_json::json!({
+            "title": content.meta.title,
+            "owner_id": uuid_to_base62(content.meta.owner_id),
+        }))
+}
+
+async fn find_authorized_content(
+    manager: &Manager,
+    session: &SessionInfo,
+    id: &str,
+    action: ContentAction,
+) -> InspirerResult<Content> {
+    let content = manager.find_content_by_id(base62_to_uuid(id)?).await?;
+    session.authorize_content(action, content.meta.owner_id)?;
+
+    Ok(content)
+}
+
+/// 获取内容列表（需要授权登录），无权管理他人内容的用户仅能看到自己的内容
 pub async fn get_content_list(
     Extension(manager): Extension<Manager>,
-    // session: SessionInfo,
+    session: SessionInfo,
     Query(pagination): Query<Pagination>,
 ) -> InspirerResult<Json<Paginated<ContentFull>>> {
     manager
@@ -88,7 +121,9 @@ pub async fn get_content_list(
                 with_unpublish: true,
                 without_page: false,
                 list_deleted: false,
+                owner_id: session.content_owner_scope(),
                 sort: vec![Order::Desc(SortField::CreatedAt)],
+                ..Default::default()
             },
             pagination,
         )
@@ -100,6 +135,7 @@ pub async fn get_content_list(
 
 pub async fn get_deleted_content_list(
     Extension(manager): Extension<Manager>,
+    session: SessionInfo,
     Query(pagination): Query<Pagination>,
 ) -> InspirerResult<Json<Paginated<DeletedContent>>> {
     manager
@@ -109,7 +145,9 @@ pub async fn get_deleted_content_list(
                 with_unpublish: true,
                 without_page: false,
                 list_deleted: true,
+                owner_id: session.content_owner_scope(),
                 sort: vec![Order::Desc(SortField::DeletedAt)],
+                ..Default::default()
             },
             pagination,
         )
@@ -122,17 +160,12 @@ pub async fn get_deleted_content_list(
 pub async fn get_content(
     Extension(manager): Extension<Manager>,
     Path((id,)): Path<(String,)>,
-    _session: SessionInfo,
+    session: SessionInfo,
 ) -> InspirerResult<Json<ContentFullWithEntity>> {
-    manager
-        .find_content_by_id(base62_to_uuid(&id)?)
+    find_authorized_content(&manager, &session, &id, ContentAction::View)
         .await
-        .map(|content| ContentFullWithEntity {
-            content: ContentFull::from(content.meta),
-            entity: content.entity,
-        })
+        .map(ContentFullWithEntity::from)
         .map(Json)
-        .map_err(Into::into)
 }
 
 pub async fn update_content(
@@ -141,19 +174,50 @@ pub async fn update_content(
     Json(payload): Json<UpdateContent>,
     session: SessionInfo,
 ) -> InspirerResult<Json<()>> {
+    let content = find_authorized_content(&manager, &session, &id, ContentAction::Update).await?;
+
     manager
-        .update_content(session.uuid(), base62_to_uuid(&id)?, payload)
+        .update_content(
+            session.uuid(),
+            content.meta.id,
+            payload,
+            session.can_create_terms(),
+        )
         .await?;
 
     Ok(Json(()))
 }
 
+/// 发布内容，请求体中可指定 `publish_at` 预定发布
 pub async fn publish_content(
     Extension(manager): Extension<Manager>,
     Path((id,)): Path<(String,)>,
-    _session: SessionInfo,
+    session: SessionInfo,
+    client: ClientInfo,
+    payload: Result<Json<PublishContent>, JsonRejection>,
 ) -> InspirerResult<Json<()>> {
-    manager.publish_content(base62_to_uuid(&id)?).await?;
+    let payload = match payload {
+        Ok(Json(payload)) => payload,
+        // 未提交请求体时立即发布
+        Err(JsonRejection::MissingJsonContentType(_)) => PublishContent::default(),
+        Err(err) => return Err(InspirerError::InvalidRequest(err.to_string())),
+    };
+    let content = find_authorized_content(&manager, &session, &id, ContentAction::Publish).await?;
+
+    let mut log = audit_content(&session, AuditAction::ContentPublished, &content);
+    if let (Some(detail), Some(publish_at)) = (log.detail.as_mut(), payload.publish_at) {
+        detail["publish_at"] = serde_json::json!(publish_at);
+    }
+    let log = Some(client.audit_log(log));
+
+    match payload.publish_at {
+        Some(publish_at) => {
+            manager
+                .publish_content_at(content.meta.id, publish_at, log)
+                .await?
+        }
+        None => manager.publish_content(content.meta.id, log).await?,
+    }
 
     Ok(Json(()))
 }
@@ -161,9 +225,15 @@ pub async fn publish_content(
 pub async fn unpublish_content(
     Extension(manager): Extension<Manager>,
     Path((id,)): Path<(String,)>,
-    _session: SessionInfo,
+    session: SessionInfo,
+    client: ClientInfo,
 ) -> InspirerResult<Json<()>> {
-    manager.unpublish_content(base62_to_uuid(&id)?).await?;
+    let content = find_authorized_content(&manager, &session, &id, ContentAction::Publish).await?;
+
+    let log = audit_content(&session, AuditAction::ContentUnpublished, &content);
+    manager
+        .unpublish_content(content.meta.id, Some(client.audit_log(log)))
+        .await?;
 
     Ok(Json(()))
 }
@@ -172,10 +242,23 @@ pub async fn delete_content(
     Extension(manager): Extension<Manager>,
     Path((id,)): Path<(String,)>,
     Query(force_delete): Query<ForceDelete>,
-    _session: SessionInfo,
+    session: SessionInfo,
+    client: ClientInfo,
 ) -> InspirerResult<Json<()>> {
+    let (action, audit_action) = if force_delete.force_delete {
+        (ContentAction::ForceDelete, AuditAction::ContentForceDeleted)
+    } else {
+        (ContentAction::Delete, AuditAction::ContentDeleted)
+    };
+    let content = find_authorized_content(&manager, &session, &id, action).await?;
+
+    let log = audit_content(&session, audit_action, &content);
     manager
-        .delete_content(base62_to_uuid(&id)?, force_delete.force_delete)
+        .delete_content(
+            content.meta.id,
+            force_delete.force_delete,
+            Some(client.audit_log(log)),
+        )
         .await?;
 
     Ok(Json(()))
@@ -184,8 +267,211 @@ pub async fn delete_content(
 pub async fn revert_deleted_content(
     Extension(manager): Extension<Manager>,
     Path((id,)): Path<(String,)>,
+    session: SessionInfo,
+    client: ClientInfo,
+) -> InspirerResult<Json<()>> {
+    let content = find_authorized_content(&manager, &session, &id, ContentAction::Delete).await?;
+
+    let log = audit_content(&session, AuditAction::ContentReverted, &content);
+    manager
+        .revert_deleted_content(content.meta.id, Some(client.audit_log(log)))
+        .await?;
+
+    Ok(Json(()))
+}
+
+async fn set_content_display(
+    manager: &Manager,
+    session: &SessionInfo,
+    client: &ClientInfo,
+    id: &str,
+    display: bool,
 ) -> InspirerResult<Json<()>> {
-    manager.revert_deleted_content(base62_to_uuid(&id)?).await?;
+    let content = find_authorized_content(manager, session, id, ContentAction::Publish).await?;
+
+    let audit_action = if display {
+        AuditAction::ContentShown
+    } else {
+        AuditAction::ContentHidden
+    };
+    let log = audit_content(session, audit_action, &content);
+
+    manager
+        .set_display(content.meta.id, display, Some(client.audit_log(log)))
+        .await?;
+
+    Ok(Json(()))
+}
+
+pub async fn show_content(
+    Extension(manager): Extension<Manager>,
+    Path((id,)): Path<(String,)>,
+    session: SessionInfo,
+    client: ClientInfo,
+) -> InspirerResult<Json<()>> {
+    set_content_display(&manager, &session, &client, &id, true).await
+}
+
+/// 隐藏内容，隐藏的内容不会出现在公开列表及搜索结果中
+pub async fn hide_content(
+    Extension(manager): Extension<Manager>,
+    Path((id,)): Path<(String,)>,
+    session: SessionInfo,
+    client: ClientInfo,
+) -> InspirerResult<Json<()>> {
+    set_content_display(&manager, &session, &client, &id, false).await
+}
+
+/// 批量操作内容：逐条校验权限，通过校验的内容在同一事务中处理，按请求顺序返回每条内容的处理结果
+pub async fn bulk_update_contents(
+    Extension(manager): Extension<Manager>,
+    session: SessionInfo,
+    client: ClientInfo,
+    Json(payload): Json<BulkContent>,
+) -> InspirerResult<Json<Vec<BulkContentResult>>> {
+    if payload.ids.is_empty() || payload.ids.len() > MAX_BULK_CONTENTS {
+        return Err(InspirerError::InvalidRequest(format!(
+            "ids 数量必须在 1 到 {MAX_BULK_CONTENTS} 之间"
+        )));
+    }
+    session.authorize_scope(TokenScope::required_for(payload.action.content_action()))?;
+
+    let mut checked = Vec::with_capacity(payload.ids.len());
+    let mut contents = HashMap::new();
+    for id in payload.ids {
+        match find_authorized_content(&manager, &session, &id, payload.action.content_action())
+            .await
+        {
+            Ok(content) => {
+                checked.push((id, Ok(content.meta.id)));
+                contents.insert(content.meta.id, content);
+            }
+            Err(err) => checked.push((id, Err(err))),
+        }
+    }
+
+    let audit_action = match payload.action {
+        BulkContentAction::Publish => AuditAction::ContentPublished,
+        BulkContentAction::Unpublish => AuditAction::ContentUnpublished,
+        BulkContentAction::Hide => AuditAction::ContentHidden,
+        BulkContentAction::Show => AuditAction::ContentShown,
+        BulkContentAction::Delete => AuditAction::ContentDeleted,
+        BulkContentAction::Restore => AuditAction::ContentReverted,
+    };
+    let audits = contents
+        .iter()
+        .map(|(id, content)| {
+            let mut log = audit_content(&session, audit_action, content);
+            if let Some(detail) = log.detail.as_mut() {
+                detail["bulk"] = true.into();
+            }
+            (*id, client.audit_log(log))
+        })
+        .collect();
+
+    let results = manager
+        .bulk_update_contents(
+            payload.action,
+            checked
+                .iter()
+                .filter_map(|(_, checked)| checked.as_ref().ok().copied())
+                .collect(),
+            audits,
+        )
+        .await?;
+
+    let results = results.into_iter().collect::<HashMap<_, _>>();
+    Ok(Json(
+        checked
+            .into_iter()
+            .map(|(id, checked)| {
+                let result = checked.map_err(|err| err.to_string()).and_then(|uuid| {
+                    results[&uuid]
+                        .as_ref()
+                        .map(|_| ())
+                        .map_err(|err| err.to_string())
+                });
+                BulkContentResult::new(id, result)
+            })
+            .collect(),
+    ))
+}
+
+pub async fn get_content_revisions(
+    Extension(manager): Extension<Manager>,
+    Path((id,)): Path<(String,)>,
+    session: SessionInfo,
+) -> InspirerResult<Json<Vec<ContentRevision>>> {
+    let content = find_authorized_content(&manager, &session, &id, ContentAction::View).await?;
+
+    manager
+        .get_content_revisions(content.meta.id)
+        .await
+        .map(|revisions| revisions.into_iter().map(ContentRevision::from).collect())
+        .map(Json)
+        .map_err(Into::into)
+}
+
+/// 获取单条修订记录，以及该修订版本时的完整内容
+pub async fn get_content_revision(
+    Extension(manager): Extension<Manager>,
+    Path((id, revision_id)): Path<(String, String)>,
+    session: SessionInfo,
+) -> InspirerResult<Json<ContentRevisionWithSnapshot>> {
+    let id = find_authorized_content(&manager, &session, &id, ContentAction::View)
+        .await?
+        .meta
+        .id;
+    let revision_id = base62_to_uuid(&revision_id)?;
+
+    let revision = manager.find_content_revision(id, revision_id).await?;
+    let content = manager.get_content_snapshot(id, revision_id).await?;
+
+    Ok(Json(ContentRevisionWithSnapshot {
+        revision: revision.into(),
+        content,
+    }))
+}
+
+/// 对比修订版本，未指定 `from` 时与上一个版本对比
+pub async fn get_content_revision_diff(
+    Extension(manager): Extension<Manager>,
+    Path((id, revision_id)): Path<(String, String)>,
+    Query(query): Query<RevisionDiff>,
+    session: SessionInfo,
+) -> InspirerResult<Json<ContentDiff>> {
+    let content = find_authorized_content(&manager, &session, &id, ContentAction::View).await?;
+    let from = query.from.as_deref().map(base62_to_uuid).transpose()?;
+
+    manager
+        .diff_revisions(content.meta.id, from, base62_to_uuid(&revision_id)?)
+        .await
+        .map(Json)
+        .map_err(Into::into)
+}
+
+pub async fn restore_content_revision(
+    Extension(manager): Extension<Manager>,
+    Path((id, revision_id)): Path<(String, String)>,
+    session: SessionInfo,
+    client: ClientInfo,
+) -> InspirerResult<Json<()>> {
+    let content = find_authorized_content(&manager, &session, &id, ContentAction::Update).await?;
+
+    let mut log = audit_content(&session, AuditAction::ContentRevisionRestored, &content);
+    if let Some(detail) = log.detail.as_mut() {
+        detail["revision_id"] = revision_id.clone().into();
+    }
+
+    manager
+        .restore_revision(
+            session.uuid(),
+            content.meta.id,
+            base62_to_uuid(&revision_id)?,
+            session.can_create_terms(),
+            Some(client.audit_log(log)),
+        )
+        .await?;
 
     Ok(Json(()))
 }
@@ -0,0 +1,156 @@
+use axum::{http::header, response::IntoResponse, Extension, Json};
+use inspirer_content::{
+    manager::Manager,
+    model::{content::GetListCondition, paginate::Pagination},
+    service::content::ContentService,
+};
+
+use crate::{
+    error::InspirerResult,
+    response::feed::{atom_feed, rss_channel, FeedEntry, JsonFeed},
+    site::SITE,
+};
+
+/// 获取 Feed 条目，与公开内容列表使用相同的查询条件
+async fn get_feed_entries(manager: &Manager) -> InspirerResult<Vec<FeedEntry>> {
+    let list = manager
+        .get_list(
+            GetListCondition::published(),
+            Pagination {
+                page: 1,
+                page_size: SITE.feed_size,
+            },
+        )
+        .await?;
+
+    let mut entries = Vec::with_capacity(list.data.len());
+    for (content, owner) in list.data {
+        let html = if SITE.feed_full_content {
+            let full = manager.find_content_by_id(content.id).await?;
+            Some(manager.render_content(&full))
+        } else {
+            None
+        };
+
+        entries.push(FeedEntry {
+            content,
+            author: owner
+                .map(|owner| owner.nickname)
+                .filter(|nickname| !nickname.is_empty()),
+            html,
+        });
+    }
+
+    Ok(entries)
+}
+
+/// RSS 2.0
+pub async fn rss(Extension(manager): Extension<Manager>) -> InspirerResult<impl IntoResponse> {
+    let entries = get_feed_entries(&manager).await?;
+
+    Ok((
+        [(header::CONTENT_TYPE, "application/rss+xml; charset=utf-8")],
+        rss_channel(&SITE, &entries).to_string(),
+    ))
+}
+
+/// Atom
+pub async fn atom(Extension(manager): Extension<Manager>) -> InspirerResult<impl IntoResponse> {
+    let entries = get_feed_entries(&manager).await?;
+
+    Ok((
+        [(header::CONTENT_TYPE, "application/atom+xml; charset=utf-8")],
+        atom_feed(&SITE, &entries).to_string(),
+    ))
+}
+
+/// JSON Feed 1.1
+pub async fn json_feed(
+    Extension(manager): Extension<Manager>,
+) -> InspirerResult<impl IntoResponse> {
+    let entries = get_feed_entries(&manager).await?;
+
+    Ok((
+        [(header::CONTENT_TYPE, "application/feed+json; charset=utf-8")],
+        Json(JsonFeed::new(&SITE, entries)),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::{header, StatusCode};
+    use inspirer_content::{
+        manager::Manager,
+        model::content::{ContentEntity, ContentMeta, NewContent},
+        service::content::ContentService,
+        util::uuid::generate_v1_uuid,
+    };
+
+    use crate::{
+        manager::tests::sqlite_manager,
+        route::tests::{body_string, get},
+    };
+
+    async fn create_content(manager: &Manager, title: &str, publish: bool) {
+        let content = manager
+            .create_content(
+                generate_v1_uuid(),
+                NewContent {
+                    meta: ContentMeta {
+                        title: title.into(),
+                        description: format!("{title} & description"),
+                        ..Default::default()
+                    },
+                    entity: ContentEntity::Post(format!("{title} body")),
+                },
+                true,
+            )
+            .await
+            .unwrap();
+
+        if publish {
+            manager
+                .publish_content(content.meta.id, None)
+                .await
+                .unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_feeds() {
+        let manager = sqlite_manager().await;
+        create_content(&manager, "published", true).await;
+        create_content(&manager, "draft", false).await;
+
+        let response = get(&manager, "/feed.xml").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            "application/rss+xml; charset=utf-8"
+        );
+        let xml = body_string(response).await;
+        assert!(xml.contains("<item><title>published</title>"));
+        assert!(xml.contains("<description><![CDATA[published & description]]></description>"));
+        assert!(!xml.contains("draft"));
+
+        let response = get(&manager, "/atom.xml").await;
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            "application/atom+xml; charset=utf-8"
+        );
+        let xml = body_string(response).await;
+        assert_eq!(xml.matches("<entry>").count(), 1);
+        assert!(xml.contains("published"));
+        assert!(!xml.contains("draft"));
+
+        let response = get(&manager, "/feed.json").await;
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            "application/feed+json; charset=utf-8"
+        );
+        let feed: serde_json::Value = serde_json::from_str(&body_string(response).await).unwrap();
+        assert_eq!(feed["version"], "https://jsonfeed.org/version/1.1");
+        assert_eq!(feed["items"].as_array().unwrap().len(), 1);
+        assert_eq!(feed["items"][0]["title"], "published");
+    }
+}
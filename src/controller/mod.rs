@@ -1,2 +1,7 @@
+pub mod audit;
+pub mod auth;
 pub mod content;
-pub mod auth;
\ No newline at end of file
+pub mod feed;
+pub mod search;
+pub mod seo;
+pub mod taxonomy;
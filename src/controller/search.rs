@@ -0,0 +1,21 @@
+use axum::{extract::Query, Extension, Json};
+use inspirer_content::{
+    manager::Manager,
+    model::paginate::{Paginated, Pagination},
+    service::search::SearchService,
+};
+
+use crate::{error::InspirerResult, request::search::SearchQuery, response::search::SearchResult};
+
+pub async fn search(
+    Query(SearchQuery { q }): Query<SearchQuery>,
+    Query(pagination): Query<Pagination>,
+    Extension(manager): Extension<Manager>,
+) -> InspirerResult<Json<Paginated<SearchResult>>> {
+    manager
+        .search(q, pagination)
+        .await
+        .map(|res| res.map(|data| data.into_iter().map(SearchResult::from).collect()))
+        .map_err(Into::into)
+        .map(Json)
+}
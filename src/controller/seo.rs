@@ -0,0 +1,246 @@
+use axum::{extract::Path, http::header, response::IntoResponse, Extension};
+use inspirer_content::{
+    manager::Manager,
+    model::{
+        content::{GetListCondition, SortField},
+        paginate::{Paginated, Pagination},
+        Order,
+    },
+    service::content::ContentService,
+};
+
+use crate::{
+    error::{InspirerError, InspirerResult},
+    response::sitemap::{sitemap_index, url_set, SitemapUrl, SITEMAP_MAX_URLS},
+    site::SITE,
+};
+
+const XML_CONTENT_TYPE: (header::HeaderName, &str) =
+    (header::CONTENT_TYPE, "application/xml; charset=utf-8");
+
+/// 获取第 `page` 个 sitemap 中的内容，包含所有已发布且未隐藏的内容（含页面）
+async fn get_sitemap_urls(
+    manager: &Manager,
+    page: usize,
+    max_urls: usize,
+) -> InspirerResult<Paginated<SitemapUrl>> {
+    let list = manager
+        .get_list(
+            GetListCondition {
+                sort: vec![Order::Asc(SortField::CreatedAt)],
+                ..Default::default()
+            },
+            Pagination {
+                page,
+                page_size: max_urls,
+            },
+        )
+        .await?;
+
+    Ok(list.map(|data| {
+        data.into_iter()
+            .map(|(content, _)| SitemapUrl {
+                loc: SITE.content_url(&content),
+                lastmod: content.modified_at,
+            })
+            .collect()
+    }))
+}
+
+/// 生成 sitemap，内容数量超过 `max_urls` 时生成 sitemap 索引
+async fn sitemap_xml(manager: &Manager, max_urls: usize) -> InspirerResult<String> {
+    // 仅统计数量并取最近修改的一条内容，避免为计算页数加载全部内容
+    let latest = manager
+        .get_list(
+            GetListCondition {
+                sort: vec![Order::Desc(SortField::ModifiedAt)],
+                ..Default::default()
+            },
+            Pagination {
+                page: 1,
+                page_size: 1,
+            },
+        )
+        .await?;
+
+    if latest.total <= max_urls {
+        return Ok(url_set(&get_sitemap_urls(manager, 1, max_urls).await?.data));
+    }
+
+    let lastmod = latest
+        .data
+        .first()
+        .map(|(content, _)| content.modified_at)
+        .unwrap_or_else(chrono::Utc::now);
+    let sitemaps = (1..=latest.total.div_ceil(max_urls))
+        .map(|page| SitemapUrl {
+            loc: SITE.url_for(&format!("sitemap/{}.xml", page)),
+            lastmod,
+        })
+        .collect::<Vec<_>>();
+
+    Ok(sitemap_index(&sitemaps))
+}
+
+/// 生成 sitemap 索引中的第 `page` 个 sitemap
+async fn sitemap_page_xml(
+    manager: &Manager,
+    page: usize,
+    max_urls: usize,
+) -> InspirerResult<String> {
+    let urls = get_sitemap_urls(manager, page, max_urls).await?;
+    if urls.data.is_empty() {
+        return Err(InspirerError::NotFound);
+    }
+
+    Ok(url_set(&urls.data))
+}
+
+/// 内容数量超过单个 sitemap 上限时返回 sitemap 索引
+pub async fn sitemap(Extension(manager): Extension<Manager>) -> InspirerResult<impl IntoResponse> {
+    Ok((
+        [XML_CONTENT_TYPE],
+        sitemap_xml(&manager, SITEMAP_MAX_URLS).await?,
+    ))
+}
+
+/// sitemap 索引中的分页 sitemap，路径形如 `/sitemap/1.xml`
+pub async fn sitemap_page(
+    Path((file,)): Path<(String,)>,
+    Extension(manager): Extension<Manager>,
+) -> InspirerResult<impl IntoResponse> {
+    let page = file
+        .strip_suffix(".xml")
+        .and_then(|page| page.parse::<usize>().ok())
+        .filter(|page| *page > 0)
+        .ok_or(InspirerError::NotFound)?;
+
+    Ok((
+        [XML_CONTENT_TYPE],
+        sitemap_page_xml(&manager, page, SITEMAP_MAX_URLS).await?,
+    ))
+}
+
+pub async fn robots() -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/plain; charset=utf-8")],
+        format!(
+            "User-agent: *\nDisallow: /security/\n\nSitemap: {}\n",
+            SITE.url_for("sitemap.xml")
+        ),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::StatusCode;
+    use inspirer_content::{
+        model::content::{ContentEntity, ContentMeta, NewContent},
+        util::uuid::generate_v1_uuid,
+    };
+
+    use super::*;
+    use crate::{
+        manager::tests::sqlite_manager,
+        route::tests::{body_string, get},
+    };
+
+    /// 创建 `count` 篇已发布内容及一篇隐藏内容，返回已发布内容的名称
+    async fn create_contents(manager: &Manager, count: usize) -> Vec<String> {
+        let mut names = vec![];
+        for index in 0..=count {
+            let name = format!("post-{index}");
+            let content = manager
+                .create_content(
+                    generate_v1_uuid(),
+                    NewContent {
+                        meta: ContentMeta {
+                            title: name.clone(),
+                            name: Some(name.clone()),
+                            ..Default::default()
+                        },
+                        entity: ContentEntity::Post(String::new()),
+                    },
+                    true,
+                )
+                .await
+                .unwrap();
+            manager
+                .publish_content(content.meta.id, None)
+                .await
+                .unwrap();
+
+            if index == count {
+                manager
+                    .set_display(content.meta.id, false, None)
+                    .await
+                    .unwrap();
+            } else {
+                names.push(name);
+            }
+        }
+
+        names
+    }
+
+    #[tokio::test]
+    async fn test_sitemap() {
+        let manager = sqlite_manager().await;
+        let names = create_contents(&manager, 2).await;
+
+        let response = get(&manager, "/sitemap.xml").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[axum::http::header::CONTENT_TYPE],
+            XML_CONTENT_TYPE.1
+        );
+        let xml = body_string(response).await;
+        assert!(xml.starts_with(r#"<?xml version="1.0" encoding="UTF-8"?><urlset "#));
+        assert_eq!(xml.matches("<url>").count(), 2);
+        for name in names {
+            assert!(xml.contains(&format!(
+                "<loc>{}</loc>",
+                SITE.url_for(&format!("content/{name}"))
+            )));
+        }
+        assert!(!xml.contains("post-2"));
+
+        let response = get(&manager, "/sitemap/2.xml").await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let response = get(&manager, "/sitemap/first.xml").await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_sitemap_index() {
+        let manager = sqlite_manager().await;
+        let names = create_contents(&manager, 3).await;
+
+        let xml = sitemap_xml(&manager, 2).await.unwrap();
+        assert!(xml.contains("<sitemapindex "));
+        assert_eq!(xml.matches("<sitemap>").count(), 2);
+        assert!(xml.contains(&format!("<loc>{}</loc>", SITE.url_for("sitemap/2.xml"))));
+
+        let xml = sitemap_page_xml(&manager, 1, 2).await.unwrap();
+        assert_eq!(xml.matches("<url>").count(), 2);
+        assert!(xml.contains(&names[0]) && xml.contains(&names[1]));
+
+        let xml = sitemap_page_xml(&manager, 2, 2).await.unwrap();
+        assert_eq!(xml.matches("<url>").count(), 1);
+        assert!(xml.contains(&names[2]));
+
+        assert!(matches!(
+            sitemap_page_xml(&manager, 3, 2).await,
+            Err(InspirerError::NotFound)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_robots() {
+        let manager = sqlite_manager().await;
+
+        let text = body_string(get(&manager, "/robots.txt").await).await;
+        assert!(text.contains("Disallow: /security/"));
+        assert!(text.contains(&format!("Sitemap: {}", SITE.url_for("sitemap.xml"))));
+    }
+}
@@ -0,0 +1,155 @@
+use crate::{
+    error::InspirerResult,
+    request::taxonomy::{CreateCategory, CreateTag, UpdateCategory},
+    response::{
+        content::ContentBase,
+        taxonomy::{Category, Tag},
+    },
+    session::SessionInfo,
+};
+use axum::{
+    extract::{Path, Query},
+    Extension, Json,
+};
+use inspirer_content::{
+    manager::Manager,
+    model::{
+        content::GetListCondition,
+        paginate::{Paginated, Pagination},
+    },
+    service::{content::ContentService, taxonomy::TaxonomyService},
+};
+
+pub async fn get_tags(Extension(manager): Extension<Manager>) -> InspirerResult<Json<Vec<Tag>>> {
+    manager
+        .get_tags()
+        .await
+        .map(|tags| tags.into_iter().map(Tag::from).collect())
+        .map(Json)
+        .map_err(Into::into)
+}
+
+/// 获取包含指定标签的已发布内容
+pub async fn get_tag_content_list(
+    Path((name,)): Path<(String,)>,
+    Query(pagination): Query<Pagination>,
+    Extension(manager): Extension<Manager>,
+) -> InspirerResult<Json<Paginated<ContentBase>>> {
+    let tag = manager.find_tag_by_name(name).await?;
+
+    manager
+        .get_list(
+            GetListCondition {
+                tag: Some(tag.name),
+                ..GetListCondition::published()
+            },
+            pagination,
+        )
+        .await
+        .map(|res| res.map(|data| data.into_iter().map(ContentBase::from).collect()))
+        .map_err(Into::into)
+        .map(Json)
+}
+
+pub async fn create_tag(
+    Extension(manager): Extension<Manager>,
+    session: SessionInfo,
+    Json(payload): Json<CreateTag>,
+) -> InspirerResult<Json<Tag>> {
+    session.authorize_taxonomy()?;
+
+    manager
+        .create_tag(payload.name)
+        .await
+        .map(Tag::from)
+        .map(Json)
+        .map_err(Into::into)
+}
+
+pub async fn delete_tag(
+    Extension(manager): Extension<Manager>,
+    Path((name,)): Path<(String,)>,
+    session: SessionInfo,
+) -> InspirerResult<Json<()>> {
+    session.authorize_taxonomy()?;
+
+    manager.delete_tag(name).await?;
+
+    Ok(Json(()))
+}
+
+pub async fn get_categories(
+    Extension(manager): Extension<Manager>,
+) -> InspirerResult<Json<Vec<Category>>> {
+    manager
+        .get_categories()
+        .await
+        .map(|categories| categories.into_iter().map(Category::from).collect())
+        .map(Json)
+        .map_err(Into::into)
+}
+
+/// 获取属于指定分类的已发布内容
+pub async fn get_category_content_list(
+    Path((name,)): Path<(String,)>,
+    Query(pagination): Query<Pagination>,
+    Extension(manager): Extension<Manager>,
+) -> InspirerResult<Json<Paginated<ContentBase>>> {
+    let category = manager.find_category_by_name(name).await?;
+
+    manager
+        .get_list(
+            GetListCondition {
+                category: Some(category.name),
+                ..GetListCondition::published()
+            },
+            pagination,
+        )
+        .await
+        .map(|res| res.map(|data| data.into_iter().map(ContentBase::from).collect()))
+        .map_err(Into::into)
+        .map(Json)
+}
+
+pub async fn create_category(
+    Extension(manager): Extension<Manager>,
+    session: SessionInfo,
+    Json(payload): Json<CreateCategory>,
+) -> InspirerResult<Json<Category>> {
+    session.authorize_taxonomy()?;
+
+    manager
+        .create_category(payload)
+        .await
+        .map(Category::from)
+        .map(Json)
+        .map_err(Into::into)
+}
+
+pub async fn update_category(
+    Extension(manager): Extension<Manager>,
+    Path((name,)): Path<(String,)>,
+    session: SessionInfo,
+    Json(payload): Json<UpdateCategory>,
+) -> InspirerResult<Json<Category>> {
+    session.authorize_taxonomy()?;
+
+    manager
+        .update_category(name, payload)
+        .await
+        .map(Category::from)
+        .map(Json)
+        .map_err(Into::into)
+}
+
+pub async fn delete_category(
+    Extension(manager): Extension<Manager>,
+    Path((name,)): Path<(String,)>,
+    session: SessionInfo,
+) -> InspirerResult<Json<()>> {
+    session.authorize_taxonomy()?;
+
+    manager.delete_category(name).await?;
+
+    Ok(Json(()))
+}
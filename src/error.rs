@@ -1,4 +1,8 @@
-use axum::{response::IntoResponse, http::StatusCode, Json};
+use axum::{
+    http::{header, StatusCode},
+    response::IntoResponse,
+    Json,
+};
 use inspirer_content::error::Error as InspirerContentError;
 
 use crate::response::error_message_from_err;
@@ -15,19 +19,85 @@ pub enum InspirerError {
     CreateTokenError,
     #[error("Token 解析失败")]
     ParseTokenError,
+    #[error("请求参数错误：{0}")]
+    InvalidRequest(String),
+    #[error("资源不存在")]
+    NotFound,
+    #[error("没有权限执行该操作")]
+    Forbidden,
+    #[error("请求体过大，不能超过 {0} 字节")]
+    PayloadTooLarge(usize),
+    #[error("服务未正确初始化")]
+    ManagerNotRegistered,
 }
 
 impl IntoResponse for InspirerError {
     fn into_response(self) -> axum::response::Response {
         let msg = error_message_from_err(&self);
+        let retry_after = match self {
+            InspirerError::InspirerContentError(InspirerContentError::TooManyLoginAttempts {
+                retry_after,
+            }) => Some(retry_after),
+            _ => None,
+        };
         let status = match self {
-            InspirerError::InspirerContentError(InspirerContentError::ContentNotFound) => StatusCode::NOT_FOUND,
-            InspirerError::InspirerContentError(InspirerContentError::DatabaseWriteConflict) => StatusCode::CONFLICT,
-            InspirerError::InspirerContentError(InspirerContentError::UserNotFoundOrPasswordError) | InspirerError::ParseTokenError => StatusCode::FORBIDDEN,
-            InspirerError::Unauthorized => StatusCode::UNAUTHORIZED,
+            InspirerError::InspirerContentError(
+                InspirerContentError::ContentNotFound
+                | InspirerContentError::ContentRevisionNotFound
+                | InspirerContentError::TagNotFound
+                | InspirerContentError::CategoryNotFound
+                | InspirerContentError::SessionNotFound
+                | InspirerContentError::AccessTokenNotFound
+                | InspirerContentError::UserNotFound,
+            ) => StatusCode::NOT_FOUND,
+            InspirerError::InspirerContentError(
+                InspirerContentError::EmptyTermName
+                | InspirerContentError::InvalidPublicKey
+                | InspirerContentError::InvalidPassword(_)
+                | InspirerContentError::InvalidProfile(_)
+                | InspirerContentError::InvalidAccessTokenOptions(_)
+                | InspirerContentError::TwoFactorAlreadyEnabled
+                | InspirerContentError::TwoFactorNotEnabled,
+            ) => StatusCode::BAD_REQUEST,
+            InspirerError::InspirerContentError(
+                InspirerContentError::DatabaseWriteConflict
+                | InspirerContentError::ContentAlreadyPublished,
+            ) => StatusCode::CONFLICT,
+            InspirerError::InspirerContentError(
+                InspirerContentError::CreateTermForbidden(_)
+                | InspirerContentError::UserNotFoundOrPasswordError
+                | InspirerContentError::PasswordLoginDisabled
+                | InspirerContentError::UserDisabled
+                | InspirerContentError::IncorrectPassword
+                | InspirerContentError::InvalidTwoFactorCode,
+            )
+            | InspirerError::ParseTokenError => StatusCode::FORBIDDEN,
+            InspirerError::InvalidRequest(_) => StatusCode::BAD_REQUEST,
+            InspirerError::NotFound => StatusCode::NOT_FOUND,
+            InspirerError::Forbidden => StatusCode::FORBIDDEN,
+            InspirerError::PayloadTooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+            InspirerError::InspirerContentError(InspirerContentError::TooManyLoginAttempts {
+                ..
+            }) => StatusCode::TOO_MANY_REQUESTS,
+            InspirerError::Unauthorized
+            | InspirerError::InspirerContentError(
+                InspirerContentError::InvalidSession
+                | InspirerContentError::InvalidAccessToken
+                | InspirerContentError::InvalidSignature
+                | InspirerContentError::SignatureExpired
+                | InspirerContentError::ReplayedRequest,
+            ) => StatusCode::UNAUTHORIZED,
             _ => StatusCode::INTERNAL_SERVER_ERROR,
         };
 
-        (status, Json(msg)).into_response()
+        match retry_after {
+            Some(retry_after) => (
+                status,
+                [(header::RETRY_AFTER, retry_after.to_string())],
+                Json(msg),
+            )
+                .into_response(),
+            None => (status, Json(msg)).into_response(),
+        }
     }
 }
\ No newline at end of file
@@ -0,0 +1,431 @@
+//! 访问令牌签名密钥
+//!
+//! 使用 `JWT_SIGNING_KEY` 指定的私钥签名，令牌头部携带密钥 ID（`kid`）。轮换后原密钥可加入
+//! `JWT_VERIFICATION_KEYS` 继续用于校验，全部校验密钥以 JWK Set 形式公开，便于其他服务校验令牌。
+use std::path::Path;
+
+use anyhow::{anyhow, bail, Context, Result};
+use base64ct::{Base64UrlUnpadded, Encoding};
+use inspirer_content::util::signature::ED25519_SPKI_PREFIX;
+use jsonwebtoken::{
+    decode, decode_header, encode,
+    errors::{Error as TokenError, ErrorKind as TokenErrorKind},
+    Algorithm, DecodingKey, EncodingKey, Header, Validation,
+};
+use lazy_static::lazy_static;
+use ring::{
+    digest,
+    signature::{EcdsaKeyPair, Ed25519KeyPair, KeyPair, ECDSA_P256_SHA256_FIXED_SIGNING},
+};
+use serde::{de::DeserializeOwned, Serialize};
+
+/// P-256 公钥 SubjectPublicKeyInfo 结构的 DER 前缀，其后为 65 字节未压缩格式公钥
+const P256_SPKI_PREFIX: [u8; 26] = [
+    0x30, 0x59, 0x30, 0x13, 0x06, 0x07, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02, 0x01, 0x06, 0x08, 0x2a,
+    0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07, 0x03, 0x42, 0x00,
+];
+const P256_PUBLIC_KEY_LEN: usize = 65;
+
+#[cfg(not(test))]
+lazy_static! {
+    pub static ref TOKEN_KEYS: TokenKeys =
+        TokenKeys::from_env().unwrap_or_else(|err| panic!("加载令牌签名密钥失败：{err:#}"));
+}
+
+#[cfg(test)]
+lazy_static! {
+    /// 测试时使用临时生成的签名密钥
+    pub static ref TOKEN_KEYS: TokenKeys = TokenKeys::new(tests::generate_key(), vec![]).unwrap();
+}
+
+/// 校验令牌使用的公钥
+pub struct VerificationKey {
+    /// 密钥 ID，即 JWK 指纹（RFC 7638）
+    pub kid: String,
+    pub algorithm: Algorithm,
+    public_key: Vec<u8>,
+    decoding_key: DecodingKey,
+}
+
+impl VerificationKey {
+    fn new(algorithm: Algorithm, public_key: Vec<u8>) -> Self {
+        let decoding_key = match algorithm {
+            Algorithm::EdDSA => DecodingKey::from_ed_der(&public_key),
+            _ => DecodingKey::from_ec_der(&public_key),
+        };
+
+        let mut key = VerificationKey {
+            kid: String::new(),
+            algorithm,
+            public_key,
+            decoding_key,
+        };
+        key.kid = key.thumbprint();
+
+        key
+    }
+
+    /// 公开的 JWK，不包含 `kid` 以外的私有信息
+    pub fn to_jwk(&self) -> Jwk {
+        let (kty, crv, x, y) = self.key_parameters();
+
+        Jwk {
+            kty,
+            crv,
+            x,
+            y,
+            kid: self.kid.clone(),
+            alg: match self.algorithm {
+                Algorithm::EdDSA => "EdDSA",
+                _ => "ES256",
+            },
+            key_use: "sig",
+        }
+    }
+
+    fn key_parameters(&self) -> (&'static str, &'static str, String, Option<String>) {
+        match self.algorithm {
+            Algorithm::EdDSA => (
+                "OKP",
+                "Ed25519",
+                Base64UrlUnpadded::encode_string(&self.public_key),
+                None,
+            ),
+            // 未压缩格式：0x04 || x || y
+            _ => (
+                "EC",
+                "P-256",
+                Base64UrlUnpadded::encode_string(&self.public_key[1..33]),
+                Some(Base64UrlUnpadded::encode_string(&self.public_key[33..])),
+            ),
+        }
+    }
+
+    /// JWK 指纹：按字典序排列必需成员后的 JSON 的 SHA-256 摘要
+    fn thumbprint(&self) -> String {
+        let members = match self.key_parameters() {
+            (kty, crv, x, None) => format!(r#"{{"crv":"{crv}","kty":"{kty}","x":"{x}"}}"#),
+            (kty, crv, x, Some(y)) => {
+                format!(r#"{{"crv":"{crv}","kty":"{kty}","x":"{x}","y":"{y}"}}"#)
+            }
+        };
+
+        Base64UrlUnpadded::encode_string(
+            digest::digest(&digest::SHA256, members.as_bytes()).as_ref(),
+        )
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct Jwk {
+    pub kty: &'static str,
+    pub crv: &'static str,
+    pub x: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub y: Option<String>,
+    pub kid: String,
+    pub alg: &'static str,
+    #[serde(rename = "use")]
+    pub key_use: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+pub struct JwkSet {
+    pub keys: Vec<Jwk>,
+}
+
+/// 从 PEM 文件读取的密钥
+pub struct KeyMaterial {
+    algorithm: Algorithm,
+    /// PKCS#8 格式的私钥，公钥文件为空
+    private_key: Option<Vec<u8>>,
+    public_key: Vec<u8>,
+}
+
+impl KeyMaterial {
+    /// 读取 PKCS#8 私钥（`BEGIN PRIVATE KEY`）或公钥（`BEGIN PUBLIC KEY`），支持 Ed25519 及 P-256
+    pub fn from_pem(text: &str) -> Result<Self> {
+        let (label, document) =
+            der::Document::from_pem(text).map_err(|err| anyhow!("PEM 格式错误：{err}"))?;
+        let der = document.as_bytes();
+
+        match label {
+            "PRIVATE KEY" => {
+                if let Ok(key_pair) = Ed25519KeyPair::from_pkcs8_maybe_unchecked(der) {
+                    return Ok(KeyMaterial {
+                        algorithm: Algorithm::EdDSA,
+                        private_key: Some(der.to_vec()),
+                        public_key: key_pair.public_key().as_ref().to_vec(),
+                    });
+                }
+
+                if let Ok(key_pair) =
+                    EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, der)
+                {
+                    return Ok(KeyMaterial {
+                        algorithm: Algorithm::ES256,
+                        private_key: Some(der.to_vec()),
+                        public_key: key_pair.public_key().as_ref().to_vec(),
+                    });
+                }
+
+                bail!("仅支持 PKCS#8 格式的 Ed25519 或 P-256 私钥")
+            }
+            "PUBLIC KEY" => {
+                if let Some(public_key) = der.strip_prefix(&ED25519_SPKI_PREFIX) {
+                    return Ok(KeyMaterial {
+                        algorithm: Algorithm::EdDSA,
+                        private_key: None,
+                        public_key: public_key.to_vec(),
+                    });
+                }
+
+                match der.strip_prefix(&P256_SPKI_PREFIX) {
+                    Some(public_key) if public_key.len() == P256_PUBLIC_KEY_LEN => {
+                        Ok(KeyMaterial {
+                            algorithm: Algorithm::ES256,
+                            private_key: None,
+                            public_key: public_key.to_vec(),
+                        })
+                    }
+                    _ => bail!("仅支持 Ed25519 或 P-256 公钥"),
+                }
+            }
+            _ => bail!("不支持的 PEM 类型：{label}"),
+        }
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("读取密钥文件 {} 失败", path.display()))?;
+
+        Self::from_pem(&text).with_context(|| format!("解析密钥文件 {} 失败", path.display()))
+    }
+
+    pub fn verification_key(&self) -> VerificationKey {
+        VerificationKey::new(self.algorithm, self.public_key.clone())
+    }
+}
+
+/// 令牌签名密钥及全部有效的校验密钥
+pub struct TokenKeys {
+    encoding_key: EncodingKey,
+    /// 第一个为签名密钥对应的公钥
+    verification_keys: Vec<VerificationKey>,
+}
+
+impl TokenKeys {
+    pub fn new(signing_key: KeyMaterial, retired_keys: Vec<KeyMaterial>) -> Result<Self> {
+        let private_key = signing_key
+            .private_key
+            .as_deref()
+            .ok_or_else(|| anyhow!("签名密钥必须为私钥"))?;
+        let encoding_key = match signing_key.algorithm {
+            Algorithm::EdDSA => EncodingKey::from_ed_der(private_key),
+            _ => EncodingKey::from_ec_der(private_key),
+        };
+
+        let mut verification_keys: Vec<VerificationKey> = vec![signing_key.verification_key()];
+        for key in retired_keys {
+            let key = key.verification_key();
+            if verification_keys.iter().all(|exists| exists.kid != key.kid) {
+                verification_keys.push(key);
+            }
+        }
+
+        Ok(TokenKeys {
+            encoding_key,
+            verification_keys,
+        })
+    }
+
+    /// 读取 `JWT_SIGNING_KEY` 及 `JWT_VERIFICATION_KEYS`（以逗号分隔的文件路径）
+    #[cfg_attr(test, allow(dead_code))]
+    pub fn from_env() -> Result<Self> {
+        let signing_key = std::env::var("JWT_SIGNING_KEY")
+            .ok()
+            .filter(|path| !path.is_empty())
+            .ok_or_else(|| anyhow!("未配置 JWT_SIGNING_KEY"))?;
+        let signing_key = KeyMaterial::from_file(Path::new(&signing_key))?;
+
+        let retired_keys = std::env::var("JWT_VERIFICATION_KEYS")
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|path| !path.is_empty())
+            .map(|path| KeyMaterial::from_file(Path::new(path)))
+            .collect::<Result<Vec<_>>>()?;
+
+        Self::new(signing_key, retired_keys)
+    }
+
+    pub fn signing_key(&self) -> &VerificationKey {
+        &self.verification_keys[0]
+    }
+
+    pub fn verification_keys(&self) -> &[VerificationKey] {
+        &self.verification_keys
+    }
+
+    pub fn encode<T: Serialize>(&self, claims: &T) -> Result<String, TokenError> {
+        let signing_key = self.signing_key();
+        let mut header = Header::new(signing_key.algorithm);
+        header.kid = Some(signing_key.kid.clone());
+
+        encode(&header, claims, &self.encoding_key)
+    }
+
+    /// 按令牌头部的 `kid` 选择校验密钥，`validation` 中的算法被替换为该密钥的算法
+    pub fn decode<T: DeserializeOwned>(
+        &self,
+        token: &str,
+        mut validation: Validation,
+    ) -> Result<T, TokenError> {
+        let header = decode_header(token)?;
+        let key = header
+            .kid
+            .and_then(|kid| self.verification_keys.iter().find(|key| key.kid == kid))
+            .ok_or_else(|| TokenError::from(TokenErrorKind::InvalidToken))?;
+
+        validation.algorithms = vec![key.algorithm];
+        decode::<T>(token, &key.decoding_key, &validation).map(|token| token.claims)
+    }
+
+    pub fn jwks(&self) -> JwkSet {
+        JwkSet {
+            keys: self
+                .verification_keys
+                .iter()
+                .map(VerificationKey::to_jwk)
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use der::pem::LineEnding;
+    use inspirer_content::util::signature::private_key_to_pem;
+    use jsonwebtoken::get_current_timestamp;
+    use ring::{rand::SystemRandom, signature::Ed25519KeyPair};
+    use serde::Deserialize;
+
+    use super::*;
+
+    pub fn generate_key() -> KeyMaterial {
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).unwrap();
+        KeyMaterial::from_pem(&private_key_to_pem(pkcs8.as_ref()).unwrap()).unwrap()
+    }
+
+    fn generate_p256_key() -> KeyMaterial {
+        let pkcs8 =
+            EcdsaKeyPair::generate_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, &SystemRandom::new())
+                .unwrap();
+        KeyMaterial::from_pem(&private_key_to_pem(pkcs8.as_ref()).unwrap()).unwrap()
+    }
+
+    /// 只包含公钥的密钥，即轮换后仅用于校验的密钥
+    fn public_key_of(key: &KeyMaterial) -> KeyMaterial {
+        let prefix: &[u8] = match key.algorithm {
+            Algorithm::EdDSA => &ED25519_SPKI_PREFIX,
+            _ => &P256_SPKI_PREFIX,
+        };
+        let spki = [prefix, key.public_key.as_slice()].concat();
+        let pem = der::Document::try_from(spki.as_slice())
+            .unwrap()
+            .to_pem("PUBLIC KEY", LineEnding::LF)
+            .unwrap();
+
+        KeyMaterial::from_pem(&pem).unwrap()
+    }
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Claims {
+        sub: String,
+        exp: u64,
+    }
+
+    fn claims() -> Claims {
+        Claims {
+            sub: "chongyi".into(),
+            exp: get_current_timestamp() + 60,
+        }
+    }
+
+    #[test]
+    fn test_thumbprint() {
+        // RFC 8037 附录 A.3
+        let x =
+            Base64UrlUnpadded::decode_vec("11qYAYKxCrfVS_7TyWQHOg7hcvPapiMlrwIaaPcHURo").unwrap();
+        let key = VerificationKey::new(Algorithm::EdDSA, x);
+        assert_eq!(key.kid, "kPrK_qmxVWaYVA9wwBF6Iuo3vVzz7TxHCTwXBygrS4k");
+
+        // RFC 7517 附录 A.1 中的 P-256 公钥，按 RFC 7638 计算
+        let x =
+            Base64UrlUnpadded::decode_vec("MKBCTNIcKUSDii11ySs3526iDZ8AiTo7Tu6KPAqv7D4").unwrap();
+        let y =
+            Base64UrlUnpadded::decode_vec("4Etl6SRW2YiLUrN5vfvVHuhp7x8PxltmWWlbbM4IFyM").unwrap();
+        let key = VerificationKey::new(Algorithm::ES256, [&[0x04], &x[..], &y[..]].concat());
+        assert_eq!(key.kid, "cn-I_WNMClehiVp51i_0VpOENW1upEerA8sEam5hn-s");
+
+        let jwk = serde_json::to_value(key.to_jwk()).unwrap();
+        assert_eq!(
+            jwk,
+            serde_json::json!({
+                "kty": "EC",
+                "crv": "P-256",
+                "x": "MKBCTNIcKUSDii11ySs3526iDZ8AiTo7Tu6KPAqv7D4",
+                "y": "4Etl6SRW2YiLUrN5vfvVHuhp7x8PxltmWWlbbM4IFyM",
+                "kid": "cn-I_WNMClehiVp51i_0VpOENW1upEerA8sEam5hn-s",
+                "alg": "ES256",
+                "use": "sig",
+            })
+        );
+    }
+
+    #[test]
+    fn test_encode_decode() {
+        for key in [generate_key(), generate_p256_key()] {
+            let public_key = public_key_of(&key);
+            assert!(public_key.private_key.is_none());
+            assert_eq!(
+                public_key.verification_key().kid,
+                key.verification_key().kid
+            );
+
+            let keys = TokenKeys::new(key, vec![]).unwrap();
+            let token = keys.encode(&claims()).unwrap();
+            let header = decode_header(&token).unwrap();
+            assert_eq!(header.kid.as_deref(), Some(keys.signing_key().kid.as_str()));
+            assert_eq!(
+                keys.decode::<Claims>(&token, Validation::default())
+                    .unwrap(),
+                claims()
+            );
+        }
+    }
+
+    #[test]
+    fn test_key_rotation() {
+        let old_key = generate_key();
+        let retired = public_key_of(&old_key);
+        let old_keys = TokenKeys::new(old_key, vec![]).unwrap();
+        let token = old_keys.encode(&claims()).unwrap();
+
+        // 公钥不能作为签名密钥
+        assert!(TokenKeys::new(public_key_of(&generate_key()), vec![]).is_err());
+
+        // 轮换后原密钥签发的令牌仍可校验，重复的校验密钥被忽略
+        let retired_again = public_key_of(&retired);
+        let keys = TokenKeys::new(generate_p256_key(), vec![retired, retired_again]).unwrap();
+        assert_eq!(keys.verification_keys().len(), 2);
+        assert_eq!(keys.jwks().keys.len(), 2);
+        assert!(keys.decode::<Claims>(&token, Validation::default()).is_ok());
+
+        // 移除原密钥后不再接受
+        let keys = TokenKeys::new(generate_key(), vec![]).unwrap();
+        assert!(keys
+            .decode::<Claims>(&token, Validation::default())
+            .is_err());
+    }
+}
@@ -1,14 +1,17 @@
 use clap::Parser;
 
+mod audit;
 mod cli;
 mod controller;
 mod error;
+mod jwk;
 mod request;
 mod response;
 mod route;
 mod server;
 mod middleware;
 mod session;
+mod site;
 mod manager;
 
 fn main() {
@@ -1,13 +1,122 @@
 use anyhow::Result;
-use inspirer_content::manager::{Manager, ManagerConfigBuilder};
+use inspirer_content::{
+    manager::{Manager, ManagerConfigBuilder},
+    model::user::PasswordPolicy,
+    throttle::LoginThrottlePolicy,
+};
 
 pub async fn create_manager() -> Result<Manager> {
-    let manager = Manager::create_from_config(
-        ManagerConfigBuilder::default()
-            .database_url(std::env::var("DATABASE_URL").expect("未找到数据库配置"))
-            .build()?,
-    )
-    .await?;
+    let mut config = ManagerConfigBuilder::default();
+    config
+        .database_url(std::env::var("DATABASE_URL").expect("未找到数据库配置"))
+        .data_dir(std::env::var("DATA_DIR").unwrap_or_else(|_| "data".into()))
+        .disable_admin_password_login(
+            std::env::var("DISABLE_ADMIN_PASSWORD_LOGIN")
+                .map(|v| {
+                    v.parse::<bool>()
+                        .expect("DISABLE_ADMIN_PASSWORD_LOGIN 参数格式化错误")
+                })
+                .unwrap_or_default(),
+        )
+        .password_policy(password_policy())
+        .login_throttle_policy(login_throttle_policy())
+        .persist_login_attempts(persist_login_attempts());
+
+    // 仅两步验证需要加密密钥，未配置时其他功能不受影响
+    if let Some(key) = encryption_key() {
+        config.secret_key(key);
+    }
+
+    let manager = Manager::create_from_config(config.build()?).await?;
 
     Ok(manager)
-}
\ No newline at end of file
+}
+
+/// 读取密码策略配置，未配置的项使用默认值
+fn password_policy() -> PasswordPolicy {
+    let default = PasswordPolicy::default();
+
+    PasswordPolicy {
+        min_length: std::env::var("PASSWORD_MIN_LENGTH")
+            .map(|v| {
+                v.parse::<usize>()
+                    .expect("PASSWORD_MIN_LENGTH 参数格式化错误")
+            })
+            .unwrap_or(default.min_length),
+        min_character_classes: std::env::var("PASSWORD_MIN_CHARACTER_CLASSES")
+            .map(|v| {
+                v.parse::<usize>()
+                    .ok()
+                    .filter(|classes| (1..=4).contains(classes))
+                    .expect("PASSWORD_MIN_CHARACTER_CLASSES 参数格式化错误，取值为 1 至 4")
+            })
+            .unwrap_or(default.min_character_classes),
+    }
+}
+
+/// 读取登录限流配置，未配置的项使用默认值
+fn login_throttle_policy() -> LoginThrottlePolicy {
+    let default = LoginThrottlePolicy::default();
+
+    LoginThrottlePolicy {
+        max_username_failures: std::env::var("LOGIN_MAX_USERNAME_FAILURES")
+            .map(|v| {
+                v.parse::<u32>()
+                    .expect("LOGIN_MAX_USERNAME_FAILURES 参数格式化错误")
+            })
+            .unwrap_or(default.max_username_failures),
+        max_ip_failures: std::env::var("LOGIN_MAX_IP_FAILURES")
+            .map(|v| {
+                v.parse::<u32>()
+                    .expect("LOGIN_MAX_IP_FAILURES 参数格式化错误")
+            })
+            .unwrap_or(default.max_ip_failures),
+        lockout: std::env::var("LOGIN_LOCKOUT_DURATION")
+            .map(|v| {
+                chrono::Duration::minutes(
+                    v.parse::<i64>()
+                        .expect("LOGIN_LOCKOUT_DURATION 参数格式化错误"),
+                )
+            })
+            .unwrap_or(default.lockout),
+        ..default
+    }
+}
+
+/// 登录失败记录的存储位置，`memory`（默认）或 `database`
+fn persist_login_attempts() -> bool {
+    match std::env::var("LOGIN_ATTEMPT_STORE").as_deref() {
+        Ok("database") => true,
+        Ok("memory") | Err(_) => false,
+        Ok(_) => panic!("LOGIN_ATTEMPT_STORE 参数格式化错误"),
+    }
+}
+
+/// 加密两步验证密钥等敏感数据的密钥，未配置时使用早期版本的 `SECRET`
+fn encryption_key() -> Option<String> {
+    ["ENCRYPTION_KEY", "SECRET"]
+        .iter()
+        .find_map(|name| std::env::var(name).ok().filter(|key| !key.is_empty()))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use inspirer_content::manager::{Manager, ManagerConfigBuilder};
+
+    /// 创建基于内存 SQLite 数据库的 Manager，并执行全部迁移
+    pub async fn sqlite_manager() -> Manager {
+        let manager = Manager::create_from_config(
+            ManagerConfigBuilder::default()
+                .database_url("sqlite::memory:".into())
+                .secret_key("test-secret")
+                .build()
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+        manager.migrate().await.unwrap();
+
+        manager
+    }
+}
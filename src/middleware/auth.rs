@@ -1,30 +1,283 @@
-use axum::{http::Request, middleware::Next, response::Response};
+//! 接口鉴权
+//!
+//! 支持三种方式：
+//!
+//! - JWT：`Authorization: Bearer <access_token>`，访问令牌须对应有效的登录会话
+//! - 个人访问令牌：`Authorization: Bearer ipat_...`，仅能访问以 [`require_scope`] 声明了权限范围的内容接口
+//! - 请求签名：使用用户的 Ed25519 私钥对请求签名，适用于 CI 等无需登录的场景，需携带以下请求头：
+//!   - `X-Inspirer-Key`：公钥指纹（公钥 SHA-256 摘要的十六进制文本）
+//!   - `X-Inspirer-Timestamp`：签名时间（Unix 秒）
+//!   - `X-Inspirer-Nonce`：8 至 64 位随机字符串，有效期内不可重复使用
+//!   - `X-Inspirer-Signature`：签名的十六进制文本
+//!
+//!   待签名内容见 [`inspirer_content::util::signature::signing_message`]。
+
+use axum::{
+    body::{Body, Bytes},
+    extract::OriginalUri,
+    http::{HeaderMap, Request},
+    middleware::Next,
+    response::Response,
+};
+use chrono::Duration;
+use http_body::{LengthLimitError, Limited};
+use inspirer_content::{
+    enumerate::token::TokenScope,
+    manager::Manager,
+    model::{signature::SignedRequest, token::ACCESS_TOKEN_PREFIX},
+    service::{
+        session::SessionService, signature::SignatureService, token::AccessTokenService,
+        user::UserService,
+    },
+    util::hash::{from_hex, sha256},
+};
+use lazy_static::lazy_static;
 
 use crate::{
     error::{InspirerError, InspirerResult},
-    session::Claims,
+    session::{Claims, SessionInfo},
 };
 
-pub async fn auth<B>(mut req: Request<B>, next: Next<B>) -> InspirerResult<Response> {
-    let auth_header = req
-        .headers()
+const SIGNATURE_KEY_HEADER: &str = "x-inspirer-key";
+const SIGNATURE_TIMESTAMP_HEADER: &str = "x-inspirer-timestamp";
+const SIGNATURE_NONCE_HEADER: &str = "x-inspirer-nonce";
+const SIGNATURE_HEADER: &str = "x-inspirer-signature";
+
+/// 签名时间与服务器时间默认允许相差 5 分钟
+const DEFAULT_SIGNATURE_MAX_SKEW: i64 = 300;
+/// 签名请求的请求体默认不超过 2 MiB
+const DEFAULT_SIGNATURE_MAX_BODY_SIZE: usize = 2 * 1024 * 1024;
+
+lazy_static! {
+    static ref SIGNATURE_MAX_SKEW: i64 = {
+        std::env::var("SIGNATURE_MAX_SKEW")
+            .map(|v| v.parse::<i64>().expect("SIGNATURE_MAX_SKEW 参数格式化错误"))
+            .unwrap_or(DEFAULT_SIGNATURE_MAX_SKEW)
+    };
+    static ref SIGNATURE_MAX_BODY_SIZE: usize = {
+        std::env::var("SIGNATURE_MAX_BODY_SIZE")
+            .map(|v| {
+                v.parse::<usize>()
+                    .expect("SIGNATURE_MAX_BODY_SIZE 参数格式化错误")
+            })
+            .unwrap_or(DEFAULT_SIGNATURE_MAX_BODY_SIZE)
+    };
+}
+
+pub async fn auth(req: Request<Body>, next: Next<Body>) -> InspirerResult<Response> {
+    let manager = req
+        .extensions()
+        .get::<Manager>()
+        .cloned()
+        .ok_or(InspirerError::ManagerNotRegistered)?;
+
+    let (mut req, session) = if req.headers().contains_key(SIGNATURE_HEADER) {
+        signature_auth(&manager, req).await?
+    } else {
+        let session = token_auth(&manager, req.headers()).await?;
+        (req, session)
+    };
+
+    req.extensions_mut().insert(session);
+
+    Ok(next.run(req).await)
+}
+
+async fn token_auth(manager: &Manager, headers: &HeaderMap) -> InspirerResult<SessionInfo> {
+    let token = headers
         .get(http::header::AUTHORIZATION)
         .and_then(|header| header.to_str().ok())
-        .ok_or(InspirerError::Unauthorized)?
-        .strip_prefix("Bearer ");
+        .and_then(|header| header.strip_prefix("Bearer "))
+        .ok_or(InspirerError::Unauthorized)?;
+
+    if token.starts_with(ACCESS_TOKEN_PREFIX) {
+        let (access_token, user) = manager.authenticate_access_token(token).await?;
+        return Ok(SessionInfo::with_access_token(user, &access_token));
+    }
+
+    let claims = Claims::from_token(token)?;
+
+    // 令牌需对应未被吊销且未被轮换的会话
+    let session = manager.find_active_session(claims.token_id()?).await?;
+    if session.user_id != claims.user_id() {
+        return Err(InspirerError::Unauthorized);
+    }
+
+    // 每次请求重新读取用户，使角色变更及禁用即时生效
+    let user = manager
+        .get_user_by_id(claims.user_id())
+        .await?
+        .filter(|user| !user.is_disabled())
+        .ok_or(InspirerError::Unauthorized)?;
+
+    Ok(SessionInfo::new(user, Some(session.id)))
+}
+
+/// 账号相关接口（个人资料、密码、会话、密钥及令牌管理等）不允许使用个人访问令牌访问
+pub async fn reject_access_token(req: Request<Body>, next: Next<Body>) -> InspirerResult<Response> {
+    match req.extensions().get::<SessionInfo>() {
+        Some(session) if session.is_access_token() => Err(InspirerError::Forbidden),
+        _ => Ok(next.run(req).await),
+    }
+}
+
+/// 个人访问令牌须拥有 `scope` 才能访问，其他方式鉴权时不受限制
+///
+/// 未使用该中间件声明权限范围的接口不允许使用个人访问令牌访问。
+pub async fn require_scope(
+    scope: TokenScope,
+    mut req: Request<Body>,
+    next: Next<Body>,
+) -> InspirerResult<Response> {
+    if let Some(session) = req.extensions_mut().get_mut::<SessionInfo>() {
+        session.authorize_route_scope(scope)?;
+    }
 
-    match auth_header {
-        Some(auth_header) => {
-            let claims = extract_token_payload(auth_header)?;
+    Ok(next.run(req).await)
+}
+
+/// 权限范围取决于请求内容的接口，个人访问令牌的权限范围由接口自行校验
+pub async fn defer_scope(mut req: Request<Body>, next: Next<Body>) -> InspirerResult<Response> {
+    if let Some(session) = req.extensions_mut().get_mut::<SessionInfo>() {
+        session.defer_route_scope();
+    }
 
-            req.extensions_mut().insert(claims.to_session_info());
+    Ok(next.run(req).await)
+}
 
-            Ok(next.run(req).await)
-        }
-        _ => Err(InspirerError::Unauthorized),
+/// 修改登录凭据的接口（密钥、个人访问令牌等）仅允许通过登录会话访问，不允许使用请求签名或个人访问令牌
+pub async fn require_login_session(
+    req: Request<Body>,
+    next: Next<Body>,
+) -> InspirerResult<Response> {
+    match req.extensions().get::<SessionInfo>() {
+        Some(session) if session.session_id().is_some() => Ok(next.run(req).await),
+        _ => Err(InspirerError::Forbidden),
     }
 }
 
-fn extract_token_payload(token: &str) -> InspirerResult<Claims> {
-    Claims::from_token(token)
+/// 读取不超过 `limit` 字节的请求体，`Content-Length` 超出限制时不读取直接拒绝
+async fn read_body(headers: &HeaderMap, body: Body, limit: usize) -> InspirerResult<Bytes> {
+    let content_length = headers
+        .get(http::header::CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<u64>().ok());
+    if content_length.is_some_and(|length| length > limit as u64) {
+        return Err(InspirerError::PayloadTooLarge(limit));
+    }
+
+    hyper::body::to_bytes(Limited::new(body, limit))
+        .await
+        .map_err(|err| {
+            if err.is::<LengthLimitError>() {
+                InspirerError::PayloadTooLarge(limit)
+            } else {
+                InspirerError::InvalidRequest(err.to_string())
+            }
+        })
+}
+
+/// 校验请求签名，校验需要读取完整的请求体，校验后以原请求体重建请求
+async fn signature_auth(
+    manager: &Manager,
+    req: Request<Body>,
+) -> InspirerResult<(Request<Body>, SessionInfo)> {
+    let (parts, body) = req.into_parts();
+
+    let header = |name: &str| {
+        parts
+            .headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .ok_or(InspirerError::Unauthorized)
+    };
+
+    let fingerprint = from_hex(header(SIGNATURE_KEY_HEADER)?).ok_or(InspirerError::Unauthorized)?;
+    let signature = from_hex(header(SIGNATURE_HEADER)?).ok_or(InspirerError::Unauthorized)?;
+    let timestamp = header(SIGNATURE_TIMESTAMP_HEADER)?
+        .parse::<i64>()
+        .map_err(|_| InspirerError::Unauthorized)?;
+    let nonce = header(SIGNATURE_NONCE_HEADER)?.to_string();
+
+    // 嵌套路由中的 URI 已去除前缀，签名使用完整路径
+    let uri = parts
+        .extensions
+        .get::<OriginalUri>()
+        .map(|OriginalUri(uri)| uri)
+        .unwrap_or(&parts.uri);
+    let path = uri
+        .path_and_query()
+        .map(|path| path.as_str())
+        .unwrap_or_else(|| uri.path())
+        .to_string();
+
+    let body = read_body(&parts.headers, body, *SIGNATURE_MAX_BODY_SIZE).await?;
+
+    let user = manager
+        .verify_signed_request(
+            SignedRequest {
+                fingerprint,
+                method: parts.method.to_string(),
+                path,
+                timestamp,
+                nonce,
+                body_hash: sha256(&body),
+                signature,
+            },
+            Duration::seconds(*SIGNATURE_MAX_SKEW),
+        )
+        .await?;
+
+    Ok((
+        Request::from_parts(parts, Body::from(body)),
+        SessionInfo::new(user, None),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::{Method, StatusCode};
+
+    use super::*;
+    use crate::{
+        manager::tests::sqlite_manager,
+        route::tests::{request, send},
+    };
+
+    #[tokio::test]
+    async fn test_read_body() {
+        let headers = HeaderMap::new();
+        let body = read_body(&headers, Body::from("0123456789"), 10)
+            .await
+            .unwrap();
+        assert_eq!(&body[..], b"0123456789");
+
+        // 未携带 Content-Length 时读取超出限制后拒绝
+        let res = read_body(&headers, Body::from("01234567890"), 10).await;
+        assert!(matches!(res, Err(InspirerError::PayloadTooLarge(10))));
+
+        let mut headers = HeaderMap::new();
+        headers.insert(http::header::CONTENT_LENGTH, "11".parse().unwrap());
+        let res = read_body(&headers, Body::empty(), 10).await;
+        assert!(matches!(res, Err(InspirerError::PayloadTooLarge(10))));
+    }
+
+    #[tokio::test]
+    async fn test_signature_body_too_large() {
+        let manager = sqlite_manager().await;
+
+        let mut req = request(Method::POST, "/security/content", None, None);
+        let headers = req.headers_mut();
+        headers.insert(SIGNATURE_KEY_HEADER, "00".parse().unwrap());
+        headers.insert(SIGNATURE_TIMESTAMP_HEADER, "0".parse().unwrap());
+        headers.insert(SIGNATURE_NONCE_HEADER, "nonce-nonce".parse().unwrap());
+        headers.insert(SIGNATURE_HEADER, "00".parse().unwrap());
+        headers.insert(
+            http::header::CONTENT_LENGTH,
+            (*SIGNATURE_MAX_BODY_SIZE + 1).to_string().parse().unwrap(),
+        );
+
+        let response = send(&manager, req).await;
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
 }
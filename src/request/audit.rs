@@ -0,0 +1,33 @@
+use chrono::{DateTime, Utc};
+use inspirer_content::{
+    enumerate::audit::AuditAction, model::audit::AuditLogCondition, util::uuid::base62_to_uuid,
+};
+use serde::Deserialize;
+
+use crate::error::InspirerError;
+
+/// 审计日志查询条件，分页参数见 [`inspirer_content::model::paginate::Pagination`]
+#[derive(Debug, Deserialize)]
+pub struct AuditLogQuery {
+    /// 执行操作的用户 ID
+    pub actor: Option<String>,
+    pub action: Option<AuditAction>,
+    /// 操作对象 ID
+    pub target: Option<String>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+}
+
+impl TryFrom<AuditLogQuery> for AuditLogCondition {
+    type Error = InspirerError;
+
+    fn try_from(query: AuditLogQuery) -> Result<Self, Self::Error> {
+        Ok(AuditLogCondition {
+            actor_id: query.actor.as_deref().map(base62_to_uuid).transpose()?,
+            action: query.action,
+            target_id: query.target.as_deref().map(base62_to_uuid).transpose()?,
+            since: query.since,
+            until: query.until,
+        })
+    }
+}
@@ -1,7 +1,110 @@
+use chrono::{DateTime, Utc};
+use inspirer_content::{
+    enumerate::token::TokenScope,
+    model::{profile::UserProfileData, token::NewAccessToken, user::UpdateUser},
+};
 use serde::Deserialize;
 
 #[derive(Debug, Deserialize)]
 pub struct LoginPayload {
     pub username: String,
     pub password: String,
-}
\ No newline at end of file
+}
+
+/// 提交验证码或恢复码完成两步验证登录
+#[derive(Debug, Deserialize)]
+pub struct TwoFactorLoginPayload {
+    pub two_factor_token: String,
+    pub code: String,
+}
+
+/// 申请密钥登录挑战
+#[derive(Debug, Deserialize)]
+pub struct LoginChallengePayload {
+    /// 公钥指纹的十六进制文本
+    pub fingerprint: String,
+}
+
+/// 使用私钥对登录挑战签名后登录
+#[derive(Debug, Deserialize)]
+pub struct KeyLoginPayload {
+    pub challenge: String,
+    /// 签名的十六进制文本
+    pub signature: String,
+}
+
+/// 登记用户自行生成的公钥
+#[derive(Debug, Deserialize)]
+pub struct RegisterPublicKeyPayload {
+    /// PEM 或 OpenSSH 格式的 Ed25519 公钥
+    pub public_key: String,
+    /// 当前密码
+    pub password: String,
+}
+
+/// 执行敏感操作前确认当前密码
+#[derive(Debug, Deserialize)]
+pub struct ConfirmPasswordPayload {
+    pub password: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RefreshTokenPayload {
+    pub refresh_token: String,
+}
+
+/// 更新个人资料，未提供的字段保持不变
+#[derive(Debug, Deserialize)]
+pub struct UpdateProfilePayload {
+    pub nickname: Option<String>,
+    pub avatar: Option<String>,
+    pub profile: Option<UserProfileData>,
+}
+
+impl From<UpdateProfilePayload> for UpdateUser {
+    fn from(payload: UpdateProfilePayload) -> Self {
+        UpdateUser {
+            nickname: payload.nickname,
+            avatar: payload.avatar,
+            profile: payload.profile,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ChangePasswordPayload {
+    pub current_password: String,
+    pub new_password: String,
+}
+
+/// 确认两步验证登记
+#[derive(Debug, Deserialize)]
+pub struct TwoFactorCodePayload {
+    pub code: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DisableTwoFactorPayload {
+    pub password: String,
+}
+
+/// 创建个人访问令牌
+#[derive(Debug, Deserialize)]
+pub struct CreateAccessTokenPayload {
+    pub name: String,
+    pub scopes: Vec<TokenScope>,
+    /// 为空时永不过期
+    pub expires_at: Option<DateTime<Utc>>,
+    /// 当前密码
+    pub password: String,
+}
+
+impl From<CreateAccessTokenPayload> for NewAccessToken {
+    fn from(payload: CreateAccessTokenPayload) -> Self {
+        NewAccessToken {
+            name: payload.name,
+            scopes: payload.scopes,
+            expires_at: payload.expires_at,
+        }
+    }
+}
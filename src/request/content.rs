@@ -1,6 +1,6 @@
+use chrono::{DateTime, Utc};
 pub use inspirer_content::model::content::{
-    NewContent as CreateContent,
-    UpdateContent
+    BulkContentAction, NewContent as CreateContent, UpdateContent,
 };
 use serde::Deserialize;
 
@@ -8,3 +8,35 @@ use serde::Deserialize;
 pub struct ForceDelete {
     pub force_delete: bool
 }
+
+/// 批量操作内容
+#[derive(Debug, Deserialize)]
+pub struct BulkContent {
+    pub action: BulkContentAction,
+    pub ids: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RevisionDiff {
+    pub from: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+pub struct PublishContent {
+    /// 发布时间，晚于当前时间时预定发布
+    pub publish_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RenderFormat {
+    Html,
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+pub struct RenderContent {
+    /// 指定后在返回原始内容的同时返回渲染结果
+    pub render: Option<RenderFormat>,
+}
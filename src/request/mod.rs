@@ -1,2 +1,5 @@
+pub mod audit;
 pub mod auth;
-pub mod content;
\ No newline at end of file
+pub mod content;
+pub mod search;
+pub mod taxonomy;
@@ -0,0 +1,8 @@
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+pub struct SearchQuery {
+    /// 搜索关键词
+    pub q: String,
+}
@@ -0,0 +1,7 @@
+pub use inspirer_content::model::taxonomy::{NewCategory as CreateCategory, UpdateCategory};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct CreateTag {
+    pub name: String,
+}
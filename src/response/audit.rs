@@ -0,0 +1,42 @@
+use chrono::{DateTime, Utc};
+use inspirer_content::{
+    enumerate::audit::{AuditAction, AuditTargetType},
+    model::{audit::AuditLogModel, user::UserModel},
+    util::uuid::uuid_to_base62,
+};
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct AuditLog {
+    pub id: String,
+    pub actor_id: Option<String>,
+    /// 执行操作的用户名，用户已被删除时为空
+    pub actor: Option<String>,
+    /// 使用个人访问令牌执行操作时的令牌 ID
+    pub access_token_id: Option<String>,
+    pub action: AuditAction,
+    pub target_type: Option<AuditTargetType>,
+    pub target_id: Option<String>,
+    pub detail: Option<serde_json::Value>,
+    pub ip: Option<String>,
+    pub user_agent: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<(AuditLogModel, Option<UserModel>)> for AuditLog {
+    fn from((log, actor): (AuditLogModel, Option<UserModel>)) -> Self {
+        AuditLog {
+            id: uuid_to_base62(log.id),
+            actor_id: log.actor_id.map(uuid_to_base62),
+            actor: actor.map(|user| user.username),
+            access_token_id: log.access_token_id.map(uuid_to_base62),
+            action: log.action,
+            target_type: log.target_type,
+            target_id: log.target_id.map(uuid_to_base62),
+            detail: log.detail,
+            ip: log.ip,
+            user_agent: log.user_agent,
+            created_at: log.created_at,
+        }
+    }
+}
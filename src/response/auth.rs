@@ -1,8 +1,59 @@
+use chrono::{DateTime, Utc};
+use inspirer_content::{
+    enumerate::{token::TokenScope, user::UserRole},
+    error::Error as InspirerContentError,
+    model::{
+        profile::UserProfileData,
+        session::SessionModel,
+        signature::LoginChallengeModel,
+        token::{AccessTokenModel, IssuedAccessToken},
+        user::{TwoFactorEnrollment as TwoFactorEnrollmentModel, UserModel},
+    },
+    util::{
+        hash::to_hex,
+        uuid::{uuid_to_base62, Uuid},
+    },
+};
 use serde::Serialize;
 
 #[derive(Debug, Serialize)]
 pub struct AccessToken {
     pub access_token: String,
+    pub token_type: &'static str,
+    /// 访问令牌有效期（秒）
+    pub expires_in: i64,
+    pub refresh_token: String,
+}
+
+/// 已开启两步验证的用户通过密码校验后返回，需提交验证码完成登录
+#[derive(Debug, Serialize)]
+pub struct TwoFactorChallenge {
+    pub two_factor_token: String,
+    /// 两步验证令牌有效期（秒）
+    pub expires_in: i64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum LoginResult {
+    AccessToken(AccessToken),
+    TwoFactorRequired(TwoFactorChallenge),
+}
+
+/// 密钥登录挑战，客户端需使用私钥对 `challenge` 签名
+#[derive(Debug, Serialize)]
+pub struct LoginChallenge {
+    pub challenge: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl From<LoginChallengeModel> for LoginChallenge {
+    fn from(model: LoginChallengeModel) -> Self {
+        LoginChallenge {
+            challenge: model.challenge,
+            expires_at: model.expires_at,
+        }
+    }
 }
 
 #[derive(Debug, Serialize)]
@@ -11,4 +62,117 @@ pub struct UserProfile {
     pub username: String,
     pub nickname: String,
     pub avatar: String,
-}
\ No newline at end of file
+    pub role: UserRole,
+    /// 当前公钥指纹的十六进制文本
+    pub public_key_fingerprint: String,
+    pub two_factor_enabled: bool,
+    pub profile: UserProfileData,
+}
+
+impl TryFrom<UserModel> for UserProfile {
+    type Error = InspirerContentError;
+
+    fn try_from(model: UserModel) -> Result<Self, Self::Error> {
+        Ok(UserProfile {
+            profile: model.profile()?,
+            two_factor_enabled: model.two_factor_enabled(),
+            id: uuid_to_base62(model.id),
+            nickname: model.nickname,
+            username: model.username,
+            avatar: model.avatar,
+            role: model.role,
+            public_key_fingerprint: to_hex(&model.public_key_fingerprint),
+        })
+    }
+}
+
+/// 两步验证登记信息，恢复码仅在登记时可见
+#[derive(Debug, Serialize)]
+pub struct TwoFactorEnrollment {
+    /// Base32 编码的密钥
+    pub secret: String,
+    pub otpauth_uri: String,
+    pub recovery_codes: Vec<String>,
+}
+
+impl From<TwoFactorEnrollmentModel> for TwoFactorEnrollment {
+    fn from(model: TwoFactorEnrollmentModel) -> Self {
+        TwoFactorEnrollment {
+            secret: model.secret,
+            otpauth_uri: model.otpauth_uri,
+            recovery_codes: model.recovery_codes,
+        }
+    }
+}
+
+/// 重新生成的密钥对，私钥仅在生成时可见
+#[derive(Debug, Serialize)]
+pub struct RotatedKeyPair {
+    /// PEM 格式的私钥
+    pub private_key: String,
+}
+/// 登录会话
+#[derive(Debug, Serialize)]
+pub struct UserSession {
+    pub id: String,
+    pub user_agent: String,
+    pub created_at: DateTime<Utc>,
+    pub refreshed_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    /// 是否为当前请求所使用的会话
+    pub current: bool,
+}
+
+impl UserSession {
+    pub fn new(session: SessionModel, current_session_id: Option<Uuid>) -> Self {
+        UserSession {
+            id: uuid_to_base62(session.id),
+            current: Some(session.id) == current_session_id,
+            user_agent: session.user_agent,
+            created_at: session.created_at,
+            refreshed_at: session.refreshed_at,
+            expires_at: session.expires_at,
+        }
+    }
+}
+
+/// 个人访问令牌，不包含令牌明文
+#[derive(Debug, Serialize)]
+pub struct PersonalAccessToken {
+    pub id: String,
+    pub name: String,
+    pub scopes: Vec<TokenScope>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<AccessTokenModel> for PersonalAccessToken {
+    fn from(model: AccessTokenModel) -> Self {
+        PersonalAccessToken {
+            id: uuid_to_base62(model.id),
+            scopes: model.scopes(),
+            name: model.name,
+            expires_at: model.expires_at,
+            last_used_at: model.last_used_at,
+            created_at: model.created_at,
+        }
+    }
+}
+
+/// 新创建的个人访问令牌，令牌明文仅在创建时可见
+#[derive(Debug, Serialize)]
+pub struct CreatedAccessToken {
+    #[serde(flatten)]
+    pub access_token: PersonalAccessToken,
+    pub token: String,
+}
+
+impl From<IssuedAccessToken> for CreatedAccessToken {
+    fn from(issued: IssuedAccessToken) -> Self {
+        CreatedAccessToken {
+            access_token: issued.access_token.into(),
+            token: issued.token,
+        }
+    }
+}
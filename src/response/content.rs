@@ -1,14 +1,15 @@
 use chrono::{DateTime, Utc};
 use inspirer_content::{
     model::{
-        content::{Content, ContentEntity, ContentModel},
+        content::{Content, ContentEntity, ContentModel, UpdateContent},
+        revision::{ContentRevision as ContentRevisionModel, ContentSnapshot},
         user::UserModel,
     },
     util::uuid::uuid_to_base62,
 };
 use serde::Serialize;
 
-pub use inspirer_content::model::content::ContentConfig;
+pub use inspirer_content::model::{content::ContentConfig, revision::ContentDiff};
 
 #[derive(Debug, Clone, Serialize)]
 pub struct ContentBase {
@@ -57,13 +58,21 @@ pub struct ContentWithEntity {
     #[serde(flatten)]
     pub base: ContentBase,
     pub entity: ContentEntity,
+    /// 渲染后的 HTML，仅在请求渲染时返回
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub html: Option<String>,
+    pub tags: Vec<String>,
+    pub categories: Vec<String>,
 }
 
 impl From<Content> for ContentWithEntity {
-    fn from(Content { meta, entity }: Content) -> Self {
+    fn from(content: Content) -> Self {
         ContentWithEntity {
-            base: ContentBase::from(meta),
-            entity,
+            base: ContentBase::from(content.meta),
+            entity: content.entity,
+            html: None,
+            tags: content.tags,
+            categories: content.categories,
         }
     }
 }
@@ -75,6 +84,8 @@ pub struct ContentFull {
     pub content_type: u32,
     pub is_publish: bool,
     pub is_display: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scheduled_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -88,7 +99,7 @@ pub struct DeletedContent {
 
 impl From<(ContentModel, Option<UserModel>)> for DeletedContent {
     fn from((content_raw, owner): (ContentModel, Option<UserModel>)) -> Self {
-        let deleted_at = content_raw.deleted_at.clone();
+        let deleted_at = content_raw.deleted_at;
         DeletedContent {
             content: (content_raw, owner).into(),
             deleted_at,
@@ -102,6 +113,7 @@ impl From<ContentModel> for ContentFull {
             content_type: content_raw.content_type as u32,
             is_display: content_raw.is_display,
             is_publish: content_raw.is_publish,
+            scheduled_at: content_raw.scheduled_at,
             created_at: content_raw.created_at,
             updated_at: content_raw.updated_at,
             base: content_raw.into(),
@@ -115,6 +127,7 @@ impl From<(ContentModel, Option<UserModel>)> for ContentFull {
             content_type: content_raw.content_type as u32,
             is_display: content_raw.is_display,
             is_publish: content_raw.is_publish,
+            scheduled_at: content_raw.scheduled_at,
             created_at: content_raw.created_at,
             updated_at: content_raw.updated_at,
             base: (content_raw, owner).into(),
@@ -127,6 +140,19 @@ pub struct ContentFullWithEntity {
     #[serde(flatten)]
     pub content: ContentFull,
     pub entity: ContentEntity,
+    pub tags: Vec<String>,
+    pub categories: Vec<String>,
+}
+
+impl From<Content> for ContentFullWithEntity {
+    fn from(content: Content) -> Self {
+        ContentFullWithEntity {
+            content: ContentFull::from(content.meta),
+            entity: content.entity,
+            tags: content.tags,
+            categories: content.categories,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -141,7 +167,53 @@ impl From<UserModel> for ContentOwner {
         ContentOwner {
             id: uuid_to_base62(user_raw.id),
             nickname: user_raw.nickname,
-            avatar: user_raw.avatar.is_empty().then(|| user_raw.avatar),
+            avatar: user_raw.avatar.is_empty().then_some(user_raw.avatar),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ContentRevision {
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<ContentOwner>,
+    pub changes: UpdateContent,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<ContentRevisionModel> for ContentRevision {
+    fn from(revision: ContentRevisionModel) -> Self {
+        ContentRevision {
+            id: uuid_to_base62(revision.id),
+            author: revision.author.map(ContentOwner::from),
+            changes: revision.update_data,
+            created_at: revision.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ContentRevisionWithSnapshot {
+    #[serde(flatten)]
+    pub revision: ContentRevision,
+    pub content: ContentSnapshot,
+}
+
+/// 批量操作中单条内容的处理结果
+#[derive(Debug, Serialize)]
+pub struct BulkContentResult {
+    pub id: String,
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl BulkContentResult {
+    pub fn new<E: std::fmt::Display>(id: String, result: Result<(), E>) -> Self {
+        BulkContentResult {
+            id,
+            success: result.is_ok(),
+            error: result.err().map(|err| err.to_string()),
         }
     }
 }
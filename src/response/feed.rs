@@ -0,0 +1,190 @@
+use chrono::{DateTime, Utc};
+use inspirer_content::{model::content::ContentModel, util::uuid::uuid_to_base62};
+use serde::Serialize;
+
+use crate::site::SiteConfig;
+
+const RSS_CONTENT_NAMESPACE: &str = "http://purl.org/rss/1.0/modules/content/";
+const RSS_DUBLIN_CORE_NAMESPACE: &str = "http://purl.org/dc/elements/1.1/";
+
+/// Feed 条目
+#[derive(Debug, Clone)]
+pub struct FeedEntry {
+    pub content: ContentModel,
+    /// 作者昵称
+    pub author: Option<String>,
+    /// 渲染后的全文
+    pub html: Option<String>,
+}
+
+impl FeedEntry {
+    fn published_at(&self) -> DateTime<Utc> {
+        self.content.published_at.unwrap_or(self.content.created_at)
+    }
+}
+
+/// Feed 的最后更新时间，取最近一篇内容的发布时间
+fn feed_updated_at(entries: &[FeedEntry]) -> DateTime<Utc> {
+    entries
+        .iter()
+        .map(FeedEntry::published_at)
+        .max()
+        .unwrap_or_else(Utc::now)
+}
+
+pub fn rss_channel(site: &SiteConfig, entries: &[FeedEntry]) -> rss::Channel {
+    let items = entries
+        .iter()
+        .map(|entry| {
+            let link = site.content_url(&entry.content);
+
+            rss::Item {
+                title: Some(entry.content.title.clone()),
+                link: Some(link.clone()),
+                description: Some(entry.content.description.clone()),
+                guid: Some(rss::Guid {
+                    value: link,
+                    permalink: true,
+                }),
+                pub_date: Some(entry.published_at().to_rfc2822()),
+                content: entry.html.clone(),
+                dublin_core_ext: entry.author.clone().map(|author| {
+                    rss::extension::dublincore::DublinCoreExtension {
+                        creators: vec![author],
+                        ..Default::default()
+                    }
+                }),
+                ..Default::default()
+            }
+        })
+        .collect();
+
+    rss::Channel {
+        title: site.title.clone(),
+        link: site.url_for("/"),
+        description: site.description.clone(),
+        last_build_date: Some(feed_updated_at(entries).to_rfc2822()),
+        generator: Some(env!("CARGO_PKG_NAME").into()),
+        items,
+        namespaces: [
+            ("content".to_string(), RSS_CONTENT_NAMESPACE.to_string()),
+            ("dc".to_string(), RSS_DUBLIN_CORE_NAMESPACE.to_string()),
+        ]
+        .into_iter()
+        .collect(),
+        ..Default::default()
+    }
+}
+
+pub fn atom_feed(site: &SiteConfig, entries: &[FeedEntry]) -> atom_syndication::Feed {
+    use atom_syndication::{Content, Entry, Feed, Link, Person, Text};
+
+    let link = |href: String, rel: &str| Link {
+        href,
+        rel: rel.into(),
+        ..Default::default()
+    };
+
+    let items = entries
+        .iter()
+        .map(|entry| {
+            let url = site.content_url(&entry.content);
+
+            Entry {
+                title: Text::plain(entry.content.title.clone()),
+                id: url.clone(),
+                updated: entry.content.modified_at.into(),
+                published: Some(entry.published_at().into()),
+                authors: entry
+                    .author
+                    .iter()
+                    .map(|name| Person {
+                        name: name.clone(),
+                        ..Default::default()
+                    })
+                    .collect(),
+                links: vec![link(url, "alternate")],
+                summary: Some(Text::plain(entry.content.description.clone())),
+                content: entry.html.as_ref().map(|html| Content {
+                    value: Some(html.clone()),
+                    content_type: Some("html".into()),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }
+        })
+        .collect();
+
+    Feed {
+        title: Text::plain(site.title.clone()),
+        id: site.url_for("/"),
+        updated: feed_updated_at(entries).into(),
+        subtitle: (!site.description.is_empty()).then(|| Text::plain(site.description.clone())),
+        links: vec![
+            link(site.url_for("/"), "alternate"),
+            link(site.url_for("/atom.xml"), "self"),
+        ],
+        entries: items,
+        ..Default::default()
+    }
+}
+
+/// JSON Feed 1.1
+#[derive(Debug, Serialize)]
+pub struct JsonFeed {
+    pub version: &'static str,
+    pub title: String,
+    pub home_page_url: String,
+    pub feed_url: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub description: String,
+    pub items: Vec<JsonFeedItem>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct JsonFeedItem {
+    pub id: String,
+    pub url: String,
+    pub title: String,
+    pub summary: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_html: Option<String>,
+    pub date_published: DateTime<Utc>,
+    pub date_modified: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub authors: Vec<JsonFeedAuthor>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct JsonFeedAuthor {
+    pub name: String,
+}
+
+impl JsonFeed {
+    pub fn new(site: &SiteConfig, entries: Vec<FeedEntry>) -> Self {
+        JsonFeed {
+            version: "https://jsonfeed.org/version/1.1",
+            title: site.title.clone(),
+            home_page_url: site.url_for("/"),
+            feed_url: site.url_for("/feed.json"),
+            description: site.description.clone(),
+            items: entries
+                .into_iter()
+                .map(|entry| JsonFeedItem {
+                    id: uuid_to_base62(entry.content.id),
+                    url: site.content_url(&entry.content),
+                    date_published: entry.published_at(),
+                    date_modified: entry.content.modified_at,
+                    title: entry.content.title,
+                    summary: entry.content.description,
+                    content_html: entry.html,
+                    authors: entry
+                        .author
+                        .into_iter()
+                        .map(|name| JsonFeedAuthor { name })
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+}
@@ -1,10 +1,14 @@
 use std::fmt::Display;
 
-use inspirer_content::util::uuid::{uuid_to_base62, Uuid};
 use serde::Serialize;
 
+pub mod audit;
 pub mod auth;
 pub mod content;
+pub mod feed;
+pub mod search;
+pub mod sitemap;
+pub mod taxonomy;
 
 #[derive(Debug, Serialize)]
 pub struct ErrorMessage {
@@ -16,16 +20,3 @@ pub fn error_message_from_err<E: Display>(err: &E) -> ErrorMessage {
         msg: format!("{}", err),
     }
 }
-
-#[derive(Debug, Serialize, Clone, PartialEq)]
-pub struct CreatedDataStringId {
-    id: String,
-}
-
-impl CreatedDataStringId {
-    pub fn from_uuid(uuid: Uuid) -> Self {
-        CreatedDataStringId {
-            id: uuid_to_base62(uuid),
-        }
-    }
-}
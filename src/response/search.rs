@@ -0,0 +1,25 @@
+use inspirer_content::model::search::SearchResult as SearchResultModel;
+use serde::Serialize;
+
+use crate::response::content::ContentBase;
+
+/// 搜索结果，高亮片段为 HTML，命中的关键词以 `<b>` 标记
+#[derive(Debug, Serialize)]
+pub struct SearchResult {
+    #[serde(flatten)]
+    pub base: ContentBase,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title_highlight: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body_highlight: Option<String>,
+}
+
+impl From<SearchResultModel> for SearchResult {
+    fn from(result: SearchResultModel) -> Self {
+        SearchResult {
+            base: ContentBase::from((result.content, result.owner)),
+            title_highlight: result.title_highlight,
+            body_highlight: result.body_highlight,
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use std::fmt::Write;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::Serialize;
+
+/// 单个 sitemap 文件允许包含的最大 URL 数量
+pub const SITEMAP_MAX_URLS: usize = 50_000;
+
+const SITEMAP_NAMESPACE: &str = "http://www.sitemaps.org/schemas/sitemap/0.9";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SitemapUrl {
+    pub loc: String,
+    pub lastmod: DateTime<Utc>,
+}
+
+/// 生成 `<urlset>` 格式的 sitemap
+pub fn url_set(urls: &[SitemapUrl]) -> String {
+    let mut xml = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?><urlset xmlns="{}">"#,
+        SITEMAP_NAMESPACE
+    );
+
+    for url in urls {
+        write_entry(&mut xml, "url", url);
+    }
+
+    xml.push_str("</urlset>");
+    xml
+}
+
+/// 生成 `<sitemapindex>` 格式的 sitemap 索引
+pub fn sitemap_index(sitemaps: &[SitemapUrl]) -> String {
+    let mut xml = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?><sitemapindex xmlns="{}">"#,
+        SITEMAP_NAMESPACE
+    );
+
+    for sitemap in sitemaps {
+        write_entry(&mut xml, "sitemap", sitemap);
+    }
+
+    xml.push_str("</sitemapindex>");
+    xml
+}
+
+fn write_entry(xml: &mut String, tag: &str, url: &SitemapUrl) {
+    let _ = write!(
+        xml,
+        "<{tag}><loc>{}</loc><lastmod>{}</lastmod></{tag}>",
+        escape(&url.loc),
+        url.lastmod.to_rfc3339_opts(SecondsFormat::Secs, true),
+    );
+}
+
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
@@ -0,0 +1,42 @@
+use chrono::{DateTime, Utc};
+use inspirer_content::{
+    model::taxonomy::{CategoryModel, TagModel},
+    util::uuid::uuid_to_base62,
+};
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Tag {
+    pub id: String,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<TagModel> for Tag {
+    fn from(tag: TagModel) -> Self {
+        Tag {
+            id: uuid_to_base62(tag.id),
+            name: tag.name,
+            created_at: tag.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Category {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<CategoryModel> for Category {
+    fn from(category: CategoryModel) -> Self {
+        Category {
+            id: uuid_to_base62(category.id),
+            name: category.name,
+            description: category.description,
+            created_at: category.created_at,
+        }
+    }
+}
@@ -1,9 +1,15 @@
-use crate::{controller, middleware::auth::auth};
+use crate::{
+    controller,
+    middleware::auth::{
+        auth, defer_scope, reject_access_token, require_login_session, require_scope,
+    },
+};
 use axum::{
     middleware,
-    routing::{delete, get, post},
+    routing::{delete, get, post, put},
     Router,
 };
+use inspirer_content::enumerate::token::TokenScope;
 
 pub fn create_routes() -> Router {
     Router::new()
@@ -12,39 +18,863 @@ pub fn create_routes() -> Router {
             get(controller::content::get_content_list_simple),
         )
         .route("/content/:id", get(controller::content::find_content))
+        .route("/search", get(controller::search::search))
+        .route("/tags", get(controller::taxonomy::get_tags))
+        .route(
+            "/tags/:name",
+            get(controller::taxonomy::get_tag_content_list),
+        )
+        .route("/categories", get(controller::taxonomy::get_categories))
+        .route(
+            "/categories/:name",
+            get(controller::taxonomy::get_category_content_list),
+        )
+        .route("/feed.xml", get(controller::feed::rss))
+        .route("/atom.xml", get(controller::feed::atom))
+        .route("/feed.json", get(controller::feed::json_feed))
+        .route("/sitemap.xml", get(controller::seo::sitemap))
+        .route("/sitemap/:file", get(controller::seo::sitemap_page))
+        .route("/robots.txt", get(controller::seo::robots))
+        .route("/.well-known/jwks.json", get(controller::auth::jwks))
         .route("/login", post(controller::auth::login))
+        .route("/login/challenge", post(controller::auth::login_challenge))
+        .route("/login/key", post(controller::auth::login_with_key))
+        .route("/login/2fa", post(controller::auth::login_two_factor))
+        .route("/token/refresh", post(controller::auth::refresh_token))
         .nest("/security", secure_routes())
 }
 
 pub fn secure_routes() -> Router {
     Router::new()
-        .route("/profile", get(controller::auth::get_profile))
+        .merge(account_routes())
+        .merge(management_routes())
+        .merge(content_routes())
+        .route_layer(middleware::from_fn(auth))
+}
+
+/// 内容接口，个人访问令牌须拥有各组接口对应的权限范围
+fn content_routes() -> Router {
+    let read = Router::new()
         .route(
             "/content-service-config",
             get(controller::content::get_config),
         )
-        .route(
-            "/content",
-            get(controller::content::get_content_list).post(controller::content::create_content),
-        )
+        .route("/content", get(controller::content::get_content_list))
         .route(
             "/deleted/content",
             get(controller::content::get_deleted_content_list),
         )
+        .route("/content/:id", get(controller::content::get_content))
         .route(
-            "/deleted/content/:id",
-            delete(controller::content::revert_deleted_content),
+            "/content/:id/revisions",
+            get(controller::content::get_content_revisions),
         )
         .route(
-            "/content/:id",
-            get(controller::content::get_content)
-                .put(controller::content::update_content)
-                .delete(controller::content::delete_content),
+            "/content/:id/revisions/:rev",
+            get(controller::content::get_content_revision),
         )
+        .route(
+            "/content/:id/revisions/:rev/diff",
+            get(controller::content::get_content_revision_diff),
+        );
+
+    // 批量操作按操作类型在接口中校验对应的权限范围
+    let bulk = Router::new()
+        .route(
+            "/content/bulk",
+            post(controller::content::bulk_update_contents),
+        )
+        .route_layer(middleware::from_fn(defer_scope));
+
+    let write = Router::new()
+        .route("/content", post(controller::content::create_content))
+        .route("/content/:id", put(controller::content::update_content))
+        .route(
+            "/content/:id/revisions/:rev/restore",
+            post(controller::content::restore_content_revision),
+        );
+
+    let publish = Router::new()
         .route(
             "/content/:id/publish",
             post(controller::content::publish_content)
                 .delete(controller::content::unpublish_content),
         )
-        .route_layer(middleware::from_fn(auth))
+        .route(
+            "/content/:id/display",
+            post(controller::content::show_content).delete(controller::content::hide_content),
+        );
+
+    let delete = Router::new()
+        .route("/content/:id", delete(controller::content::delete_content))
+        .route(
+            "/deleted/content/:id",
+            delete(controller::content::revert_deleted_content),
+        );
+
+    Router::new()
+        .merge(scoped(read, TokenScope::ContentRead))
+        .merge(scoped(write, TokenScope::ContentWrite))
+        .merge(scoped(publish, TokenScope::ContentPublish))
+        .merge(scoped(delete, TokenScope::ContentDelete))
+        .merge(bulk)
+}
+
+fn scoped(router: Router, scope: TokenScope) -> Router {
+    router.route_layer(middleware::from_fn(move |req, next| {
+        require_scope(scope, req, next)
+    }))
+}
+
+fn management_routes() -> Router {
+    Router::new()
+        .route("/audit", get(controller::audit::get_audit_logs))
+        .route("/tags", post(controller::taxonomy::create_tag))
+        .route("/tags/:name", delete(controller::taxonomy::delete_tag))
+        .route("/categories", post(controller::taxonomy::create_category))
+        .route(
+            "/categories/:name",
+            put(controller::taxonomy::update_category)
+                .delete(controller::taxonomy::delete_category),
+        )
+        .route_layer(middleware::from_fn(reject_access_token))
+}
+
+fn account_routes() -> Router {
+    Router::new()
+        .route(
+            "/profile",
+            get(controller::auth::get_profile).put(controller::auth::update_profile),
+        )
+        .route("/password", put(controller::auth::change_password))
+        .route("/logout", post(controller::auth::logout))
+        .route("/sessions", get(controller::auth::get_sessions))
+        .route("/sessions/:id", delete(controller::auth::revoke_session))
+        .merge(credential_routes())
+        .route_layer(middleware::from_fn(reject_access_token))
+}
+
+fn credential_routes() -> Router {
+    Router::new()
+        .route("/keypair", post(controller::auth::rotate_keypair))
+        .route("/public-key", put(controller::auth::register_public_key))
+        .route(
+            "/tokens",
+            get(controller::auth::get_access_tokens).post(controller::auth::create_access_token),
+        )
+        .route("/tokens/:id", delete(controller::auth::revoke_access_token))
+        .route(
+            "/2fa",
+            post(controller::auth::enroll_two_factor).delete(controller::auth::disable_two_factor),
+        )
+        .route("/2fa/confirm", post(controller::auth::confirm_two_factor))
+        .route_layer(middleware::from_fn(require_login_session))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::net::SocketAddr;
+
+    use axum::{
+        body::Body,
+        extract::ConnectInfo,
+        http::{header, Method, Request, StatusCode},
+        response::Response,
+        Extension,
+    };
+    use chrono::Utc;
+    use inspirer_content::util::{
+        hash::{sha256, to_hex},
+        signature::signing_message,
+        totp::{time_step, totp},
+    };
+    use inspirer_content::{
+        enumerate::user::UserRole,
+        manager::Manager,
+        model::{
+            content::{ContentEntity, ContentMeta, NewContent},
+            user::NewUser,
+        },
+        service::{content::ContentService, throttle::LoginThrottleService, user::UserService},
+        throttle::AttemptKey,
+        util::uuid::{uuid_to_base62, Uuid},
+    };
+    use ring::signature::{Ed25519KeyPair, KeyPair};
+    use serde_json::Value;
+    use tower::ServiceExt;
+
+    use super::create_routes;
+    use crate::manager::tests::sqlite_manager;
+
+    pub const PASSWORD: &str = "Correct-Horse-42";
+
+    /// 向完整路由发送请求，请求来源为 `127.0.0.1`
+    pub async fn send(manager: &Manager, mut request: Request<Body>) -> Response {
+        request
+            .extensions_mut()
+            .insert(ConnectInfo(SocketAddr::from(([127, 0, 0, 1], 0))));
+
+        create_routes()
+            .layer(Extension(manager.clone()))
+            .oneshot(request)
+            .await
+            .unwrap()
+    }
+
+    pub async fn get(manager: &Manager, uri: &str) -> Response {
+        send(manager, Request::get(uri).body(Body::empty()).unwrap()).await
+    }
+
+    pub async fn body_string(response: Response) -> String {
+        let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
+
+    pub async fn body_json(response: Response) -> Value {
+        serde_json::from_str(&body_string(response).await).unwrap()
+    }
+
+    /// 构造请求，`token` 为空时不携带授权信息
+    pub fn request(
+        method: Method,
+        uri: &str,
+        token: Option<&str>,
+        body: Option<Value>,
+    ) -> Request<Body> {
+        let mut builder = Request::builder().method(method).uri(uri);
+        if let Some(token) = token {
+            builder = builder.header(header::AUTHORIZATION, format!("Bearer {token}"));
+        }
+
+        match body {
+            Some(body) => builder
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(body.to_string())),
+            None => builder.body(Body::empty()),
+        }
+        .unwrap()
+    }
+
+    pub fn signed_request(
+        private_key: &str,
+        method: Method,
+        uri: &str,
+        body: Value,
+    ) -> Request<Body> {
+        let (_, document) = der::Document::from_pem(private_key).unwrap();
+        let key_pair = Ed25519KeyPair::from_pkcs8_maybe_unchecked(document.as_bytes()).unwrap();
+        let body = body.to_string();
+        let timestamp = Utc::now().timestamp();
+        let nonce = Uuid::new_v4().simple().to_string();
+        let message = signing_message(
+            method.as_str(),
+            uri,
+            timestamp,
+            &nonce,
+            &sha256(body.as_bytes()),
+        );
+
+        Request::builder()
+            .method(method)
+            .uri(uri)
+            .header(header::CONTENT_TYPE, "application/json")
+            .header(
+                "x-inspirer-key",
+                to_hex(&sha256(key_pair.public_key().as_ref())),
+            )
+            .header("x-inspirer-timestamp", timestamp.to_string())
+            .header("x-inspirer-nonce", &nonce)
+            .header(
+                "x-inspirer-signature",
+                to_hex(key_pair.sign(message.as_bytes()).as_ref()),
+            )
+            .body(Body::from(body))
+            .unwrap()
+    }
+
+    /// 创建用户，密码为 [`PASSWORD`]，返回用户 ID 及 PEM 格式的私钥
+    pub async fn create_user_with_key(
+        manager: &Manager,
+        username: &str,
+        role: UserRole,
+    ) -> (Uuid, String) {
+        manager
+            .create_user_simple(
+                NewUser {
+                    username: username.into(),
+                    password: PASSWORD.into(),
+                    nickname: username.into(),
+                    avatar: String::new(),
+                    role,
+                },
+                None,
+            )
+            .await
+            .unwrap()
+    }
+
+    pub async fn create_user(manager: &Manager, username: &str, role: UserRole) -> Uuid {
+        create_user_with_key(manager, username, role).await.0
+    }
+
+    pub async fn login(manager: &Manager, username: &str) -> String {
+        let payload = serde_json::json!({ "username": username, "password": PASSWORD });
+        let response = send(
+            manager,
+            request(Method::POST, "/login", None, Some(payload)),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        body_json(response).await["access_token"]
+            .as_str()
+            .unwrap()
+            .to_string()
+    }
+
+    #[tokio::test]
+    async fn test_content_authorization() {
+        let manager = sqlite_manager().await;
+        let owner_id = create_user(&manager, "owner", UserRole::Author).await;
+        create_user(&manager, "author", UserRole::Author).await;
+        create_user(&manager, "editor", UserRole::Editor).await;
+
+        let content = manager
+            .create_content(
+                owner_id,
+                NewContent {
+                    meta: ContentMeta {
+                        title: "owned".into(),
+                        ..Default::default()
+                    },
+                    entity: ContentEntity::Post(String::new()),
+                },
+                true,
+            )
+            .await
+            .unwrap();
+        let uri = format!("/security/content/{}", uuid_to_base62(content.meta.id));
+        let update = serde_json::json!({ "title": "changed" });
+
+        let response = send(&manager, request(Method::GET, &uri, None, None)).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        // 作者不能查看或修改他人的内容
+        let token = login(&manager, "author").await;
+        let response = send(&manager, request(Method::GET, &uri, Some(&token), None)).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        let response = send(
+            &manager,
+            request(Method::PUT, &uri, Some(&token), Some(update.clone())),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        let response = send(
+            &manager,
+            request(
+                Method::DELETE,
+                &format!("{uri}?force_delete=false"),
+                Some(&token),
+                None,
+            ),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        let response = send(
+            &manager,
+            request(Method::POST, &format!("{uri}/publish"), Some(&token), None),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        let content = manager.find_content_by_id(content.meta.id).await.unwrap();
+        assert_eq!(content.meta.title, "owned");
+        assert!(!content.meta.is_deleted && !content.meta.is_publish);
+
+        let token = login(&manager, "owner").await;
+        let response = send(&manager, request(Method::GET, &uri, Some(&token), None)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(body_json(response).await["title"], "owned");
+
+        // 编辑可以修改所有人的内容
+        let token = login(&manager, "editor").await;
+        let response = send(
+            &manager,
+            request(Method::PUT, &uri, Some(&token), Some(update)),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let content = manager.find_content_by_id(content.meta.id).await.unwrap();
+        assert_eq!(content.meta.title, "changed");
+
+        // 未注册 Manager 时返回错误而不是 panic
+        let response = create_routes()
+            .oneshot(request(Method::GET, &uri, Some(&token), None))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[tokio::test]
+    async fn test_credential_routes() {
+        let manager = sqlite_manager().await;
+        let (_, private_key) = create_user_with_key(&manager, "chongyi", UserRole::Author).await;
+        let password = serde_json::json!({ "password": PASSWORD });
+
+        // 请求签名可访问其他账号接口，但不能修改登录凭据
+        let response = send(
+            &manager,
+            signed_request(&private_key, Method::GET, "/security/profile", Value::Null),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = send(
+            &manager,
+            signed_request(
+                &private_key,
+                Method::POST,
+                "/security/keypair",
+                password.clone(),
+            ),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        for method in [Method::POST, Method::DELETE] {
+            let response = send(
+                &manager,
+                signed_request(&private_key, method, "/security/2fa", password.clone()),
+            )
+            .await;
+            assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        }
+
+        let token = login(&manager, "chongyi").await;
+        let wrong_password = serde_json::json!({ "password": "wrong" });
+        let response = send(
+            &manager,
+            request(
+                Method::POST,
+                "/security/keypair",
+                Some(&token),
+                Some(wrong_password),
+            ),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        // 密码错误后需等待才能再次确认密码
+        let rotate = || {
+            request(
+                Method::POST,
+                "/security/keypair",
+                Some(&token),
+                Some(password.clone()),
+            )
+        };
+        let response = send(&manager, rotate()).await;
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        manager
+            .unlock_login(AttemptKey::Username("chongyi".into()))
+            .await
+            .unwrap();
+        manager
+            .unlock_login(AttemptKey::Ip([127, 0, 0, 1].into()))
+            .await
+            .unwrap();
+
+        let response = send(&manager, rotate()).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let new_private_key = body_json(response).await["private_key"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        // 宽限期内的旧密钥同样不能登记新的公钥
+        let public_key = serde_json::json!({
+            "public_key": "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIAbDPd9/lthJz88b1oqB0ueRWizBp8Sm/SAiTZQqz/d0",
+            "password": PASSWORD,
+        });
+        for key in [&private_key, &new_private_key] {
+            let response = send(
+                &manager,
+                signed_request(key, Method::PUT, "/security/public-key", public_key.clone()),
+            )
+            .await;
+            assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        }
+        let response = send(
+            &manager,
+            request(
+                Method::PUT,
+                "/security/public-key",
+                Some(&token),
+                Some(public_key),
+            ),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    /// 解码不带填充的 Base32 文本
+    fn base32_decode(text: &str) -> Vec<u8> {
+        let mut output = Vec::new();
+        let mut buffer = 0u16;
+        let mut bits = 0;
+
+        for char in text.bytes() {
+            let value = match char {
+                b'A'..=b'Z' => char - b'A',
+                b'2'..=b'7' => char - b'2' + 26,
+                _ => panic!("invalid base32 character"),
+            };
+            buffer = (buffer << 5) | value as u16;
+            bits += 5;
+
+            if bits >= 8 {
+                bits -= 8;
+                output.push((buffer >> bits) as u8);
+            }
+        }
+
+        output
+    }
+
+    #[tokio::test]
+    async fn test_two_factor_routes() {
+        let manager = sqlite_manager().await;
+        let (_, private_key) = create_user_with_key(&manager, "chongyi", UserRole::Author).await;
+        let token = login(&manager, "chongyi").await;
+
+        let response = send(
+            &manager,
+            request(
+                Method::POST,
+                "/security/2fa",
+                Some(&token),
+                Some(serde_json::json!({ "password": PASSWORD })),
+            ),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let secret = base32_decode(body_json(response).await["secret"].as_str().unwrap());
+
+        // 确认开启时验证码错误同样计入失败记录
+        let confirm = |code: String| {
+            request(
+                Method::POST,
+                "/security/2fa/confirm",
+                Some(&token),
+                Some(serde_json::json!({ "code": code })),
+            )
+        };
+        let response = send(&manager, confirm("000000".into())).await;
+        assert_ne!(response.status(), StatusCode::OK);
+        let code = totp(&secret, time_step(Utc::now()));
+        let response = send(&manager, confirm(code.clone())).await;
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        manager
+            .unlock_login(AttemptKey::Username("chongyi".into()))
+            .await
+            .unwrap();
+        manager
+            .unlock_login(AttemptKey::Ip([127, 0, 0, 1].into()))
+            .await
+            .unwrap();
+
+        let response = send(&manager, confirm(code)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // 使用密钥登录同样需要完成两步验证
+        let (_, document) = der::Document::from_pem(&private_key).unwrap();
+        let key_pair = Ed25519KeyPair::from_pkcs8_maybe_unchecked(document.as_bytes()).unwrap();
+        let payload = serde_json::json!({
+            "fingerprint": to_hex(&sha256(key_pair.public_key().as_ref())),
+        });
+        let response = send(
+            &manager,
+            request(Method::POST, "/login/challenge", None, Some(payload)),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let challenge = body_json(response).await["challenge"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        let payload = serde_json::json!({
+            "challenge": challenge,
+            "signature": to_hex(key_pair.sign(challenge.as_bytes()).as_ref()),
+        });
+        let response = send(
+            &manager,
+            request(Method::POST, "/login/key", None, Some(payload)),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = body_json(response).await;
+        assert!(body["two_factor_token"].is_string());
+        assert!(body["access_token"].is_null());
+    }
+
+    #[tokio::test]
+    async fn test_access_token_routes() {
+        let manager = sqlite_manager().await;
+        let (user_id, private_key) =
+            create_user_with_key(&manager, "chongyi", UserRole::Author).await;
+        let content = manager
+            .create_content(
+                user_id,
+                NewContent {
+                    meta: ContentMeta {
+                        title: "owned".into(),
+                        ..Default::default()
+                    },
+                    entity: ContentEntity::Post(String::new()),
+                },
+                true,
+            )
+            .await
+            .unwrap();
+        let uri = format!("/security/content/{}", uuid_to_base62(content.meta.id));
+        let payload = |scope: &str, password: &str| serde_json::json!({ "name": "ci", "scopes": [scope], "password": password });
+
+        // 请求签名不能管理个人访问令牌
+        let response = send(
+            &manager,
+            signed_request(
+                &private_key,
+                Method::POST,
+                "/security/tokens",
+                payload("content:read", PASSWORD),
+            ),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        // 创建令牌需确认当前密码
+        let token = login(&manager, "chongyi").await;
+        let response = send(
+            &manager,
+            request(
+                Method::POST,
+                "/security/tokens",
+                Some(&token),
+                Some(payload("content:read", "wrong")),
+            ),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        manager
+            .unlock_login(AttemptKey::Username("chongyi".into()))
+            .await
+            .unwrap();
+        manager
+            .unlock_login(AttemptKey::Ip([127, 0, 0, 1].into()))
+            .await
+            .unwrap();
+
+        let mut access_tokens = vec![];
+        for scope in ["content:read", "content:write", "content:publish"] {
+            let response = send(
+                &manager,
+                request(
+                    Method::POST,
+                    "/security/tokens",
+                    Some(&token),
+                    Some(payload(scope, PASSWORD)),
+                ),
+            )
+            .await;
+            assert_eq!(response.status(), StatusCode::OK);
+            access_tokens.push(
+                body_json(response).await["token"]
+                    .as_str()
+                    .unwrap()
+                    .to_string(),
+            );
+        }
+        let (read, write, publish) = (
+            access_tokens[0].as_str(),
+            access_tokens[1].as_str(),
+            access_tokens[2].as_str(),
+        );
+
+        // 仅能访问在路由上声明了权限范围的接口
+        for uri in [
+            "/security/content",
+            "/security/content-service-config",
+            uri.as_str(),
+        ] {
+            let response = send(&manager, request(Method::GET, uri, Some(read), None)).await;
+            assert_eq!(response.status(), StatusCode::OK, "{uri}");
+            let response = send(&manager, request(Method::GET, uri, Some(write), None)).await;
+            assert_eq!(response.status(), StatusCode::FORBIDDEN, "{uri}");
+        }
+        for uri in ["/security/profile", "/security/tokens", "/security/audit"] {
+            let response = send(&manager, request(Method::GET, uri, Some(read), None)).await;
+            assert_eq!(response.status(), StatusCode::FORBIDDEN, "{uri}");
+        }
+
+        let update = serde_json::json!({ "title": "changed" });
+        let response = send(
+            &manager,
+            request(Method::PUT, &uri, Some(read), Some(update.clone())),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        let response = send(
+            &manager,
+            request(Method::PUT, &uri, Some(write), Some(update)),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // 批量操作按操作类型校验权限范围
+        let bulk = serde_json::json!({
+            "action": "publish",
+            "ids": [uuid_to_base62(content.meta.id)],
+        });
+        let response = send(
+            &manager,
+            request(
+                Method::POST,
+                "/security/content/bulk",
+                Some(read),
+                Some(bulk.clone()),
+            ),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        let response = send(
+            &manager,
+            request(
+                Method::POST,
+                "/security/content/bulk",
+                Some(publish),
+                Some(bulk),
+            ),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(body_json(response).await[0]["success"], true);
+        assert!(
+            manager
+                .find_content_by_id(content.meta.id)
+                .await
+                .unwrap()
+                .meta
+                .is_publish
+        );
+
+        // 修改密码后个人访问令牌随之失效
+        let response = send(
+            &manager,
+            request(
+                Method::PUT,
+                "/security/password",
+                Some(&token),
+                Some(serde_json::json!({
+                    "current_password": PASSWORD,
+                    "new_password": "Battery-Staple-43",
+                })),
+            ),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = send(
+            &manager,
+            request(Method::GET, "/security/content", Some(read), None),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    async fn is_display(manager: &Manager, id: Uuid) -> bool {
+        manager
+            .find_content_by_id(id)
+            .await
+            .unwrap()
+            .meta
+            .is_display
+    }
+
+    #[tokio::test]
+    async fn test_content_visibility_routes() {
+        let manager = sqlite_manager().await;
+        let owner_id = create_user(&manager, "owner", UserRole::Author).await;
+        let author_id = create_user(&manager, "author", UserRole::Author).await;
+        let mut ids = vec![];
+        for (user_id, title) in [(owner_id, "owned"), (author_id, "own")] {
+            let content = manager
+                .create_content(
+                    user_id,
+                    NewContent {
+                        meta: ContentMeta {
+                            title: title.into(),
+                            ..Default::default()
+                        },
+                        entity: ContentEntity::Post(String::new()),
+                    },
+                    true,
+                )
+                .await
+                .unwrap();
+            ids.push(content.meta.id);
+        }
+        let uri = format!("/security/content/{}/display", uuid_to_base62(ids[0]));
+
+        // 作者不能隐藏他人的内容
+        let token = login(&manager, "author").await;
+        let response = send(&manager, request(Method::DELETE, &uri, Some(&token), None)).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        assert!(is_display(&manager, ids[0]).await);
+
+        // 批量操作逐条返回结果，无权操作的内容不影响其他内容
+        let bulk = |ids: Vec<String>| serde_json::json!({ "action": "hide", "ids": ids });
+        let response = send(
+            &manager,
+            request(
+                Method::POST,
+                "/security/content/bulk",
+                Some(&token),
+                Some(bulk(vec![
+                    uuid_to_base62(ids[0]),
+                    uuid_to_base62(ids[1]),
+                    "invalid".into(),
+                ])),
+            ),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let results = body_json(response).await;
+        let success = results
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|result| result["success"].as_bool().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(success, vec![false, true, false]);
+        assert!(is_display(&manager, ids[0]).await);
+        assert!(!is_display(&manager, ids[1]).await);
+
+        let response = send(
+            &manager,
+            request(
+                Method::POST,
+                "/security/content/bulk",
+                Some(&token),
+                Some(bulk(vec![])),
+            ),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let token = login(&manager, "owner").await;
+        let response = send(&manager, request(Method::DELETE, &uri, Some(&token), None)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(!is_display(&manager, ids[0]).await);
+        let response = send(&manager, request(Method::POST, &uri, Some(&token), None)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(is_display(&manager, ids[0]).await);
+    }
 }
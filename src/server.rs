@@ -1,8 +1,11 @@
-use anyhow::Result;
+use std::{net::SocketAddr, time::Duration};
+
+use anyhow::{bail, Result};
 use axum::Extension;
+use inspirer_content::{manager::Manager, service::content::ContentService};
 use tracing_subscriber::EnvFilter;
 
-use crate::{cli::Cli, route::create_routes, manager::create_manager};
+use crate::{cli::Cli, jwk::TOKEN_KEYS, manager::create_manager, route::create_routes};
 
 pub fn run(args: Cli) -> Result<()> {
     #[cfg(target_family = "unix")]
@@ -37,15 +40,82 @@ pub fn run(args: Cli) -> Result<()> {
 }
 
 async fn start_server(args: Cli) -> Result<()> {
+    // 未配置或无法读取签名密钥时拒绝启动
+    lazy_static::initialize(&TOKEN_KEYS);
+    for key in TOKEN_KEYS.verification_keys() {
+        tracing::info!(
+            "Loaded token verification key, kid = {}, alg = {:?}",
+            key.kid,
+            key.algorithm
+        );
+    }
+    tracing::info!("Signing tokens with key {}", TOKEN_KEYS.signing_key().kid);
+
     let manager = create_manager().await?;
 
+    ensure_schema_up_to_date(&manager).await?;
+
+    tokio::spawn(run_scheduled_publisher(manager.clone()));
+
     axum::Server::bind(&args.listen.unwrap_or("0.0.0.0:8088".parse()?))
         .serve(
             create_routes()
                 .layer(Extension(manager))
-                .into_make_service(),
+                .into_make_service_with_connect_info::<SocketAddr>(),
         )
         .await?;
 
     Ok(())
 }
+
+/// 检查数据库结构版本，落后时拒绝启动或按配置（`AUTO_MIGRATE`）自动执行迁移
+async fn ensure_schema_up_to_date(manager: &Manager) -> Result<()> {
+    let pending = manager.pending_migrations().await?;
+
+    if pending.is_empty() {
+        return Ok(());
+    }
+
+    let auto_migrate = std::env::var("AUTO_MIGRATE")
+        .map(|s| s == "true" || s == "1")
+        .unwrap_or(false);
+
+    if !auto_migrate {
+        for status in pending.iter() {
+            tracing::error!(
+                "Pending migration: {} {}",
+                status.version,
+                status.description
+            );
+        }
+
+        bail!("数据库结构版本落后，请执行 `manage migrate up` 或设置 AUTO_MIGRATE=true");
+    }
+
+    tracing::info!("Running {} pending migrations", pending.len());
+    manager.migrate().await?;
+
+    Ok(())
+}
+
+/// 定时发布已到预定发布时间的内容，检查间隔由 `PUBLISH_INTERVAL`（秒）配置
+async fn run_scheduled_publisher(manager: Manager) {
+    let period = std::env::var("PUBLISH_INTERVAL")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .filter(|secs| *secs > 0)
+        .map(Duration::from_secs)
+        .unwrap_or(Duration::from_secs(60));
+
+    let mut interval = tokio::time::interval(period);
+
+    loop {
+        interval.tick().await;
+
+        match manager.publish_scheduled_contents().await {
+            Ok(0) => (),
+            Ok(count) => tracing::info!("Published {} scheduled contents", count),
+            Err(err) => tracing::error!("Publish scheduled contents error: {}", err),
+        }
+    }
+}
@@ -1,21 +1,92 @@
 use axum::extract::{FromRequest, RequestParts};
-use chrono::{Duration, Utc};
-use inspirer_content::util::uuid::Uuid;
-use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation, errors::ErrorKind as TokenErrorKind};
+use chrono::{DateTime, Duration, Utc};
+use inspirer_content::{
+    enumerate::{
+        audit::{AuditAction, AuditTargetType},
+        token::TokenScope,
+        user::UserRole,
+    },
+    model::{
+        audit::NewAuditLog,
+        session::SessionModel,
+        token::AccessTokenModel,
+        user::{ContentAction, UserModel},
+    },
+    util::uuid::{base62_to_uuid, uuid_to_base62, Uuid},
+};
+use jsonwebtoken::{errors::ErrorKind as TokenErrorKind, Validation};
 use lazy_static::lazy_static;
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
-use crate::error::{InspirerError, InspirerResult};
+use crate::{
+    error::{InspirerError, InspirerResult},
+    jwk::TOKEN_KEYS,
+};
 
-/// 默认过期时间为 24 小时
-const DEFAULT_TOKEN_EXPIRATION: i64 = 1440;
+/// 访问令牌默认过期时间为 15 分钟
+const DEFAULT_TOKEN_EXPIRATION: i64 = 15;
+/// 刷新令牌默认过期时间为 30 天
+const DEFAULT_REFRESH_TOKEN_EXPIRATION: i64 = 43200;
+/// 登录挑战默认有效期为 60 秒
+const DEFAULT_LOGIN_CHALLENGE_EXPIRATION: i64 = 60;
+/// 轮换密钥后旧公钥默认在 1 天内仍然有效
+const DEFAULT_KEY_ROTATION_GRACE_PERIOD: i64 = 1440;
+/// 两步验证令牌默认有效期为 5 分钟
+const DEFAULT_TWO_FACTOR_TOKEN_EXPIRATION: i64 = 300;
+/// 两步验证令牌的 `aud`，与访问令牌区分
+const TWO_FACTOR_AUDIENCE: &str = "2fa";
 
 lazy_static! {
-    static ref EXPIRATION: i64 = {
+    pub static ref EXPIRATION: i64 = {
         std::env::var("TOKEN_EXPIRATION")
             .map(|v| v.parse::<i64>().expect("TOKEN_EXPIRATION 参数格式化错误"))
             .unwrap_or(DEFAULT_TOKEN_EXPIRATION)
     };
+    static ref REFRESH_EXPIRATION: i64 = {
+        std::env::var("REFRESH_TOKEN_EXPIRATION")
+            .map(|v| {
+                v.parse::<i64>()
+                    .expect("REFRESH_TOKEN_EXPIRATION 参数格式化错误")
+            })
+            .unwrap_or(DEFAULT_REFRESH_TOKEN_EXPIRATION)
+    };
+    static ref LOGIN_CHALLENGE_EXPIRATION: i64 = {
+        std::env::var("LOGIN_CHALLENGE_EXPIRATION")
+            .map(|v| {
+                v.parse::<i64>()
+                    .expect("LOGIN_CHALLENGE_EXPIRATION 参数格式化错误")
+            })
+            .unwrap_or(DEFAULT_LOGIN_CHALLENGE_EXPIRATION)
+    };
+    static ref TWO_FACTOR_TOKEN_EXPIRATION: i64 = {
+        std::env::var("TWO_FACTOR_TOKEN_EXPIRATION")
+            .map(|v| {
+                v.parse::<i64>()
+                    .expect("TWO_FACTOR_TOKEN_EXPIRATION 参数格式化错误")
+            })
+            .unwrap_or(DEFAULT_TWO_FACTOR_TOKEN_EXPIRATION)
+    };
+    static ref KEY_ROTATION_GRACE_PERIOD: i64 = {
+        std::env::var("KEY_ROTATION_GRACE_PERIOD")
+            .map(|v| {
+                v.parse::<i64>()
+                    .expect("KEY_ROTATION_GRACE_PERIOD 参数格式化错误")
+            })
+            .unwrap_or(DEFAULT_KEY_ROTATION_GRACE_PERIOD)
+    };
+}
+
+pub fn key_rotation_grace_period() -> Duration {
+    Duration::minutes(*KEY_ROTATION_GRACE_PERIOD)
+}
+
+pub fn login_challenge_expiration() -> Duration {
+    Duration::seconds(*LOGIN_CHALLENGE_EXPIRATION)
+}
+
+/// 刷新令牌的过期时间，每次刷新后重新计算
+pub fn refresh_token_expires_at() -> DateTime<Utc> {
+    Utc::now() + Duration::minutes(*REFRESH_EXPIRATION)
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -23,6 +94,8 @@ pub struct Claims {
     pub sub: u128,
     pub exp: usize,
     pub iat: usize,
+    /// 访问令牌 ID，用于校验令牌是否已被吊销或轮换
+    pub jti: String,
 }
 
 impl Default for Claims {
@@ -34,76 +107,222 @@ impl Default for Claims {
             sub: 0,
             exp: exp.timestamp() as usize,
             iat: iat.timestamp() as usize,
+            jti: String::new(),
         }
     }
 }
 
-impl From<inspirer_content::model::user::UserModel> for Claims {
-    fn from(model: inspirer_content::model::user::UserModel) -> Self {
+impl From<&SessionModel> for Claims {
+    fn from(session: &SessionModel) -> Self {
         Claims {
-            sub: model.id.as_u128(),
+            sub: session.user_id.as_u128(),
+            jti: uuid_to_base62(session.access_token_id),
             ..Default::default()
         }
     }
 }
 
+fn encode_token<T: Serialize>(claims: &T) -> InspirerResult<String> {
+    TOKEN_KEYS.encode(claims).map_err(|err| {
+        tracing::error!("Create token error: {err}");
+        InspirerError::CreateTokenError
+    })
+}
+
+fn decode_token<T: DeserializeOwned>(token: &str, validation: Validation) -> InspirerResult<T> {
+    TOKEN_KEYS
+        .decode(token, validation)
+        .map_err(|err| match err.into_kind() {
+            TokenErrorKind::ExpiredSignature => InspirerError::Unauthorized,
+            _ => InspirerError::ParseTokenError,
+        })
+}
+
 impl Claims {
     pub fn to_token(&self) -> InspirerResult<String> {
-        let token = encode(
-            &Header::default(),
-            self,
-            &EncodingKey::from_secret(
-                std::env::var("SECRET")
-                    .as_ref()
-                    .map(|s| s.as_str())
-                    .unwrap_or("SECRET")
-                    .as_ref(),
-            ),
-        )
-        .map_err(|err| {
-            tracing::error!("Create token error: {err}");
-            InspirerError::CreateTokenError
-        })?;
-
-        Ok(token)
+        encode_token(self)
     }
 
     pub fn from_token(token: &str) -> InspirerResult<Self> {
-        let token = decode::<Self>(
-            token,
-            &DecodingKey::from_secret(
-                std::env::var("SECRET")
-                    .as_ref()
-                    .map(|s| s.as_str())
-                    .unwrap_or("SECRET")
-                    .as_ref(),
-            ),
-            &Validation::default(),
-        )
-        .map_err(|err| match err.into_kind() {
-            TokenErrorKind::ExpiredSignature => InspirerError::Unauthorized,
-            _ => InspirerError::ParseTokenError
-        })?;
+        decode_token(token, Validation::default())
+    }
 
-        Ok(token.claims)
+    pub fn user_id(&self) -> Uuid {
+        Uuid::from_u128(self.sub)
     }
 
-    pub fn to_session_info(&self) -> SessionInfo {
-        SessionInfo {
-            uuid: Uuid::from_u128(self.sub),
+    pub fn token_id(&self) -> InspirerResult<Uuid> {
+        base62_to_uuid(&self.jti).map_err(|_| InspirerError::ParseTokenError)
+    }
+}
+
+/// 已通过密码校验、等待两步验证的登录，不能作为访问令牌使用
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TwoFactorClaims {
+    pub sub: u128,
+    pub exp: usize,
+    pub iat: usize,
+    pub aud: String,
+}
+
+impl TwoFactorClaims {
+    pub fn new(user_id: Uuid) -> Self {
+        let iat = Utc::now();
+        let exp = iat + Duration::seconds(Self::expires_in());
+
+        TwoFactorClaims {
+            sub: user_id.as_u128(),
+            exp: exp.timestamp() as usize,
+            iat: iat.timestamp() as usize,
+            aud: TWO_FACTOR_AUDIENCE.into(),
         }
     }
+
+    /// 两步验证令牌有效期（秒）
+    pub fn expires_in() -> i64 {
+        *TWO_FACTOR_TOKEN_EXPIRATION
+    }
+
+    pub fn to_token(&self) -> InspirerResult<String> {
+        encode_token(self)
+    }
+
+    pub fn from_token(token: &str) -> InspirerResult<Self> {
+        let mut validation = Validation::default();
+        validation.set_audience(&[TWO_FACTOR_AUDIENCE]);
+        validation.set_required_spec_claims(&["exp", "aud"]);
+
+        decode_token(token, validation)
+    }
+
+    pub fn user_id(&self) -> Uuid {
+        Uuid::from_u128(self.sub)
+    }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub struct SessionInfo {
     uuid: Uuid,
+    role: UserRole,
+    session_id: Option<Uuid>,
+    access_token_id: Option<Uuid>,
+    scopes: Option<Vec<TokenScope>>,
+    /// 当前接口要求并已校验的权限范围，由路由中间件设置
+    route_scope: Option<TokenScope>,
+    /// 权限范围取决于请求内容，由接口自行校验
+    handler_scope: bool,
 }
 
 impl SessionInfo {
+    /// 使用请求签名鉴权时没有对应的登录会话
+    pub fn new(user: UserModel, session_id: Option<Uuid>) -> Self {
+        SessionInfo {
+            uuid: user.id,
+            role: user.role,
+            session_id,
+            access_token_id: None,
+            scopes: None,
+            route_scope: None,
+            handler_scope: false,
+        }
+    }
+
+    /// 使用个人访问令牌鉴权，权限为令牌权限范围与用户角色的交集
+    pub fn with_access_token(user: UserModel, access_token: &AccessTokenModel) -> Self {
+        SessionInfo {
+            uuid: user.id,
+            role: user.role,
+            session_id: None,
+            access_token_id: Some(access_token.id),
+            scopes: Some(access_token.scopes()),
+            route_scope: None,
+            handler_scope: false,
+        }
+    }
+
     pub fn uuid(&self) -> Uuid {
         self.uuid
     }
+
+    pub fn session_id(&self) -> Option<Uuid> {
+        self.session_id
+    }
+
+    pub fn is_access_token(&self) -> bool {
+        self.scopes.is_some()
+    }
+
+    /// 由当前用户执行的操作的审计日志，使用个人访问令牌时同时记录令牌
+    pub fn audit_log(&self, action: AuditAction) -> NewAuditLog {
+        let log = NewAuditLog::new(action).actor(self.uuid);
+
+        match self.access_token_id {
+            Some(access_token_id) => log.access_token(access_token_id),
+            None => log,
+        }
+    }
+
+    pub fn audit_user(&self, action: AuditAction) -> NewAuditLog {
+        self.audit_log(action)
+            .target(AuditTargetType::User, self.uuid)
+    }
+
+    /// 校验个人访问令牌是否拥有指定的权限范围，其他方式鉴权时不受限制
+    pub fn authorize_scope(&self, scope: TokenScope) -> InspirerResult<()> {
+        match &self.scopes {
+            Some(scopes) if !scopes.contains(&scope) => Err(InspirerError::Forbidden),
+            _ => Ok(()),
+        }
+    }
+
+    /// 记录当前接口要求的权限范围，未声明权限范围的接口拒绝个人访问令牌
+    pub fn authorize_route_scope(&mut self, scope: TokenScope) -> InspirerResult<()> {
+        self.authorize_scope(scope)?;
+        self.route_scope = Some(scope);
+
+        Ok(())
+    }
+
+    /// 声明当前接口在处理请求时按请求内容调用 [`SessionInfo::authorize_scope`] 校验权限范围
+    pub fn defer_route_scope(&mut self) {
+        self.handler_scope = true;
+    }
+
+    pub fn authorize_content(&self, action: ContentAction, owner_id: Uuid) -> InspirerResult<()> {
+        self.authorize_scope(TokenScope::required_for(action))?;
+
+        if self.role.can(action, owner_id == self.uuid) {
+            Ok(())
+        } else {
+            Err(InspirerError::Forbidden)
+        }
+    }
+
+    /// 列表查询时限定的内容所有者，无权查看他人内容时仅列出自己的内容
+    pub fn content_owner_scope(&self) -> Option<Uuid> {
+        (!self.role.can_manage_others_content()).then_some(self.uuid)
+    }
+
+    pub fn authorize_taxonomy(&self) -> InspirerResult<()> {
+        if self.role.can_manage_taxonomy() && !self.is_access_token() {
+            Ok(())
+        } else {
+            Err(InspirerError::Forbidden)
+        }
+    }
+
+    /// 创建或编辑内容时能否自动创建不存在的标签与分类，与管理分类与标签的权限一致
+    pub fn can_create_terms(&self) -> bool {
+        self.authorize_taxonomy().is_ok()
+    }
+
+    pub fn authorize_audit(&self) -> InspirerResult<()> {
+        if self.role.can_view_audit_logs() && !self.is_access_token() {
+            Ok(())
+        } else {
+            Err(InspirerError::Forbidden)
+        }
+    }
 }
 
 #[async_trait::async_trait]
@@ -114,9 +333,17 @@ where
     type Rejection = InspirerError;
 
     async fn from_request(req: &mut RequestParts<B>) -> Result<Self, Self::Rejection> {
-        req.extensions()
+        let session = req
+            .extensions()
             .get::<SessionInfo>()
             .cloned()
-            .ok_or(InspirerError::Unauthorized)
+            .ok_or(InspirerError::Unauthorized)?;
+
+        // 个人访问令牌仅能访问在路由上声明了权限范围的接口
+        if session.is_access_token() && session.route_scope.is_none() && !session.handler_scope {
+            return Err(InspirerError::Forbidden);
+        }
+
+        Ok(session)
     }
 }
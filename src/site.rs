@@ -0,0 +1,72 @@
+use inspirer_content::{model::content::ContentModel, util::uuid::uuid_to_base62};
+use lazy_static::lazy_static;
+use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS};
+
+/// 默认 Feed 条目数量
+const DEFAULT_FEED_SIZE: usize = 20;
+
+/// URL 路径片段中需要转义的字符
+const PATH_SEGMENT: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'/')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'`')
+    .add(b'{')
+    .add(b'}');
+
+lazy_static! {
+    pub static ref SITE: SiteConfig = SiteConfig::from_env();
+}
+
+/// 站点信息，用于生成 Feed 等对外公开的资源
+#[derive(Debug, Clone)]
+pub struct SiteConfig {
+    pub title: String,
+    pub description: String,
+    /// 站点地址，不包含末尾的 `/`
+    pub url: String,
+    /// Feed 条目数量
+    pub feed_size: usize,
+    /// Feed 中是否包含渲染后的全文
+    pub feed_full_content: bool,
+}
+
+impl SiteConfig {
+    fn from_env() -> Self {
+        SiteConfig {
+            title: std::env::var("SITE_TITLE").unwrap_or_else(|_| "Inspirer".into()),
+            description: std::env::var("SITE_DESCRIPTION").unwrap_or_default(),
+            url: std::env::var("SITE_URL")
+                .unwrap_or_else(|_| "http://localhost:8088".into())
+                .trim_end_matches('/')
+                .into(),
+            feed_size: std::env::var("FEED_SIZE")
+                .map(|v| v.parse::<usize>().expect("FEED_SIZE 参数格式化错误"))
+                .unwrap_or(DEFAULT_FEED_SIZE),
+            feed_full_content: std::env::var("FEED_FULL_CONTENT")
+                .map(|s| s == "true" || s == "1")
+                .unwrap_or(false),
+        }
+    }
+
+    /// 拼接站点内的绝对地址
+    pub fn url_for(&self, path: &str) -> String {
+        format!("{}/{}", self.url, path.trim_start_matches('/'))
+    }
+
+    /// 内容的访问地址，优先使用内容名称
+    pub fn content_url(&self, content: &ContentModel) -> String {
+        match content.content_name.as_deref() {
+            Some(name) if !name.is_empty() => self.url_for(&format!(
+                "content/{}",
+                utf8_percent_encode(name, PATH_SEGMENT)
+            )),
+            _ => self.url_for(&format!("content/{}", uuid_to_base62(content.id))),
+        }
+    }
+}